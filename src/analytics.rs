@@ -0,0 +1,405 @@
+//! Aggregated statistics over renders, e.g. for dashboards.
+//!
+//! [`RenderStats`] summarizes finished renders of a [`RenderList`] by
+//! resolution and render server. Since renders of a list do not contain
+//! error codes, failures are summarized through [`FailureStats`] from
+//! [`RenderDone`] and [`RenderFailed`] websocket events instead.
+
+use std::{collections::HashMap, time::Duration};
+
+use futures::{Stream, StreamExt};
+use time::OffsetDateTime;
+
+use crate::{
+    client::error::ErrorCode,
+    model::{
+        Render, RenderDone, RenderFailed, RenderList, RenderResolution, RenderStatus, ServerName,
+    },
+    ClientError,
+};
+
+/// Collects statistics of finished renders.
+///
+/// Renders that are not done yet, failed, or were removed are skipped.
+///
+/// # Example
+/// ```no_run
+/// use rosu_render::{analytics::RenderStats, OrdrClient};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), rosu_render::ClientError> {
+/// let ordr = OrdrClient::new();
+/// let pages = ordr.render_list().ordr_username("my-bot").page_size(50).pages();
+/// let stats = RenderStats::from_pages(pages).await?;
+///
+/// for summary in stats.by_resolution() {
+///     println!("{}: {:?}", summary.resolution, summary.average_render_time);
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RenderStats {
+    resolutions: HashMap<RenderResolution, TimeTotals>,
+    servers: HashMap<ServerName, ServerTotals>,
+}
+
+impl RenderStats {
+    /// Create empty statistics.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect the statistics of all renders of the given pages.
+    ///
+    /// Stops at the first error.
+    pub async fn from_pages<S>(pages: S) -> Result<Self, ClientError>
+    where
+        S: Stream<Item = Result<RenderList, ClientError>>,
+    {
+        let mut pages = std::pin::pin!(pages);
+        let mut stats = Self::new();
+
+        while let Some(page) = pages.next().await {
+            stats.extend(&page?.renders);
+        }
+
+        Ok(stats)
+    }
+
+    /// Add a render to the statistics.
+    pub fn add(&mut self, render: &Render) {
+        if !RenderStatus::is_done(render) {
+            return;
+        }
+
+        let render_time = Duration::from_millis(u64::from(render.render_total_time));
+        let upload_time = Duration::from_millis(u64::from(render.upload_total_time));
+
+        let totals = self
+            .resolutions
+            .entry(render.options.resolution.clone())
+            .or_default();
+
+        totals.renders += 1;
+        totals.render_time += render_time;
+        totals.upload_time += upload_time;
+
+        let server = self
+            .servers
+            .entry(render.renderer.clone())
+            .or_insert_with(|| ServerTotals {
+                renders: 0,
+                busy_time: Duration::ZERO,
+                first_start: render.render_start_time,
+                last_end: render.upload_end_time,
+            });
+
+        server.renders += 1;
+        server.busy_time += render_time + upload_time;
+        server.first_start = server.first_start.min(render.render_start_time);
+        server.last_end = server.last_end.max(render.upload_end_time);
+    }
+
+    /// Average render and upload times per resolution, most rendered first.
+    #[must_use]
+    pub fn by_resolution(&self) -> Vec<RenderTimeSummary> {
+        let mut summaries: Vec<_> = self
+            .resolutions
+            .iter()
+            .map(|(resolution, totals)| RenderTimeSummary {
+                resolution: resolution.clone(),
+                renders: totals.renders,
+                average_render_time: totals.render_time / totals.renders,
+                average_upload_time: totals.upload_time / totals.renders,
+            })
+            .collect();
+
+        summaries.sort_unstable_by(|a, b| {
+            b.renders
+                .cmp(&a.renders)
+                .then_with(|| a.resolution.as_str().cmp(b.resolution.as_str()))
+        });
+
+        summaries
+    }
+
+    /// Throughput of each render server, most renders first.
+    #[must_use]
+    pub fn by_server(&self) -> Vec<ServerThroughput> {
+        let mut throughputs: Vec<_> = self
+            .servers
+            .iter()
+            .map(|(renderer, totals)| ServerThroughput {
+                renderer: renderer.clone(),
+                renders: totals.renders,
+                busy_time: totals.busy_time,
+                period: (totals.last_end - totals.first_start).unsigned_abs(),
+            })
+            .collect();
+
+        throughputs.sort_unstable_by(|a, b| {
+            b.renders
+                .cmp(&a.renders)
+                .then_with(|| a.renderer.as_str().cmp(b.renderer.as_str()))
+        });
+
+        throughputs
+    }
+}
+
+impl<'r> Extend<&'r Render> for RenderStats {
+    fn extend<I: IntoIterator<Item = &'r Render>>(&mut self, renders: I) {
+        for render in renders {
+            self.add(render);
+        }
+    }
+}
+
+impl<'r> FromIterator<&'r Render> for RenderStats {
+    fn from_iter<I: IntoIterator<Item = &'r Render>>(renders: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(renders);
+
+        stats
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct TimeTotals {
+    renders: u32,
+    render_time: Duration,
+    upload_time: Duration,
+}
+
+#[derive(Clone, Debug)]
+struct ServerTotals {
+    renders: u32,
+    busy_time: Duration,
+    first_start: OffsetDateTime,
+    last_end: OffsetDateTime,
+}
+
+/// Render times of all finished renders of a resolution.
+///
+/// Created through [`RenderStats::by_resolution`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderTimeSummary {
+    pub resolution: RenderResolution,
+    /// The amount of finished renders.
+    pub renders: u32,
+    pub average_render_time: Duration,
+    pub average_upload_time: Duration,
+}
+
+/// Throughput of a render server.
+///
+/// Created through [`RenderStats::by_server`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerThroughput {
+    pub renderer: ServerName,
+    /// The amount of finished renders.
+    pub renders: u32,
+    /// The total time spent rendering and uploading.
+    pub busy_time: Duration,
+    /// The time between the start of the first and the upload of the last render.
+    pub period: Duration,
+}
+
+impl ServerThroughput {
+    /// Finished renders per hour throughout the [`period`](Self::period).
+    #[must_use]
+    pub fn renders_per_hour(&self) -> f64 {
+        let hours = self.period.as_secs_f64() / 3600.0;
+
+        if hours > 0.0 {
+            f64::from(self.renders) / hours
+        } else {
+            0.0
+        }
+    }
+
+    /// The share of the [`period`](Self::period) that was spent rendering
+    /// and uploading, between 0 and 1.
+    #[must_use]
+    pub fn utilization(&self) -> f64 {
+        if self.period.is_zero() {
+            return 0.0;
+        }
+
+        (self.busy_time.as_secs_f64() / self.period.as_secs_f64()).min(1.0)
+    }
+}
+
+/// Collects how many renders succeeded and why others failed.
+///
+/// Record the outcomes as they arrive through the websocket, e.g. while
+/// tracking renders.
+///
+/// # Example
+/// ```
+/// use rosu_render::{analytics::FailureStats, client::error::ErrorCode, model::RenderFailed};
+///
+/// let mut stats = FailureStats::new();
+///
+/// stats.record_failed(&RenderFailed {
+///     render_id: 1,
+///     error_code: Some(ErrorCode::MirrorsUnavailable),
+///     error_message: Box::from("All beatmap mirrors are unavailable."),
+/// });
+///
+/// assert_eq!(stats.failed(), 1);
+/// assert_eq!(stats.by_code()[0].code, Some(ErrorCode::MirrorsUnavailable));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FailureStats {
+    succeeded: u32,
+    failed: u32,
+    codes: HashMap<Option<ErrorCode>, u32>,
+}
+
+impl FailureStats {
+    /// Create empty statistics.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a render that finished successfully.
+    pub fn record_done(&mut self, _done: &RenderDone) {
+        self.succeeded += 1;
+    }
+
+    /// Record a render that failed.
+    pub fn record_failed(&mut self, failed: &RenderFailed) {
+        self.failed += 1;
+        *self.codes.entry(failed.error_code).or_default() += 1;
+    }
+
+    /// The amount of recorded renders.
+    #[must_use]
+    pub const fn total(&self) -> u32 {
+        self.succeeded + self.failed
+    }
+
+    /// The amount of recorded renders that failed.
+    #[must_use]
+    pub const fn failed(&self) -> u32 {
+        self.failed
+    }
+
+    /// The share of recorded renders that failed, between 0 and 1.
+    #[must_use]
+    pub fn failure_rate(&self) -> f64 {
+        self.rate(self.failed)
+    }
+
+    /// The failures of each error code, most frequent first.
+    ///
+    /// Failures without error code are summarized under `None`.
+    #[must_use]
+    pub fn by_code(&self) -> Vec<FailureSummary> {
+        let mut summaries: Vec<_> = self
+            .codes
+            .iter()
+            .map(|(&code, &failures)| FailureSummary {
+                code,
+                failures,
+                rate: self.rate(failures),
+            })
+            .collect();
+
+        summaries.sort_unstable_by(|a, b| {
+            b.failures.cmp(&a.failures).then_with(|| {
+                a.code
+                    .map(ErrorCode::to_u8)
+                    .cmp(&b.code.map(ErrorCode::to_u8))
+            })
+        });
+
+        summaries
+    }
+
+    fn rate(&self, count: u32) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => f64::from(count) / f64::from(total),
+        }
+    }
+}
+
+/// Failures of an error code.
+///
+/// Created through [`FailureStats::by_code`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FailureSummary {
+    /// `None` for failures without error code.
+    pub code: Option<ErrorCode>,
+    pub failures: u32,
+    /// The share of all recorded renders that failed with this code, between 0 and 1.
+    pub rate: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        client::error::ErrorCode,
+        model::{RenderDone, RenderFailed, RenderList, RenderResolution},
+    };
+
+    use super::{FailureStats, RenderStats};
+
+    #[test]
+    fn render_stats() {
+        let json = include_str!("../tests/fixtures/http/render_list.json");
+        let list: RenderList = serde_json::from_str(json).unwrap();
+        let stats: RenderStats = list.renders.iter().collect();
+
+        let [summary] = stats.by_resolution().try_into().unwrap();
+        assert_eq!(summary.resolution, RenderResolution::HD720);
+        assert_eq!(summary.renders, 2);
+        assert_eq!(summary.average_render_time, Duration::from_secs(150));
+        assert_eq!(summary.average_upload_time, Duration::from_secs(15));
+
+        let [server] = stats.by_server().try_into().unwrap();
+        assert_eq!(server.renderer, "ordr server 1");
+        assert_eq!(server.busy_time, Duration::from_secs(330));
+        assert_eq!(server.period, Duration::from_secs(225));
+        assert!((server.renders_per_hour() - 32.0).abs() < f64::EPSILON);
+        assert!((server.utilization() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn failure_stats() {
+        let mut stats = FailureStats::new();
+        assert!(stats.failure_rate().abs() < f64::EPSILON);
+
+        let failed = |error_code| RenderFailed {
+            render_id: 1,
+            error_code,
+            error_message: Box::from(""),
+        };
+
+        stats.record_done(&RenderDone {
+            render_id: 1,
+            video_url: String::new().into(),
+        });
+        stats.record_failed(&failed(Some(ErrorCode::MirrorsUnavailable)));
+        stats.record_failed(&failed(Some(ErrorCode::MirrorsUnavailable)));
+        stats.record_failed(&failed(None));
+
+        assert_eq!(stats.total(), 4);
+        assert_eq!(stats.failed(), 3);
+        assert!((stats.failure_rate() - 0.75).abs() < f64::EPSILON);
+
+        let codes: Vec<_> = stats
+            .by_code()
+            .into_iter()
+            .map(|summary| (summary.code, summary.failures))
+            .collect();
+
+        assert_eq!(codes, [(Some(ErrorCode::MirrorsUnavailable), 2), (None, 1)]);
+    }
+}
@@ -1,17 +1,56 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use hyper::Client as HyperClient;
+use hyper::{client::connect::Connect, http::HeaderValue, Client as HyperClient};
 
-use crate::{client::connector, model::Verification};
+#[cfg(not(feature = "reqwest"))]
+use crate::client::connector;
+use crate::{
+    client::{
+        connector::TlsConfig,
+        error::{ConfigError, InvalidUserAgent},
+    },
+    dns::{DnsResolver, IpPreference, Resolve, SystemResolver},
+    model::{RenderOptions, RenderSkinOption, Verification},
+};
 
-use super::{ratelimiter::Ratelimiter, OrdrClient, OrdrRef};
+use super::{
+    circuit::CircuitBreaker,
+    error_rate::{ErrorRate, ErrorRateLimit},
+    failover::Failover,
+    ratelimiter::{RatelimitBucket, Ratelimiter},
+    HttpClient, Middleware, OrdrClient, OrdrRef, BASE_URL, ROSU_RENDER_USER_AGENT,
+};
 
 /// A builder for [`OrdrClient`].
 #[derive(Default)]
 #[must_use]
 pub struct OrdrClientBuilder {
+    base_url: Option<Box<str>>,
+    fallback_base_urls: Vec<Box<str>>,
+    failover: Option<(u32, Duration)>,
     verification: Option<Verification>,
+    default_render_options: Option<RenderOptions>,
+    compact_render_options: bool,
+    log_bodies: bool,
+    default_skin: Option<RenderSkinOption<'static>>,
     ratelimit: Option<RatelimitBuilder>,
+    disable_ratelimit: bool,
+    buckets: HashMap<RatelimitBucket, RatelimitBuilder>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    http2_only: bool,
+    tcp_keepalive: Option<Duration>,
+    timeout: Option<Duration>,
+    http_client: Option<HttpClient>,
+    resolver: Option<Arc<dyn Resolve>>,
+    ip_preference: IpPreference,
+    user_agent: Option<HeaderValue>,
+    circuit_breaker: Option<(u32, Duration)>,
+    error_rate_window: Option<Duration>,
+    error_rate_limit: Option<ErrorRateLimit>,
+    middlewares: Vec<Box<dyn Middleware>>,
+    #[cfg(feature = "cache")]
+    cache_ttls: HashMap<super::CachedRoute, Duration>,
 }
 
 impl OrdrClientBuilder {
@@ -20,11 +59,36 @@ impl OrdrClientBuilder {
         Self::default()
     }
 
-    //// Build an [`OrdrClient`].
+    /// Build an [`OrdrClient`].
+    ///
+    /// Convenience wrapper around [`try_build`](OrdrClientBuilder::try_build).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `reqwest` feature is enabled and reqwest fails to initialize its TLS
+    /// backend.
     #[must_use]
     pub fn build(self) -> OrdrClient {
-        let connector = connector::create();
-        let http = HyperClient::builder().build(connector);
+        self.try_build().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Build an [`OrdrClient`].
+    ///
+    /// Returns an error if the `reqwest` feature is enabled and reqwest fails
+    /// to initialize its TLS backend.
+    pub fn try_build(mut self) -> Result<OrdrClient, ConfigError> {
+        let resolver = self
+            .resolver
+            .take()
+            .unwrap_or_else(|| Arc::new(SystemResolver) as Arc<dyn Resolve>);
+        let resolver = DnsResolver::new(resolver, self.ip_preference);
+        let tls = TlsConfig::new();
+
+        let http = match self.http_client.take() {
+            Some(http) => http,
+            None => self.create_http_client(&resolver, &tls)?,
+        }
+        .timeout(self.timeout);
 
         let ratelimit = match (self.verification.as_ref(), self.ratelimit) {
             (None, None) => RatelimitBuilder::new(300_000, 1, 1), // One per 5 minutes
@@ -52,12 +116,97 @@ impl OrdrClientBuilder {
             (Some(_), Some(ratelimit)) => ratelimit,
         };
 
-        OrdrClient {
+        let base_url = self.base_url.unwrap_or_else(|| Box::from(BASE_URL));
+
+        let failover = (!self.fallback_base_urls.is_empty()).then(|| {
+            let (threshold, cooldown) = self
+                .failover
+                .unwrap_or((Failover::DEFAULT_THRESHOLD, Failover::DEFAULT_COOLDOWN));
+
+            let mut base_urls = Vec::with_capacity(self.fallback_base_urls.len() + 1);
+            base_urls.push(base_url.clone());
+            base_urls.extend(self.fallback_base_urls);
+
+            Arc::new(Failover::new(base_urls, threshold, cooldown))
+        });
+
+        Ok(OrdrClient {
             inner: Arc::new(OrdrRef {
                 http,
-                ratelimiter: Ratelimiter::new(&ratelimit),
+                resolver,
+                tls,
+                base_url,
+                failover,
+                ratelimiter: if self.disable_ratelimit {
+                    Ratelimiter::disabled()
+                } else {
+                    Ratelimiter::new(&ratelimit, &self.buckets)
+                },
                 verification: self.verification,
+                default_render_options: self.default_render_options,
+                compact_render_options: self.compact_render_options,
+                log_bodies: self.log_bodies,
+                default_skin: self.default_skin,
+                user_agent: self
+                    .user_agent
+                    .unwrap_or_else(|| HeaderValue::from_static(ROSU_RENDER_USER_AGENT)),
+                middlewares: (!self.middlewares.is_empty()).then(|| self.middlewares.into()),
+                stats: Arc::default(),
+                circuit: self.circuit_breaker.map(|(threshold, cooldown)| {
+                    Arc::new(CircuitBreaker::new(threshold, cooldown))
+                }),
+                error_rate: Arc::new(ErrorRate::new(
+                    self.error_rate_window.unwrap_or(ErrorRate::DEFAULT_WINDOW),
+                    self.error_rate_limit,
+                )),
+                #[cfg(feature = "cache")]
+                cache: (!self.cache_ttls.is_empty())
+                    .then(|| Arc::new(super::cache::ResponseCache::new(self.cache_ttls))),
             }),
+        })
+    }
+
+    /// Send requests to a different base URL, e.g. a mock server or proxy.
+    ///
+    /// A trailing `/` is added if missing.
+    ///
+    /// Defaults to `https://apis.issou.best/ordr/`.
+    pub fn base_url(self, base_url: impl Into<Box<str>>) -> Self {
+        Self {
+            base_url: Some(with_trailing_slash(base_url.into())),
+            ..self
+        }
+    }
+
+    /// Add a base URL, e.g. a mirror or proxy, that requests are sent to
+    /// while the previous one is failing.
+    ///
+    /// Requests switch to the next base URL on connection errors or once
+    /// the current one responded with `5xx` too often in a row. After a
+    /// cooldown, the primary [`base_url`](OrdrClientBuilder::base_url) is
+    /// tried again. See [`OrdrClientBuilder::failover`].
+    ///
+    /// Can be called multiple times to add further fallbacks. A trailing `/`
+    /// is added if missing.
+    pub fn fallback_base_url(mut self, base_url: impl Into<Box<str>>) -> Self {
+        self.fallback_base_urls
+            .push(with_trailing_slash(base_url.into()));
+
+        self
+    }
+
+    /// Switch to the next [fallback base URL] once the current one responded
+    /// with `5xx` `threshold` times in a row and try the primary base URL
+    /// again after `cooldown`.
+    ///
+    /// A `threshold` of zero is treated as one. Has no effect without fallback
+    /// base URL. Defaults to three responses and five minutes.
+    ///
+    /// [fallback base URL]: OrdrClientBuilder::fallback_base_url
+    pub fn failover(self, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failover: Some((threshold, cooldown)),
+            ..self
         }
     }
 
@@ -71,6 +220,344 @@ impl OrdrClientBuilder {
         }
     }
 
+    /// Use the given [`RenderOptions`] for every render that is commissioned
+    /// without [`CommissionRender::options`](crate::request::CommissionRender::options).
+    pub fn default_render_options(self, options: RenderOptions) -> Self {
+        Self {
+            default_render_options: Some(options),
+            ..self
+        }
+    }
+
+    /// Only send the fields of [`RenderOptions`] that differ from
+    /// [`RenderOptions::default`] when commissioning a render.
+    ///
+    /// o!rdr uses its own defaults for missing fields which currently match
+    /// the crate's defaults. This shrinks the request body from about 50 form
+    /// fields down to the changed ones.
+    ///
+    /// Disabled by default.
+    pub fn compact_render_options(self, compact: bool) -> Self {
+        Self {
+            compact_render_options: compact,
+            ..self
+        }
+    }
+
+    /// Log the fields of request bodies on debug level.
+    ///
+    /// Replay files are logged by their size and the verification key is
+    /// always redacted.
+    ///
+    /// Disabled by default.
+    pub fn log_bodies(self, log_bodies: bool) -> Self {
+        Self { log_bodies, ..self }
+    }
+
+    /// Use the given skin for every render that is commissioned without a skin.
+    ///
+    /// Defaults to [`RenderSkinOption::default`].
+    pub fn default_skin(self, skin: impl Into<RenderSkinOption<'static>>) -> Self {
+        Self {
+            default_skin: Some(skin.into()),
+            ..self
+        }
+    }
+
+    /// Cache successful responses of the given route for the duration of `ttl`.
+    ///
+    /// Responses are cached per path and query so e.g. different pages of the
+    /// skin list are cached separately. Cached responses do not count towards
+    /// the ratelimit.
+    ///
+    /// If the server provided an `ETag` or `Last-Modified` header, expired
+    /// entries are revalidated through a conditional request so that an
+    /// unchanged response does not need to be transferred again.
+    /// A `ttl` of zero thus always revalidates.
+    ///
+    /// Routes are not cached by default.
+    #[cfg(feature = "cache")]
+    pub fn cache_ttl(mut self, route: super::CachedRoute, ttl: Duration) -> Self {
+        self.cache_ttls.insert(route, ttl);
+
+        self
+    }
+
+    /// Reject requests for the duration of `cooldown` once the API responded
+    /// with `503 Service Unavailable` `threshold` times in a row.
+    ///
+    /// While the circuit is open, requests fail immediately with
+    /// [`ClientError::CircuitOpen`](crate::ClientError::CircuitOpen) without
+    /// consuming ratelimit allowances. After the cooldown, requests are sent
+    /// again and a single further 503 re-opens the circuit while any other
+    /// response closes it.
+    ///
+    /// A `threshold` of zero is treated as one. Disabled by default.
+    pub fn circuit_breaker(self, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            circuit_breaker: Some((threshold, cooldown)),
+            ..self
+        }
+    }
+
+    /// The rolling window in which render submissions are considered for
+    /// [`OrdrClient::error_rate`].
+    ///
+    /// Defaults to one hour.
+    pub fn error_rate_window(self, window: Duration) -> Self {
+        Self {
+            error_rate_window: Some(window),
+            ..self
+        }
+    }
+
+    /// Refuse to submit renders while the [`OrdrClient::error_rate`] is at
+    /// least `max_rate` (within `0.0..=1.0`) and at least `min_samples` renders
+    /// were submitted within the window.
+    ///
+    /// o!rdr bans clients whose submissions fail too often so this serves as a
+    /// safeguard against e.g. a bot forwarding broken replays. Refused
+    /// submissions fail with
+    /// [`ClientError::ErrorRateTooHigh`](crate::ClientError::ErrorRateTooHigh)
+    /// without being sent and do not count towards the error rate.
+    ///
+    /// Disabled by default.
+    pub fn error_rate_limit(self, max_rate: f64, min_samples: u32) -> Self {
+        Self {
+            error_rate_limit: Some(ErrorRateLimit {
+                max_rate,
+                min_samples,
+            }),
+            ..self
+        }
+    }
+
+    /// Add a [`Middleware`] whose hooks will be called for every request.
+    ///
+    /// Middlewares are called in the order they were added.
+    pub fn layer(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middlewares.push(Box::new(middleware));
+
+        self
+    }
+
+    /// Identify your application in the `User-Agent` header of each request.
+    ///
+    /// The identification is appended to the crate's user agent
+    /// e.g. `rosu-render (0.3.0) my-bot/1.2.0 (+https://github.com/me/my-bot)`.
+    ///
+    /// Returns an error if `name`, `version`, or `contact` contain characters
+    /// that are not allowed in a header value such as control characters.
+    ///
+    /// # Example
+    /// ```
+    /// use rosu_render::OrdrClient;
+    ///
+    /// let client = OrdrClient::builder()
+    ///     .user_agent("my-bot", "1.2.0", Some("https://github.com/me/my-bot"))?
+    ///     .build();
+    /// # Ok::<_, rosu_render::client::error::InvalidUserAgent>(())
+    /// ```
+    pub fn user_agent(
+        self,
+        name: &str,
+        version: &str,
+        contact: Option<&str>,
+    ) -> Result<Self, InvalidUserAgent> {
+        let mut user_agent = format!("{ROSU_RENDER_USER_AGENT} {name}/{version}");
+
+        if let Some(contact) = contact {
+            user_agent.push_str(" (+");
+            user_agent.push_str(contact);
+            user_agent.push(')');
+        }
+
+        let user_agent =
+            HeaderValue::try_from(user_agent).map_err(|source| InvalidUserAgent { source })?;
+
+        Ok(Self {
+            user_agent: Some(user_agent),
+            ..self
+        })
+    }
+
+    /// Set how long idle connections are kept alive in the connection pool.
+    ///
+    /// Defaults to 90 seconds.
+    pub fn pool_idle_timeout(self, timeout: Duration) -> Self {
+        Self {
+            pool_idle_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Set the maximum amount of idle connections that are kept alive in the connection pool.
+    ///
+    /// Defaults to no limit.
+    pub fn pool_max_idle_per_host(self, max_idle: usize) -> Self {
+        Self {
+            pool_max_idle_per_host: Some(max_idle),
+            ..self
+        }
+    }
+
+    /// Fail requests with [`ClientError::Timeout`] if their response did not
+    /// arrive within `timeout`.
+    ///
+    /// The timeout covers sending the request and receiving the response
+    /// headers but neither waiting for the ratelimiter nor receiving the
+    /// response body. Also applies to clients specified through
+    /// [`hyper_client`](OrdrClientBuilder::hyper_client).
+    ///
+    /// Defaults to no timeout.
+    ///
+    /// [`ClientError::Timeout`]: crate::ClientError::Timeout
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Only use HTTP/2 with prior knowledge instead of negotiating the protocol.
+    ///
+    /// Defaults to `false`.
+    pub fn http2_only(self, http2_only: bool) -> Self {
+        Self { http2_only, ..self }
+    }
+
+    #[cfg(feature = "reqwest")]
+    fn create_http_client(
+        &self,
+        resolver: &DnsResolver,
+        _: &TlsConfig,
+    ) -> Result<HttpClient, ConfigError> {
+        // Redirects are handled manually e.g. when validating replay urls
+        let mut builder = reqwest::Client::builder()
+            .dns_resolver(Arc::new(resolver.clone()))
+            .redirect(reqwest::redirect::Policy::none())
+            .tcp_keepalive(self.tcp_keepalive);
+
+        if self.http2_only {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+
+        let client = builder
+            .build()
+            .map_err(|source| ConfigError::HttpClient { source })?;
+
+        Ok(HttpClient::reqwest(client))
+    }
+
+    // Fallible to match the reqwest variant
+    #[cfg(not(feature = "reqwest"))]
+    #[allow(clippy::unnecessary_wraps)]
+    fn create_http_client(
+        &self,
+        resolver: &DnsResolver,
+        tls: &TlsConfig,
+    ) -> Result<HttpClient, ConfigError> {
+        let connector = connector::create(self.tcp_keepalive, resolver.clone(), tls);
+
+        let mut http_builder = HyperClient::builder();
+        http_builder.http2_only(self.http2_only);
+
+        if let Some(timeout) = self.pool_idle_timeout {
+            http_builder.pool_idle_timeout(timeout);
+        }
+
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            http_builder.pool_max_idle_per_host(max_idle);
+        }
+
+        Ok(HttpClient::new(http_builder.build(connector)))
+    }
+
+    /// Send requests through an existing hyper client instead of creating a new one.
+    ///
+    /// Clones of a hyper client share their connection pool so this allows reusing connections
+    /// that are already maintained elsewhere in the application. The client must be able to
+    /// handle `https` URLs.
+    ///
+    /// If specified, [`pool_idle_timeout`], [`pool_max_idle_per_host`], [`http2_only`],
+    /// [`tcp_keepalive`], [`resolver`], and [`ip_preference`] have no effect on HTTP requests.
+    ///
+    /// [`pool_idle_timeout`]: OrdrClientBuilder::pool_idle_timeout
+    /// [`pool_max_idle_per_host`]: OrdrClientBuilder::pool_max_idle_per_host
+    /// [`http2_only`]: OrdrClientBuilder::http2_only
+    /// [`tcp_keepalive`]: OrdrClientBuilder::tcp_keepalive
+    /// [`resolver`]: OrdrClientBuilder::resolver
+    /// [`ip_preference`]: OrdrClientBuilder::ip_preference
+    pub fn hyper_client<C>(self, client: HyperClient<C>) -> Self
+    where
+        C: Connect + Clone + Send + Sync + 'static,
+    {
+        Self {
+            http_client: Some(HttpClient::new(client)),
+            ..self
+        }
+    }
+
+    /// Send requests through an existing reqwest client instead of creating a new one.
+    ///
+    /// This allows configuring proxies, compression, or timeouts through reqwest. Redirects
+    /// should be disabled on the client since they are handled manually.
+    ///
+    /// If specified, [`pool_idle_timeout`], [`pool_max_idle_per_host`], [`http2_only`],
+    /// [`tcp_keepalive`], [`resolver`], and [`ip_preference`] have no effect on HTTP requests.
+    ///
+    /// [`pool_idle_timeout`]: OrdrClientBuilder::pool_idle_timeout
+    /// [`pool_max_idle_per_host`]: OrdrClientBuilder::pool_max_idle_per_host
+    /// [`http2_only`]: OrdrClientBuilder::http2_only
+    /// [`tcp_keepalive`]: OrdrClientBuilder::tcp_keepalive
+    /// [`resolver`]: OrdrClientBuilder::resolver
+    /// [`ip_preference`]: OrdrClientBuilder::ip_preference
+    #[cfg(feature = "reqwest")]
+    pub fn reqwest_client(self, client: reqwest::Client) -> Self {
+        Self {
+            http_client: Some(HttpClient::reqwest(client)),
+            ..self
+        }
+    }
+
+    /// Set the interval in which TCP keepalive probes are sent on idle connections.
+    ///
+    /// Defaults to no keepalive probes.
+    pub fn tcp_keepalive(self, interval: Duration) -> Self {
+        Self {
+            tcp_keepalive: Some(interval),
+            ..self
+        }
+    }
+
+    /// Specify the resolver for hostnames, e.g. one based on `hickory-dns`.
+    ///
+    /// Defaults to the [`SystemResolver`].
+    pub fn resolver(self, resolver: impl Resolve) -> Self {
+        Self {
+            resolver: Some(Arc::new(resolver)),
+            ..self
+        }
+    }
+
+    /// Specify which IP versions are used to connect.
+    ///
+    /// Defaults to [`IpPreference::Any`].
+    pub fn ip_preference(self, ip_preference: IpPreference) -> Self {
+        Self {
+            ip_preference,
+            ..self
+        }
+    }
+
     /// Specify a ratelimit that the client will uphold for the render endpoint.
     /// Other endpoints won't be affected, they have a pre-set ratelimit.
     ///
@@ -103,6 +590,65 @@ impl OrdrClientBuilder {
             ..self
         }
     }
+
+    /// Disable all internal ratelimiting, including the render ratelimit.
+    ///
+    /// **Warning**: Only use this if requests already pass through your own
+    /// ratelimiting, e.g. a proxy specified through [`base_url`]. o!rdr
+    /// enforces its ratelimits and exceeding them may get your application
+    /// or verification key banned.
+    ///
+    /// Takes precedence over [`render_ratelimit`] and [`ratelimit`].
+    ///
+    /// [`base_url`]: OrdrClientBuilder::base_url
+    /// [`render_ratelimit`]: OrdrClientBuilder::render_ratelimit
+    /// [`ratelimit`]: OrdrClientBuilder::ratelimit
+    pub fn disable_ratelimit(self) -> Self {
+        Self {
+            disable_ratelimit: true,
+            ..self
+        }
+    }
+
+    /// Specify the ratelimit of a [`RatelimitBucket`] for GET endpoints.
+    ///
+    /// Each bucket has its own budget so that e.g. heavy skin searching does not
+    /// delay requesting the render list. All GET requests additionally count
+    /// towards [`RatelimitBucket::Total`] which caps their combined rate.
+    ///
+    /// - `interval_ms`: How many milliseconds until the next refill
+    /// - `refill`: How many allowances are added per refill
+    /// - `max`: What's the maximum amount of available allowances
+    ///
+    /// The defaults are listed on [`RatelimitBucket`]. They add up such that
+    /// exhausting the skin, server, and general buckets still leaves part of
+    /// the total for the render list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval_ms` or `refill` are zero.
+    ///
+    /// # Example
+    /// ```
+    /// use rosu_render::{client::RatelimitBucket, OrdrClient};
+    ///
+    /// // Allow up to 5 skin requests per minute
+    /// let client = OrdrClient::builder()
+    ///     .ratelimit(RatelimitBucket::Skins, 12_000, 1, 5)
+    ///     .build();
+    /// ```
+    pub fn ratelimit(
+        mut self,
+        bucket: RatelimitBucket,
+        interval_ms: u64,
+        refill: u64,
+        max: u64,
+    ) -> Self {
+        self.buckets
+            .insert(bucket, RatelimitBuilder::new(interval_ms, refill, max));
+
+        self
+    }
 }
 
 pub(super) struct RatelimitBuilder {
@@ -120,3 +666,14 @@ impl RatelimitBuilder {
         }
     }
 }
+
+fn with_trailing_slash(base_url: Box<str>) -> Box<str> {
+    if base_url.ends_with('/') {
+        return base_url;
+    }
+
+    let mut base_url = String::from(base_url);
+    base_url.push('/');
+
+    base_url.into_boxed_str()
+}
@@ -0,0 +1,248 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use hyper::{
+    body::Bytes,
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    http::HeaderValue,
+    HeaderMap,
+};
+
+use crate::routing::Route;
+
+/// Endpoints whose responses can be cached.
+///
+/// Configure the time-to-live of each through
+/// [`OrdrClientBuilder::cache_ttl`](crate::client::OrdrClientBuilder::cache_ttl).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CachedRoute {
+    /// [`OrdrClient::server_list`](crate::OrdrClient::server_list)
+    ServerList,
+    /// [`OrdrClient::server_online_count`](crate::OrdrClient::server_online_count)
+    ServerOnlineCount,
+    /// [`OrdrClient::skin_list`](crate::OrdrClient::skin_list)
+    SkinList,
+    /// [`OrdrClient::custom_skin_info`](crate::OrdrClient::custom_skin_info)
+    SkinCustom,
+}
+
+impl CachedRoute {
+    fn from_route(route: Route) -> Option<Self> {
+        match route {
+            Route::ServerList => Some(Self::ServerList),
+            Route::ServerOnlineCount => Some(Self::ServerOnlineCount),
+            Route::SkinList => Some(Self::SkinList),
+            Route::SkinCustom => Some(Self::SkinCustom),
+            Route::Render | Route::RenderList => None,
+        }
+    }
+}
+
+/// Statistics of the response cache.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CacheStats {
+    /// Amount of requests that were answered by a fresh cache entry.
+    pub hits: u64,
+    /// Amount of requests for cached routes that had to be sent.
+    pub misses: u64,
+    /// Amount of sent requests for which the server responded with
+    /// `304 Not Modified` so the stale cache entry could be used.
+    pub revalidations: u64,
+}
+
+/// In-memory cache for successful responses of GET endpoints,
+/// keyed by the request's path including its query.
+///
+/// Once an entry expires, it is kept if the server provided an `ETag` or
+/// `Last-Modified` header so that the next request can be conditional.
+pub(crate) struct ResponseCache {
+    ttls: HashMap<CachedRoute, Duration>,
+    entries: Mutex<HashMap<Box<str>, CacheEntry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    revalidations: AtomicU64,
+}
+
+struct CacheEntry {
+    bytes: Bytes,
+    expires_at: Instant,
+    validators: Validators,
+}
+
+/// Validators of a response as provided by the server.
+#[derive(Clone, Default)]
+struct Validators {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+}
+
+impl Validators {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            etag: headers.get(ETAG).cloned(),
+            last_modified: headers.get(LAST_MODIFIED).cloned(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Result of a cache lookup.
+pub(crate) enum CacheLookup {
+    /// The route is not cached.
+    Uncached,
+    /// A fresh entry was found.
+    Fresh(Bytes),
+    /// No fresh entry was found; the request must be sent and
+    /// its response can be stored in the slot.
+    Miss(CacheSlot),
+}
+
+impl ResponseCache {
+    /// Amount of entries after which expired entries will be pruned on insert.
+    const PRUNE_THRESHOLD: usize = 256;
+
+    pub(crate) fn new(ttls: HashMap<CachedRoute, Duration>) -> Self {
+        Self {
+            ttls,
+            entries: Mutex::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            revalidations: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn lookup(self: &Arc<Self>, route: Route, path: &str) -> CacheLookup {
+        let Some(ttl) = self.ttl(route) else {
+            return CacheLookup::Uncached;
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let stale = match entries.get(path) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+
+                return CacheLookup::Fresh(entry.bytes.clone());
+            }
+            Some(entry) if !entry.validators.is_empty() => {
+                Some((entry.bytes.clone(), entry.validators.clone()))
+            }
+            Some(_) => {
+                entries.remove(path);
+
+                None
+            }
+            None => None,
+        };
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        CacheLookup::Miss(CacheSlot {
+            cache: Arc::clone(self),
+            key: Box::from(path),
+            ttl,
+            stale,
+            validators: Validators::default(),
+        })
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            revalidations: self.revalidations.load(Ordering::Relaxed),
+        }
+    }
+
+    fn ttl(&self, route: Route) -> Option<Duration> {
+        CachedRoute::from_route(route).and_then(|route| self.ttls.get(&route).copied())
+    }
+
+    fn insert(&self, key: Box<str>, entry: CacheEntry) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if entries.len() >= Self::PRUNE_THRESHOLD {
+            entries.retain(|_, entry| entry.expires_at > now || !entry.validators.is_empty());
+
+            if entries.len() >= Self::PRUNE_THRESHOLD {
+                entries.retain(|_, entry| entry.expires_at > now);
+            }
+        }
+
+        entries.insert(key, entry);
+    }
+}
+
+/// Handle to insert the response of a pending request into the cache.
+pub(crate) struct CacheSlot {
+    cache: Arc<ResponseCache>,
+    key: Box<str>,
+    ttl: Duration,
+    stale: Option<(Bytes, Validators)>,
+    validators: Validators,
+}
+
+impl CacheSlot {
+    /// Add conditional headers based on the validators of the stale entry.
+    pub(crate) fn add_conditional_headers(&self, headers: &mut HeaderMap) {
+        let Some((_, ref validators)) = self.stale else {
+            return;
+        };
+
+        if let Some(ref etag) = validators.etag {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+
+        if let Some(ref last_modified) = validators.last_modified {
+            headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
+    }
+
+    /// Keep track of the validators of the response.
+    pub(crate) fn set_validators(&mut self, headers: &HeaderMap) {
+        self.validators = Validators::from_headers(headers);
+    }
+
+    /// Store the bytes of a successful response.
+    pub(crate) fn insert(self, bytes: Bytes) {
+        let entry = CacheEntry {
+            bytes,
+            expires_at: Instant::now() + self.ttl,
+            validators: self.validators,
+        };
+
+        self.cache.insert(self.key, entry);
+    }
+
+    /// The server responded with `304 Not Modified` so the stale entry is
+    /// refreshed and its bytes are returned.
+    pub(crate) fn revalidate(self) -> Option<Bytes> {
+        let (bytes, validators) = self.stale?;
+        self.cache.revalidations.fetch_add(1, Ordering::Relaxed);
+
+        let entry = CacheEntry {
+            bytes: bytes.clone(),
+            expires_at: Instant::now() + self.ttl,
+            validators: if self.validators.is_empty() {
+                validators
+            } else {
+                self.validators
+            },
+        };
+
+        self.cache.insert(self.key, entry);
+
+        Some(bytes)
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use hyper::StatusCode;
+
+use crate::ClientError;
+
+/// Rejects requests for a cooldown period after the API repeatedly
+/// responded with `503 Service Unavailable`.
+pub(crate) struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<CircuitState>,
+}
+
+#[derive(Default)]
+struct CircuitState {
+    /// Amount of consecutive 503 responses.
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Fails with [`ClientError::CircuitOpen`] while the circuit is open.
+    pub(crate) fn check(&self) -> Result<(), ClientError> {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match state.open_until {
+            Some(retry_at) if retry_at > Instant::now() => {
+                Err(ClientError::CircuitOpen { retry_at })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Open the circuit after too many consecutive 503 responses or close it
+    /// on any other response.
+    ///
+    /// Once the cooldown is over, a single further 503 re-opens the circuit.
+    pub(crate) fn record(&self, status: StatusCode) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if status != StatusCode::SERVICE_UNAVAILABLE {
+            *state = CircuitState::default();
+
+            return;
+        }
+
+        state.failures = state.failures.saturating_add(1);
+
+        if state.failures >= self.threshold {
+            warn!(
+                failures = state.failures,
+                cooldown = ?self.cooldown,
+                "Opening circuit after repeated 503 responses"
+            );
+
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+// `Duration::from_mins` is only stable since Rust 1.91
+#[allow(clippy::duration_suboptimal_units)]
+mod tests {
+    use std::time::Duration;
+
+    use hyper::StatusCode;
+
+    use crate::ClientError;
+
+    use super::CircuitBreaker;
+
+    #[test]
+    fn open_after_threshold() {
+        let circuit = CircuitBreaker::new(2, Duration::from_secs(60));
+
+        circuit.record(StatusCode::SERVICE_UNAVAILABLE);
+        assert!(circuit.check().is_ok());
+
+        circuit.record(StatusCode::OK);
+        circuit.record(StatusCode::SERVICE_UNAVAILABLE);
+        assert!(circuit.check().is_ok());
+
+        circuit.record(StatusCode::SERVICE_UNAVAILABLE);
+        assert!(matches!(
+            circuit.check(),
+            Err(ClientError::CircuitOpen { .. })
+        ));
+    }
+
+    #[test]
+    fn close_after_cooldown() {
+        let circuit = CircuitBreaker::new(1, Duration::ZERO);
+
+        circuit.record(StatusCode::SERVICE_UNAVAILABLE);
+        assert!(circuit.check().is_ok());
+    }
+}
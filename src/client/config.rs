@@ -0,0 +1,420 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{dns::IpPreference, model::Verification};
+
+use super::{error::ConfigError, OrdrClientBuilder, RatelimitBucket};
+
+/// Settings of an [`OrdrClient`](super::OrdrClient) and the websocket
+/// connection that can be loaded from a config file.
+///
+/// All fields are optional and default to the same values as
+/// [`OrdrClientBuilder`].
+///
+/// Durations are specified in milliseconds.
+///
+/// # Example
+/// ```
+/// use rosu_render::client::{OrdrClientBuilder, OrdrConfig};
+///
+/// let config: OrdrConfig = serde_json::from_str(r#"{
+///     "verification": "devmode_success",
+///     "render_ratelimit": { "interval_ms": 5000, "refill": 1, "max": 2 },
+///     "tcp_keepalive_ms": 30000
+/// }"#)?;
+///
+/// let client = OrdrClientBuilder::from_config(config)?.build();
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct OrdrConfig {
+    /// See [`OrdrClientBuilder::base_url`].
+    pub base_url: Option<Box<str>>,
+    /// See [`OrdrClientBuilder::fallback_base_url`].
+    pub fallback_base_urls: Vec<Box<str>>,
+    /// See [`OrdrClientBuilder::failover`].
+    pub failover: Option<FailoverConfig>,
+    /// See [`OrdrClientBuilder::verification`].
+    pub verification: Option<Verification>,
+    /// See [`OrdrClientBuilder::compact_render_options`].
+    pub compact_render_options: bool,
+    /// See [`OrdrClientBuilder::log_bodies`].
+    pub log_bodies: bool,
+    /// See [`OrdrClientBuilder::render_ratelimit`].
+    pub render_ratelimit: Option<RatelimitConfig>,
+    /// See [`OrdrClientBuilder::ratelimit`].
+    pub ratelimits: HashMap<RatelimitBucket, RatelimitConfig>,
+    /// See [`OrdrClientBuilder::disable_ratelimit`].
+    pub disable_ratelimit: bool,
+    /// See [`OrdrClientBuilder::pool_idle_timeout`].
+    pub pool_idle_timeout_ms: Option<u64>,
+    /// See [`OrdrClientBuilder::pool_max_idle_per_host`].
+    pub pool_max_idle_per_host: Option<usize>,
+    /// See [`OrdrClientBuilder::http2_only`].
+    pub http2_only: bool,
+    /// See [`OrdrClientBuilder::tcp_keepalive`].
+    pub tcp_keepalive_ms: Option<u64>,
+    /// See [`OrdrClientBuilder::timeout`].
+    pub timeout_ms: Option<u64>,
+    /// See [`OrdrClientBuilder::ip_preference`]. Also applies to the websocket.
+    pub ip_preference: IpPreference,
+    /// See [`OrdrClientBuilder::user_agent`].
+    pub user_agent: Option<UserAgentConfig>,
+    /// See [`OrdrClientBuilder::circuit_breaker`].
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// See [`OrdrClientBuilder::error_rate_window`].
+    pub error_rate_window_ms: Option<u64>,
+    /// See [`OrdrClientBuilder::error_rate_limit`].
+    pub error_rate_limit: Option<ErrorRateLimitConfig>,
+    /// Backoff between reconnect attempts of the websocket.
+    ///
+    /// Only applies through `OrdrService::from_config`.
+    pub reconnect: ReconnectConfig,
+    /// Distribution of websocket events to the receivers of an `OrdrService`.
+    ///
+    /// Only applies through `OrdrService::from_config`.
+    #[cfg(any(
+        feature = "ws-native",
+        feature = "ws-rustls-native-roots",
+        feature = "ws-rustls-webpki-roots"
+    ))]
+    pub hub: crate::websocket::HubConfig,
+}
+
+/// Ratelimit of the render endpoint or a [`RatelimitBucket`].
+///
+/// See [`OrdrClientBuilder::render_ratelimit`] and [`OrdrClientBuilder::ratelimit`].
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct RatelimitConfig {
+    pub interval_ms: u64,
+    pub refill: u64,
+    pub max: u64,
+}
+
+impl RatelimitConfig {
+    fn is_valid(&self) -> bool {
+        self.interval_ms > 0 && self.refill > 0
+    }
+}
+
+/// Identification of your application.
+///
+/// See [`OrdrClientBuilder::user_agent`].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct UserAgentConfig {
+    pub name: Box<str>,
+    pub version: Box<str>,
+    #[serde(default)]
+    pub contact: Option<Box<str>>,
+}
+
+/// Rejection of requests after repeated 503 responses.
+///
+/// See [`OrdrClientBuilder::circuit_breaker`].
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    pub threshold: u32,
+    pub cooldown_ms: u64,
+}
+
+/// Switching between base URLs after repeated `5xx` responses.
+///
+/// See [`OrdrClientBuilder::failover`].
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct FailoverConfig {
+    pub threshold: u32,
+    pub cooldown_ms: u64,
+}
+
+/// Refusal of render submissions while too many of them failed.
+///
+/// See [`OrdrClientBuilder::error_rate_limit`].
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+pub struct ErrorRateLimitConfig {
+    pub max_rate: f64,
+    pub min_samples: u32,
+}
+
+/// Exponential backoff between reconnect attempts of the websocket.
+///
+/// The delay starts at `initial_backoff_ms` and doubles after each failed
+/// attempt up to `max_backoff_ms`. Unless `jitter` is disabled, each delay is
+/// randomly reduced by up to half.
+///
+/// Reconnecting is given up once `max_attempts` consecutive attempts were made
+/// or the connection was down for `max_downtime_ms`, whichever comes first.
+/// Both are unlimited by default.
+///
+/// Create it through [`ReconnectConfig::default`] and adjust its fields.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct ReconnectConfig {
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub jitter: bool,
+    pub max_attempts: Option<u32>,
+    pub max_downtime_ms: Option<u64>,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 10_000,
+            jitter: true,
+            max_attempts: None,
+            max_downtime_ms: None,
+        }
+    }
+}
+
+impl OrdrClientBuilder {
+    /// Create a builder based on the given [`OrdrConfig`].
+    ///
+    /// Further options can be set on the returned builder.
+    ///
+    /// Returns an error if a ratelimit's `interval_ms` or `refill` are zero
+    /// or if the user agent contains invalid characters.
+    ///
+    /// The builder does not handle the websocket so the fields
+    /// [`reconnect`](OrdrConfig::reconnect) and `hub` are ignored with a
+    /// warning unless they are left at their defaults. Use
+    /// `OrdrService::from_config` to apply them.
+    pub fn from_config(config: OrdrConfig) -> Result<Self, ConfigError> {
+        let OrdrConfig {
+            base_url,
+            fallback_base_urls,
+            failover,
+            verification,
+            compact_render_options,
+            log_bodies,
+            render_ratelimit,
+            ratelimits,
+            disable_ratelimit,
+            pool_idle_timeout_ms,
+            pool_max_idle_per_host,
+            http2_only,
+            tcp_keepalive_ms,
+            timeout_ms,
+            ip_preference,
+            user_agent,
+            circuit_breaker,
+            error_rate_window_ms,
+            error_rate_limit,
+            reconnect,
+            #[cfg(any(
+                feature = "ws-native",
+                feature = "ws-rustls-native-roots",
+                feature = "ws-rustls-webpki-roots"
+            ))]
+            hub,
+        } = config;
+
+        if reconnect != ReconnectConfig::default() {
+            warn!("The `reconnect` config only applies through `OrdrService::from_config`");
+        }
+
+        #[cfg(any(
+            feature = "ws-native",
+            feature = "ws-rustls-native-roots",
+            feature = "ws-rustls-webpki-roots"
+        ))]
+        if hub != crate::websocket::HubConfig::default() {
+            warn!("The `hub` config only applies through `OrdrService::from_config`");
+        }
+
+        let mut builder = Self::new()
+            .http2_only(http2_only)
+            .compact_render_options(compact_render_options)
+            .log_bodies(log_bodies)
+            .ip_preference(ip_preference);
+
+        if let Some(base_url) = base_url {
+            builder = builder.base_url(base_url);
+        }
+
+        for base_url in fallback_base_urls {
+            builder = builder.fallback_base_url(base_url);
+        }
+
+        if let Some(failover) = failover {
+            builder = builder.failover(
+                failover.threshold,
+                Duration::from_millis(failover.cooldown_ms),
+            );
+        }
+
+        if let Some(verification) = verification {
+            builder = builder.verification(verification);
+        }
+
+        builder = builder.ratelimits_from_config(render_ratelimit, ratelimits)?;
+
+        if disable_ratelimit {
+            builder = builder.disable_ratelimit();
+        }
+
+        if let Some(timeout) = pool_idle_timeout_ms {
+            builder = builder.pool_idle_timeout(Duration::from_millis(timeout));
+        }
+
+        if let Some(max_idle) = pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+
+        if let Some(interval) = tcp_keepalive_ms {
+            builder = builder.tcp_keepalive(Duration::from_millis(interval));
+        }
+
+        if let Some(timeout) = timeout_ms {
+            builder = builder.timeout(Duration::from_millis(timeout));
+        }
+
+        if let Some(user_agent) = user_agent {
+            builder = builder.user_agent(
+                &user_agent.name,
+                &user_agent.version,
+                user_agent.contact.as_deref(),
+            )?;
+        }
+
+        if let Some(circuit_breaker) = circuit_breaker {
+            builder = builder.circuit_breaker(
+                circuit_breaker.threshold,
+                Duration::from_millis(circuit_breaker.cooldown_ms),
+            );
+        }
+
+        if let Some(window) = error_rate_window_ms {
+            builder = builder.error_rate_window(Duration::from_millis(window));
+        }
+
+        if let Some(limit) = error_rate_limit {
+            builder = builder.error_rate_limit(limit.max_rate, limit.min_samples);
+        }
+
+        Ok(builder)
+    }
+
+    fn ratelimits_from_config(
+        mut self,
+        render_ratelimit: Option<RatelimitConfig>,
+        ratelimits: HashMap<RatelimitBucket, RatelimitConfig>,
+    ) -> Result<Self, ConfigError> {
+        if let Some(ratelimit) = render_ratelimit {
+            if !ratelimit.is_valid() {
+                return Err(ConfigError::RenderRatelimit);
+            }
+
+            self = self.render_ratelimit(ratelimit.interval_ms, ratelimit.refill, ratelimit.max);
+        }
+
+        for (bucket, ratelimit) in ratelimits {
+            if !ratelimit.is_valid() {
+                return Err(ConfigError::Ratelimit { bucket });
+            }
+
+            self = self.ratelimit(
+                bucket,
+                ratelimit.interval_ms,
+                ratelimit.refill,
+                ratelimit.max,
+            );
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        client::{error::ConfigError, OrdrClientBuilder, RatelimitBucket},
+        model::Verification,
+    };
+
+    use super::{OrdrConfig, RatelimitConfig, ReconnectConfig};
+
+    #[test]
+    fn deserialize_config() {
+        let config: OrdrConfig = serde_json::from_str(
+            r#"{
+                "verification": "my-key",
+                "render_ratelimit": { "interval_ms": 5000, "refill": 1, "max": 2 },
+                "ratelimits": { "skins": { "interval_ms": 20000, "refill": 1, "max": 3 } },
+                "reconnect": { "max_backoff_ms": 2000, "max_attempts": 5 }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(
+            matches!(config.verification, Some(Verification::Key(ref key)) if &**key == "my-key")
+        );
+        assert_eq!(
+            config.render_ratelimit,
+            Some(RatelimitConfig {
+                interval_ms: 5000,
+                refill: 1,
+                max: 2
+            })
+        );
+        assert_eq!(
+            config.ratelimits[&RatelimitBucket::Skins],
+            RatelimitConfig {
+                interval_ms: 20_000,
+                refill: 1,
+                max: 3
+            }
+        );
+        assert_eq!(
+            config.reconnect,
+            ReconnectConfig {
+                initial_backoff_ms: 100,
+                max_backoff_ms: 2000,
+                jitter: true,
+                max_attempts: Some(5),
+                max_downtime_ms: None,
+            }
+        );
+        assert!(config.base_url.is_none());
+        assert!(!config.http2_only);
+    }
+
+    #[test]
+    fn reject_invalid_config() {
+        let config: OrdrConfig = serde_json::from_str(
+            r#"{ "render_ratelimit": { "interval_ms": 0, "refill": 1, "max": 2 } }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            OrdrClientBuilder::from_config(config),
+            Err(ConfigError::RenderRatelimit)
+        ));
+
+        let config: OrdrConfig = serde_json::from_str(
+            r#"{ "ratelimits": { "skins": { "interval_ms": 20000, "refill": 0, "max": 3 } } }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            OrdrClientBuilder::from_config(config),
+            Err(ConfigError::Ratelimit {
+                bucket: RatelimitBucket::Skins
+            })
+        ));
+
+        let config: OrdrConfig =
+            serde_json::from_str(r#"{ "user_agent": { "name": "my\nbot", "version": "1.0.0" } }"#)
+                .unwrap();
+
+        assert!(matches!(
+            OrdrClientBuilder::from_config(config),
+            Err(ConfigError::UserAgent { .. })
+        ));
+    }
+}
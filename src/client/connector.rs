@@ -1,57 +1,121 @@
 //! HTTP connectors with different features.
 
+// With reqwest, connectors are at most used by the webhook and the vcr recorder
+#![cfg_attr(feature = "reqwest", allow(dead_code))]
+
+use std::time::Duration;
+
+use crate::dns::DnsResolver;
+
 /// HTTPS connector using `rustls` as a TLS backend.
-#[cfg(any(feature = "rustls-native-roots", feature = "rustls-webpki-roots"))]
+#[cfg(any(
+    feature = "http-rustls-native-roots",
+    feature = "http-rustls-webpki-roots"
+))]
 type HttpsConnector<T> = hyper_rustls::HttpsConnector<T>;
 /// HTTPS connector using `hyper-tls` as a TLS backend.
 #[cfg(all(
-    feature = "native",
-    not(any(feature = "rustls-native-roots", feature = "rustls-webpki-roots"))
+    feature = "http-native",
+    not(any(
+        feature = "http-rustls-native-roots",
+        feature = "http-rustls-webpki-roots"
+    ))
 ))]
 type HttpsConnector<T> = hyper_tls::HttpsConnector<T>;
 
 /// HTTP connector.
-type HttpConnector = hyper::client::HttpConnector;
+type HttpConnector = hyper::client::HttpConnector<DnsResolver>;
 
 /// Re-exported generic connector for use in the client.
 #[cfg(any(
-    feature = "native",
-    feature = "rustls-native-roots",
-    feature = "rustls-webpki-roots"
+    feature = "http-native",
+    feature = "http-rustls-native-roots",
+    feature = "http-rustls-webpki-roots"
 ))]
 pub type Connector = HttpsConnector<HttpConnector>;
 /// Re-exported generic connector for use in the client.
 #[cfg(not(any(
-    feature = "native",
-    feature = "rustls-native-roots",
-    feature = "rustls-webpki-roots"
+    feature = "http-native",
+    feature = "http-rustls-native-roots",
+    feature = "http-rustls-webpki-roots"
 )))]
 pub type Connector = HttpConnector;
 
+/// TLS config that is shared between the HTTP client and the websocket.
+#[derive(Clone)]
+pub(crate) struct TlsConfig {
+    #[cfg(any(
+        feature = "http-rustls-native-roots",
+        feature = "http-rustls-webpki-roots"
+    ))]
+    pub(crate) rustls: std::sync::Arc<rustls_tls::ClientConfig>,
+}
+
+impl TlsConfig {
+    /// Create the TLS config with the specified features.
+    pub(crate) fn new() -> Self {
+        #[cfg(any(
+            feature = "http-rustls-native-roots",
+            feature = "http-rustls-webpki-roots"
+        ))]
+        {
+            use hyper_rustls::ConfigBuilderExt;
+
+            let builder = rustls_tls::ClientConfig::builder().with_safe_defaults();
+
+            #[cfg(feature = "http-rustls-native-roots")]
+            let builder = builder.with_native_roots();
+            #[cfg(all(
+                feature = "http-rustls-webpki-roots",
+                not(feature = "http-rustls-native-roots")
+            ))]
+            let builder = builder.with_webpki_roots();
+
+            Self {
+                rustls: std::sync::Arc::new(builder.with_no_client_auth()),
+            }
+        }
+
+        #[cfg(not(any(
+            feature = "http-rustls-native-roots",
+            feature = "http-rustls-webpki-roots"
+        )))]
+        Self {}
+    }
+}
+
 /// Create a connector with the specified features.
-pub fn create() -> Connector {
-    let mut connector = hyper::client::HttpConnector::new();
+#[cfg_attr(
+    not(any(
+        feature = "http-rustls-native-roots",
+        feature = "http-rustls-webpki-roots"
+    )),
+    allow(unused_variables)
+)]
+pub fn create(
+    tcp_keepalive: Option<Duration>,
+    resolver: DnsResolver,
+    tls: &TlsConfig,
+) -> Connector {
+    let mut connector = HttpConnector::new_with_resolver(resolver);
 
     connector.enforce_http(false);
+    connector.set_keepalive(tcp_keepalive);
 
-    #[cfg(feature = "rustls-native-roots")]
-    let connector = hyper_rustls::HttpsConnectorBuilder::new()
-        .with_native_roots()
-        .https_or_http()
-        .enable_http1()
-        .enable_http2()
-        .wrap_connector(connector);
-    #[cfg(all(feature = "rustls-webpki-roots", not(feature = "rustls-native-roots")))]
+    #[cfg(any(
+        feature = "http-rustls-native-roots",
+        feature = "http-rustls-webpki-roots"
+    ))]
     let connector = hyper_rustls::HttpsConnectorBuilder::new()
-        .with_webpki_roots()
+        .with_tls_config((*tls.rustls).clone())
         .https_or_http()
         .enable_http1()
         .enable_http2()
         .wrap_connector(connector);
     #[cfg(all(
-        feature = "native",
-        not(feature = "rustls-native-roots"),
-        not(feature = "rustls-webpki-roots")
+        feature = "http-native",
+        not(feature = "http-rustls-native-roots"),
+        not(feature = "http-rustls-webpki-roots")
     ))]
     let connector = hyper_tls::HttpsConnector::new_with_connector(connector);
 
@@ -2,9 +2,10 @@ use std::{
     error::Error as StdError,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
     str::from_utf8 as str_from_utf8,
+    time::{Duration, Instant},
 };
 
-use hyper::{body::Bytes, Body, Error as HyperError, Response};
+use hyper::{body::Bytes, header::InvalidHeaderValue, Body, Error as HyperError, Response};
 use serde::{
     de::{Deserializer, Error as DeError, Unexpected, Visitor},
     Deserialize,
@@ -13,24 +14,77 @@ use serde_json::Error as JsonError;
 use serde_urlencoded::ser::Error as UrlError;
 use thiserror::Error as ThisError;
 
-use crate::model::SkinDeleted;
+use crate::{
+    client::RatelimitBucket,
+    model::{RenderFailed, RenderRequirements, SkinDeleted},
+    util::json,
+};
+
+#[cfg(feature = "i18n")]
+pub use super::i18n::Lang;
 
 #[derive(Debug, ThisError)]
 #[non_exhaustive]
 pub enum ClientError {
+    #[error("The beatmap is longer than {max:?} ({length:?})")]
+    BeatmapTooLong { length: Duration, max: Duration },
     #[error("Failed to build the request")]
     BuildingRequest {
         #[source]
         source: Box<dyn StdError + Send + Sync + 'static>,
     },
+    #[error("The request was cancelled before it was sent")]
+    Cancelled,
+    #[error("Requests are rejected after repeated 503 responses until the cooldown is over")]
+    CircuitOpen { retry_at: Instant },
     #[error("Failed to chunk the response")]
     ChunkingResponse {
         #[source]
         source: HyperError,
     },
-    #[error("Failed to deserialize response body: {body}")]
+    #[cfg(feature = "compression")]
+    #[error("Failed to decompress the response")]
+    Decompressing {
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to download {url}: status code {status_code}")]
+    Download { url: Box<str>, status_code: u16 },
+    #[error("Refusing to submit renders while the error rate is {error_rate:.2}")]
+    ErrorRateTooHigh { error_rate: f64 },
+    #[error("Download was incomplete: expected {expected} bytes but received {received}")]
+    IncompleteDownload { expected: u64, received: u64 },
+    #[error("Refusing to render the replay behind {url}")]
+    InvalidReplayUrl {
+        url: Box<str>,
+        #[source]
+        issue: ReplayUrlIssue,
+    },
+    #[error("`{name}` must be within {min}..={max} but was {value}")]
+    InvalidParameter {
+        name: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+    #[error("A middleware aborted the request")]
+    Middleware {
+        #[source]
+        source: Box<dyn StdError + Send + Sync + 'static>,
+    },
+    #[cfg(feature = "rosu-v2")]
+    #[error("Failed to request the osu! API")]
+    OsuApi {
+        #[source]
+        source: rosu_v2::error::OsuError,
+    },
+    #[error("Failed to deserialize response body{}: {body}", DisplayPath(path.as_deref()))]
     Parsing {
         body: StringOrBytes,
+        /// The path of the field that failed to deserialize, e.g. `renders[3].renderID`.
+        ///
+        /// Only available with the `serde_path_to_error` feature.
+        path: Option<Box<str>>,
         #[source]
         source: JsonError,
     },
@@ -39,6 +93,25 @@ pub enum ClientError {
         #[source]
         source: HyperError,
     },
+    #[cfg(feature = "reqwest")]
+    #[error("Sending or receiving through reqwest failed")]
+    Reqwest {
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("The render failed: {}", .event.error_message)]
+    RenderFailed { event: RenderFailed },
+    #[error("Failed to commission the render: {error}")]
+    RenderSubmission { error: RenderSubmissionError },
+    #[cfg(feature = "rosu-v2")]
+    #[error("The replay of score {score_id} is not available")]
+    ReplayUnavailable { score_id: u64 },
+    #[cfg(feature = "replay-meta")]
+    #[error("Failed to parse the replay metadata")]
+    ReplayMeta {
+        #[source]
+        source: crate::model::ReplayMetaError,
+    },
     #[error("Response error: status code {status_code}, {error}")]
     Response {
         body: Bytes,
@@ -54,20 +127,254 @@ pub enum ClientError {
     ServiceUnavailable { response: Response<Body> },
     #[error("Skin was not found (received a 404)")]
     SkinDeleted { error: SkinDeleted },
+    #[error("The star rating is greater than {max} ({star_rating})")]
+    StarRatingTooHigh { star_rating: f32, max: f32 },
+    #[error("No response was received within {timeout:?}")]
+    Timeout { timeout: Duration },
+    #[error("No render server can currently fulfill the requirements {requirements:?}")]
+    UnfulfillableRequirements { requirements: RenderRequirements },
+}
+
+/// Why a replay url was considered unusable before commissioning a render.
+///
+/// See [`CommissionRender::validate_replay_url`](crate::request::CommissionRender::validate_replay_url).
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum ReplayUrlIssue {
+    #[error("The url is malformed")]
+    Malformed,
+    #[error("The url is unreachable")]
+    Unreachable {
+        #[source]
+        source: HyperError,
+    },
+    #[cfg(feature = "reqwest")]
+    #[error("The url is unreachable")]
+    UnreachableReqwest {
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("Received status code {status_code}")]
+    Status { status_code: u16 },
+    #[error("Received too many redirects")]
+    TooManyRedirects,
+    #[error("Content type `{content_type}` does not belong to a replay")]
+    ContentType { content_type: Box<str> },
+    #[error("The replay file is empty")]
+    Empty,
+    #[error("The file is too large to be a replay ({size} bytes)")]
+    TooLarge { size: u64 },
+    #[error("The url did not respond within {timeout:?}")]
+    Timeout { timeout: Duration },
+}
+
+/// The user agent specified through
+/// [`OrdrClientBuilder::user_agent`](crate::client::OrdrClientBuilder::user_agent)
+/// contains characters that are not allowed in a header value.
+#[derive(Debug, ThisError)]
+#[error("The user agent contains characters that are not allowed in a header value")]
+pub struct InvalidUserAgent {
+    #[source]
+    pub(crate) source: InvalidHeaderValue,
+}
+
+/// An [`OrdrConfig`](crate::client::OrdrConfig) contains values that cannot
+/// be applied or the client could not be built.
+///
+/// See [`OrdrClientBuilder::from_config`](crate::client::OrdrClientBuilder::from_config)
+/// and [`OrdrClientBuilder::try_build`](crate::client::OrdrClientBuilder::try_build).
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum ConfigError {
+    #[error("`interval_ms` and `refill` of the render ratelimit must be non-zero")]
+    RenderRatelimit,
+    #[error("`interval_ms` and `refill` of the {bucket:?} ratelimit must be non-zero")]
+    Ratelimit { bucket: RatelimitBucket },
+    #[error("Invalid user agent")]
+    UserAgent {
+        #[from]
+        source: InvalidUserAgent,
+    },
+    #[cfg(feature = "reqwest")]
+    #[error("Failed to initialize the reqwest client")]
+    HttpClient {
+        #[source]
+        source: reqwest::Error,
+    },
 }
 
 impl ClientError {
+    /// Whether retrying the failed operation later may succeed.
+    ///
+    /// This is a heuristic based on [`ErrorCode::is_retryable`] for rejected
+    /// or failed renders. Connection issues, timeouts, `503` responses, and
+    /// errors due to the client's own safeguards are considered retryable as
+    /// well.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RenderSubmission { error } => error.is_retryable(),
+            Self::RenderFailed { event } => event.is_retryable(),
+            Self::ChunkingResponse { .. }
+            | Self::CircuitOpen { .. }
+            | Self::ErrorRateTooHigh { .. }
+            | Self::IncompleteDownload { .. }
+            | Self::RequestError { .. }
+            | Self::ServiceUnavailable { .. }
+            | Self::Timeout { .. } => true,
+            #[cfg(feature = "reqwest")]
+            Self::Reqwest { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the render was rejected or failed for a reason that retrying
+    /// will not resolve.
+    ///
+    /// See [`ErrorCode::is_permanent`].
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            Self::RenderSubmission { error } => error.is_permanent(),
+            Self::RenderFailed { event } => event.is_permanent(),
+            Self::BeatmapTooLong { .. } | Self::StarRatingTooHigh { .. } => true,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn render_submission_error(bytes: Bytes, status_code: u16) -> Self {
+        match Self::response_error(bytes, status_code) {
+            Self::Response {
+                body,
+                error,
+                status_code,
+            } => Self::RenderSubmission {
+                error: RenderSubmissionError {
+                    body,
+                    error,
+                    status_code,
+                    dev_mode: false,
+                },
+            },
+            err => err,
+        }
+    }
+
+    /// Mark a [`ClientError::RenderSubmission`] as being caused in dev mode.
+    pub(crate) fn into_dev_mode(self) -> Self {
+        match self {
+            Self::RenderSubmission { mut error } => {
+                error.dev_mode = true;
+
+                Self::RenderSubmission { error }
+            }
+            err => err,
+        }
+    }
+
     pub(crate) fn response_error(bytes: Bytes, status_code: u16) -> Self {
-        match serde_json::from_slice(&bytes) {
+        match json::from_bytes(&bytes) {
             Ok(error) => Self::Response {
                 body: bytes,
                 error,
                 status_code,
             },
-            Err(source) => Self::Parsing {
-                body: bytes.into(),
-                source,
-            },
+            Err(err) => err,
+        }
+    }
+}
+
+/// The reason why o!rdr rejected a render submission.
+#[derive(Debug)]
+pub struct RenderSubmissionError {
+    pub(crate) body: Bytes,
+    pub(crate) error: ApiError,
+    pub(crate) status_code: u16,
+    pub(crate) dev_mode: bool,
+}
+
+impl RenderSubmissionError {
+    /// The error code as specified by o!rdr.
+    #[must_use]
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        self.error.code
+    }
+
+    /// The broad category of the error.
+    ///
+    /// See [`ApiError::category`].
+    #[must_use]
+    pub fn category(&self) -> ErrorCategory {
+        self.error.category()
+    }
+
+    /// The response message of the server.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.error.message
+    }
+
+    /// The reason of a ban (if provided by admins).
+    #[must_use]
+    pub fn reason(&self) -> Option<&str> {
+        self.error.reason.as_deref()
+    }
+
+    /// The status code of the response.
+    #[must_use]
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// The raw response body.
+    #[must_use]
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Whether commissioning the render again later may succeed.
+    ///
+    /// See [`ErrorCode::is_retryable`].
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.error.code.is_some_and(ErrorCode::is_retryable)
+    }
+
+    /// Whether commissioning the render again will fail the same way.
+    ///
+    /// See [`ErrorCode::is_permanent`].
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        self.error.code.is_some_and(ErrorCode::is_permanent)
+    }
+
+    /// Whether the render was commissioned with one of the dev mode
+    /// [`Verification`](crate::model::Verification)s, i.e. the failure was simulated.
+    #[must_use]
+    pub fn is_dev_mode(&self) -> bool {
+        self.dev_mode
+    }
+}
+
+impl Display for RenderSubmissionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "status code {}, {}", self.status_code, self.error)?;
+
+        if self.dev_mode {
+            f.write_str(" (dev mode)")?;
+        }
+
+        Ok(())
+    }
+}
+
+struct DisplayPath<'a>(Option<&'a str>);
+
+impl Display for DisplayPath<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.0 {
+            Some(path) => write!(f, " at `{path}`"),
+            None => Ok(()),
         }
     }
 }
@@ -93,6 +400,7 @@ impl From<Bytes> for StringOrBytes {
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct ApiError {
     /// The response of the server.
     pub message: Box<str>,
@@ -103,6 +411,17 @@ pub struct ApiError {
     pub code: Option<ErrorCode>,
 }
 
+impl ApiError {
+    /// The broad category of the error, e.g. to pick a user-facing message.
+    ///
+    /// Errors without error code are [`ErrorCategory::Unknown`].
+    #[must_use]
+    pub fn category(&self) -> ErrorCategory {
+        self.code
+            .map_or(ErrorCategory::Unknown, ErrorCode::category)
+    }
+}
+
 impl Display for ApiError {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         if let Some(ref code) = self.code {
@@ -239,6 +558,95 @@ impl ErrorCode {
             Self::Other(code) => code,
         }
     }
+
+    /// The broad category of the error code.
+    #[must_use]
+    pub fn category(self) -> ErrorCategory {
+        match self {
+            Self::PlayerBannedFromOrdr | Self::IpBannedFromOrdr | Self::UsernameBannedFromOrdr => {
+                ErrorCategory::Ban
+            }
+            Self::ReplayParsingError
+            | Self::ReplayFileCorrupted
+            | Self::InvalidGameMode
+            | Self::ReplayWithoutInputData
+            | Self::ReplayIsAutoplay
+            | Self::InvalidReplayUsername
+            | Self::ReplayFileCorrupted2
+            | Self::ReplayMissingInputData
+            | Self::ReplayIncompatibleMods => ErrorCategory::ReplayIssue,
+            Self::BeatmapNotFound
+            | Self::BeatmapAudioUnavailable
+            | Self::BeatmapTooLong
+            | Self::MapNotFound
+            | Self::InconsistentMapVersion
+            | Self::BeatmapHasNoName
+            | Self::StarRatingTooHigh
+            | Self::MapperIsBlacklisted
+            | Self::BeatmapsetIsBlacklisted => ErrorCategory::BeatmapIssue,
+            Self::ReplayDownloadError
+            | Self::MirrorsUnavailable
+            | Self::OsuApiConnection
+            | Self::UnknownRendererError
+            | Self::CannotDownloadMap
+            | Self::FailedFinalizing
+            | Self::ServerFailedPreparation
+            | Self::RendererIssue
+            | Self::CannotDownloadReplay => ErrorCategory::ServerIssue,
+            Self::EmergencyStop | Self::ReplayAlreadyInQueue | Self::ReplayErroredRecently => {
+                ErrorCategory::RatelimitOrPaused
+            }
+            Self::Other(_) => ErrorCategory::Unknown,
+        }
+    }
+
+    /// Whether the same render may succeed when commissioned again later,
+    /// e.g. after a mirror outage or a renderer issue.
+    ///
+    /// This is a heuristic; unknown error codes are neither retryable nor
+    /// permanent.
+    #[must_use]
+    pub fn is_retryable(self) -> bool {
+        match self {
+            // The render exists already so commissioning it again is pointless
+            Self::ReplayAlreadyInQueue => false,
+            _ => matches!(
+                self.category(),
+                ErrorCategory::ServerIssue | ErrorCategory::RatelimitOrPaused
+            ),
+        }
+    }
+
+    /// Whether the same render will fail the same way when commissioned
+    /// again, e.g. because the player is banned or the beatmap is unavailable.
+    ///
+    /// This is a heuristic; unknown error codes are neither retryable nor
+    /// permanent.
+    #[must_use]
+    pub fn is_permanent(self) -> bool {
+        matches!(
+            self.category(),
+            ErrorCategory::Ban | ErrorCategory::ReplayIssue | ErrorCategory::BeatmapIssue
+        )
+    }
+}
+
+/// Broad grouping of [`ErrorCode`]s.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// The player, IP, or username is banned from o!rdr.
+    Ban,
+    /// The replay is invalid or unsupported.
+    ReplayIssue,
+    /// The beatmap is unavailable or not allowed.
+    BeatmapIssue,
+    /// Something went wrong on o!rdr's side or with a renderer; retrying later may succeed.
+    ServerIssue,
+    /// Rendering is paused or the replay may not be submitted again yet.
+    RatelimitOrPaused,
+    /// The error code is unknown or missing.
+    Unknown,
 }
 
 impl<'de> Deserialize<'de> for ErrorCode {
@@ -254,7 +662,10 @@ impl<'de> Deserialize<'de> for ErrorCode {
 
             fn visit_u8<E: DeError>(self, v: u8) -> Result<Self::Value, E> {
                 let code = match v {
+                    1 => ErrorCode::EmergencyStop,
                     2 => ErrorCode::ReplayParsingError,
+                    3 => ErrorCode::ReplayDownloadError,
+                    4 => ErrorCode::MirrorsUnavailable,
                     5 => ErrorCode::ReplayFileCorrupted,
                     6 => ErrorCode::InvalidGameMode,
                     7 => ErrorCode::ReplayWithoutInputData,
@@ -265,12 +676,20 @@ impl<'de> Deserialize<'de> for ErrorCode {
                     12 => ErrorCode::InvalidReplayUsername,
                     13 => ErrorCode::BeatmapTooLong,
                     14 => ErrorCode::PlayerBannedFromOrdr,
+                    15 => ErrorCode::MapNotFound,
                     16 => ErrorCode::IpBannedFromOrdr,
                     17 => ErrorCode::UsernameBannedFromOrdr,
+                    18 => ErrorCode::UnknownRendererError,
+                    19 => ErrorCode::CannotDownloadMap,
+                    20 => ErrorCode::InconsistentMapVersion,
+                    21 => ErrorCode::ReplayFileCorrupted2,
+                    22 => ErrorCode::FailedFinalizing,
                     23 => ErrorCode::ServerFailedPreparation,
                     24 => ErrorCode::BeatmapHasNoName,
                     25 => ErrorCode::ReplayMissingInputData,
                     26 => ErrorCode::ReplayIncompatibleMods,
+                    27 => ErrorCode::RendererIssue,
+                    28 => ErrorCode::CannotDownloadReplay,
                     29 => ErrorCode::ReplayAlreadyInQueue,
                     30 => ErrorCode::StarRatingTooHigh,
                     31 => ErrorCode::MapperIsBlacklisted,
@@ -294,3 +713,111 @@ impl<'de> Deserialize<'de> for ErrorCode {
         d.deserialize_u8(ErrorCodeVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hyper::body::Bytes;
+
+    use crate::model::RenderFailed;
+
+    use super::{ClientError, ErrorCategory, ErrorCode};
+
+    #[test]
+    fn dev_mode_submission_error() {
+        let body = Bytes::from_static(br#"{"message":"Simulated failure","errorCode":14}"#);
+
+        let ClientError::RenderSubmission { error } =
+            ClientError::render_submission_error(body, 400).into_dev_mode()
+        else {
+            panic!("expected render submission error");
+        };
+
+        assert!(error.is_dev_mode());
+        assert_eq!(error.message(), "Simulated failure");
+        assert_eq!(error.error_code(), Some(ErrorCode::PlayerBannedFromOrdr));
+        assert_eq!(error.category(), ErrorCategory::Ban);
+    }
+
+    #[test]
+    fn error_code_categories() {
+        assert_eq!(
+            ErrorCode::ReplayIsAutoplay.category(),
+            ErrorCategory::ReplayIssue
+        );
+        assert_eq!(
+            ErrorCode::MapperIsBlacklisted.category(),
+            ErrorCategory::BeatmapIssue
+        );
+        assert_eq!(
+            ErrorCode::RendererIssue.category(),
+            ErrorCategory::ServerIssue
+        );
+        assert_eq!(
+            ErrorCode::ReplayErroredRecently.category(),
+            ErrorCategory::RatelimitOrPaused
+        );
+        assert_eq!(ErrorCode::Other(100).category(), ErrorCategory::Unknown);
+    }
+
+    #[test]
+    fn error_code_round_trip() {
+        for code in 0..=u8::MAX {
+            let error_code: ErrorCode = serde_json::from_str(&code.to_string()).unwrap();
+            assert_eq!(error_code.to_u8(), code);
+
+            if (1..=33).contains(&code) {
+                assert!(!matches!(error_code, ErrorCode::Other(_)), "{code}");
+            }
+        }
+    }
+
+    #[test]
+    fn deserialize_error_codes() {
+        let codes = [
+            (1, ErrorCode::EmergencyStop),
+            (3, ErrorCode::ReplayDownloadError),
+            (4, ErrorCode::MirrorsUnavailable),
+            (15, ErrorCode::MapNotFound),
+            (18, ErrorCode::UnknownRendererError),
+            (19, ErrorCode::CannotDownloadMap),
+            (20, ErrorCode::InconsistentMapVersion),
+            (21, ErrorCode::ReplayFileCorrupted2),
+            (22, ErrorCode::FailedFinalizing),
+            (27, ErrorCode::RendererIssue),
+            (28, ErrorCode::CannotDownloadReplay),
+        ];
+
+        for (code, expected) in codes {
+            let error_code: ErrorCode = serde_json::from_str(&code.to_string()).unwrap();
+            assert_eq!(error_code, expected, "{code}");
+        }
+    }
+
+    #[test]
+    fn retry_heuristics() {
+        assert!(ErrorCode::MirrorsUnavailable.is_retryable());
+        assert!(!ErrorCode::MirrorsUnavailable.is_permanent());
+        assert!(ErrorCode::PlayerBannedFromOrdr.is_permanent());
+        assert!(!ErrorCode::PlayerBannedFromOrdr.is_retryable());
+        assert!(!ErrorCode::ReplayAlreadyInQueue.is_retryable());
+        assert!(!ErrorCode::Other(100).is_retryable());
+        assert!(!ErrorCode::Other(100).is_permanent());
+
+        let failed = RenderFailed {
+            render_id: 1,
+            error_code: Some(ErrorCode::MirrorsUnavailable),
+            error_message: "All beatmap mirrors are unavailable".into(),
+        };
+
+        assert!(failed.is_retryable());
+
+        let err = failed.as_client_error();
+        assert!(err.is_retryable());
+        assert!(!err.is_permanent());
+
+        let body = Bytes::from_static(br#"{"message":"Banned","errorCode":16}"#);
+        let err = ClientError::render_submission_error(body, 400);
+        assert!(err.is_permanent());
+        assert!(!err.is_retryable());
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crate::ClientError;
+
+/// Keeps track of the outcomes of render submissions within a rolling
+/// window and optionally rejects further submissions if too many failed.
+pub(crate) struct ErrorRate {
+    window: Duration,
+    limit: Option<ErrorRateLimit>,
+    outcomes: Mutex<VecDeque<(Instant, bool)>>,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct ErrorRateLimit {
+    pub(crate) max_rate: f64,
+    pub(crate) min_samples: u32,
+}
+
+impl ErrorRate {
+    // `Duration::from_hours` is only stable since Rust 1.91
+    #[allow(clippy::duration_suboptimal_units)]
+    pub(crate) const DEFAULT_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+    pub(crate) fn new(window: Duration, limit: Option<ErrorRateLimit>) -> Self {
+        Self {
+            window,
+            limit,
+            outcomes: Mutex::default(),
+        }
+    }
+
+    /// Record whether a render submission succeeded.
+    pub(crate) fn record(&self, success: bool) {
+        let now = Instant::now();
+        let mut outcomes = self.outcomes.lock().unwrap_or_else(PoisonError::into_inner);
+        self.prune(&mut outcomes, now);
+        outcomes.push_back((now, success));
+    }
+
+    /// The share of failed submissions within the window and the amount of submissions.
+    fn current(&self) -> (f64, usize) {
+        let mut outcomes = self.outcomes.lock().unwrap_or_else(PoisonError::into_inner);
+        self.prune(&mut outcomes, Instant::now());
+
+        let failures = outcomes.iter().filter(|(_, success)| !success).count();
+
+        #[allow(clippy::cast_precision_loss)]
+        let rate = if outcomes.is_empty() {
+            0.0
+        } else {
+            failures as f64 / outcomes.len() as f64
+        };
+
+        (rate, outcomes.len())
+    }
+
+    pub(crate) fn rate(&self) -> f64 {
+        self.current().0
+    }
+
+    /// Fails with [`ClientError::ErrorRateTooHigh`] if a limit is set and reached.
+    pub(crate) fn check(&self) -> Result<(), ClientError> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+
+        let (error_rate, samples) = self.current();
+
+        if samples >= limit.min_samples as usize && error_rate >= limit.max_rate {
+            warn!(
+                error_rate,
+                samples, "Refusing render submission due to high error rate"
+            );
+
+            return Err(ClientError::ErrorRateTooHigh { error_rate });
+        }
+
+        Ok(())
+    }
+
+    fn prune(&self, outcomes: &mut VecDeque<(Instant, bool)>, now: Instant) {
+        while let Some((timestamp, _)) = outcomes.front() {
+            if now.duration_since(*timestamp) < self.window {
+                break;
+            }
+
+            outcomes.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+// `Duration::from_mins` is only stable since Rust 1.91
+#[allow(clippy::duration_suboptimal_units)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::ClientError;
+
+    use super::{ErrorRate, ErrorRateLimit};
+
+    #[test]
+    fn soft_block() {
+        let limit = ErrorRateLimit {
+            max_rate: 0.5,
+            min_samples: 3,
+        };
+
+        let error_rate = ErrorRate::new(Duration::from_secs(60), Some(limit));
+
+        error_rate.record(false);
+        error_rate.record(false);
+        assert!((error_rate.rate() - 1.0).abs() < f64::EPSILON);
+        assert!(error_rate.check().is_ok());
+
+        error_rate.record(true);
+        assert!(matches!(
+            error_rate.check(),
+            Err(ClientError::ErrorRateTooHigh { .. })
+        ));
+
+        error_rate.record(true);
+        assert!(error_rate.check().is_err());
+
+        error_rate.record(true);
+        assert!(error_rate.check().is_ok());
+    }
+
+    #[test]
+    fn forget_outside_window() {
+        let error_rate = ErrorRate::new(Duration::ZERO, None);
+
+        error_rate.record(false);
+        assert!(error_rate.rate().abs() < f64::EPSILON);
+        assert!(error_rate.check().is_ok());
+    }
+}
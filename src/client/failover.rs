@@ -0,0 +1,177 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use hyper::StatusCode;
+
+use super::http::HttpError;
+
+/// Switches to the next base url after connection errors or repeated
+/// `5xx` responses and returns to the primary base url after a cooldown.
+pub(crate) struct Failover {
+    /// The primary base url followed by its fallbacks.
+    base_urls: Box<[Box<str>]>,
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<FailoverState>,
+}
+
+#[derive(Default)]
+struct FailoverState {
+    /// Index of the base url that is currently used.
+    active: usize,
+    /// Amount of consecutive `5xx` responses of the active base url.
+    failures: u32,
+    switched_at: Option<Instant>,
+}
+
+impl Failover {
+    pub(crate) const DEFAULT_THRESHOLD: u32 = 3;
+    // `Duration::from_mins` is only stable since Rust 1.91
+    #[allow(clippy::duration_suboptimal_units)]
+    pub(crate) const DEFAULT_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+    pub(crate) fn new(base_urls: Vec<Box<str>>, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            base_urls: base_urls.into_boxed_slice(),
+            threshold: threshold.max(1),
+            cooldown,
+            state: Mutex::default(),
+        }
+    }
+
+    /// The base url that requests should currently be sent to.
+    ///
+    /// Once the cooldown is over, the primary base url is tried again.
+    pub(crate) fn base_url(self: &Arc<Self>) -> (&str, FailoverSlot) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if state.active != 0
+            && state
+                .switched_at
+                .is_some_and(|switched_at| switched_at.elapsed() >= self.cooldown)
+        {
+            debug!("Retrying the primary base url after failover cooldown");
+            *state = FailoverState::default();
+        }
+
+        let slot = FailoverSlot {
+            failover: Arc::clone(self),
+            idx: state.active,
+        };
+
+        (&self.base_urls[state.active], slot)
+    }
+
+    fn record(&self, idx: usize, failed: bool, immediate: bool) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // The outcome of a request that was sent before switching
+        if idx != state.active {
+            return;
+        }
+
+        if !failed {
+            state.failures = 0;
+
+            return;
+        }
+
+        state.failures = state.failures.saturating_add(1);
+
+        if !immediate && state.failures < self.threshold {
+            return;
+        }
+
+        let next = (state.active + 1) % self.base_urls.len();
+
+        warn!(
+            from = &*self.base_urls[state.active],
+            to = &*self.base_urls[next],
+            "Switching base url"
+        );
+
+        *state = FailoverState {
+            active: next,
+            failures: 0,
+            switched_at: Some(Instant::now()),
+        };
+    }
+}
+
+/// Records the outcome of a request in a [`Failover`].
+pub(crate) struct FailoverSlot {
+    failover: Arc<Failover>,
+    idx: usize,
+}
+
+impl FailoverSlot {
+    pub(crate) fn record(self, status: StatusCode) {
+        self.failover
+            .record(self.idx, status.is_server_error(), false);
+    }
+
+    pub(crate) fn record_error(self, err: &HttpError) {
+        if err.is_connect() {
+            self.failover.record(self.idx, true, true);
+        }
+    }
+}
+
+#[cfg(test)]
+// `Duration::from_mins` is only stable since Rust 1.91
+#[allow(clippy::duration_suboptimal_units)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use hyper::StatusCode;
+
+    use super::Failover;
+
+    fn failover(cooldown: Duration) -> Arc<Failover> {
+        let base_urls = vec![Box::from("primary/"), Box::from("fallback/")];
+
+        Arc::new(Failover::new(base_urls, 2, cooldown))
+    }
+
+    #[test]
+    fn switch_after_threshold() {
+        let failover = failover(Duration::from_secs(60));
+
+        let (base_url, slot) = failover.base_url();
+        assert_eq!(base_url, "primary/");
+        slot.record(StatusCode::BAD_GATEWAY);
+
+        failover.base_url().1.record(StatusCode::OK);
+        failover.base_url().1.record(StatusCode::BAD_GATEWAY);
+        assert_eq!(failover.base_url().0, "primary/");
+
+        failover.base_url().1.record(StatusCode::BAD_GATEWAY);
+        assert_eq!(failover.base_url().0, "fallback/");
+    }
+
+    #[test]
+    fn ignore_stale_outcomes() {
+        let failover = failover(Duration::from_secs(60));
+
+        let (_, first) = failover.base_url();
+        let (_, second) = failover.base_url();
+        first.record(StatusCode::BAD_GATEWAY);
+        second.record(StatusCode::BAD_GATEWAY);
+        assert_eq!(failover.base_url().0, "fallback/");
+
+        let (_, stale) = failover.base_url();
+        failover.record(0, true, true);
+        stale.record(StatusCode::BAD_GATEWAY);
+        assert_eq!(failover.base_url().0, "fallback/");
+    }
+
+    #[test]
+    fn return_to_primary_after_cooldown() {
+        let failover = failover(Duration::ZERO);
+
+        failover.record(0, true, true);
+        assert_eq!(failover.base_url().0, "primary/");
+    }
+}
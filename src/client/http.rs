@@ -0,0 +1,171 @@
+//! The HTTP client that requests are sent through.
+
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use futures::TryFutureExt;
+use hyper::{
+    client::connect::Connect, Body, Client as HyperClient, Error as HyperError, Request, Response,
+};
+
+use super::error::{ClientError, ReplayUrlIssue};
+
+/// Future that resolves to the response of a request.
+pub(crate) type HttpFuture =
+    Pin<Box<dyn Future<Output = Result<Response<Body>, HttpError>> + Send + 'static>>;
+
+/// A hyper or reqwest client with its connector type erased so that clients
+/// provided through the [`OrdrClientBuilder`](super::OrdrClientBuilder) can
+/// be used as well.
+#[derive(Clone)]
+pub(crate) struct HttpClient {
+    inner: Arc<dyn SendRequest>,
+    timeout: Option<Duration>,
+}
+
+impl HttpClient {
+    pub(crate) fn new<C>(client: HyperClient<C>) -> Self
+    where
+        C: Connect + Clone + Send + Sync + 'static,
+    {
+        Self {
+            inner: Arc::new(client),
+            timeout: None,
+        }
+    }
+
+    #[cfg(feature = "reqwest")]
+    pub(crate) fn reqwest(client: reqwest::Client) -> Self {
+        Self {
+            inner: Arc::new(client),
+            timeout: None,
+        }
+    }
+
+    /// Fail requests whose response headers did not arrive within `timeout`.
+    pub(crate) fn timeout(self, timeout: Option<Duration>) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub(crate) fn request(&self, req: Request<Body>) -> HttpFuture {
+        let fut = self.inner.request(req);
+
+        match self.timeout {
+            Some(timeout) => Box::pin(async move {
+                tokio::time::timeout(timeout, fut)
+                    .await
+                    .unwrap_or(Err(HttpError::Timeout(timeout)))
+            }),
+            None => fut,
+        }
+    }
+}
+
+trait SendRequest: Send + Sync {
+    fn request(&self, req: Request<Body>) -> HttpFuture;
+}
+
+impl<C> SendRequest for HyperClient<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    fn request(&self, req: Request<Body>) -> HttpFuture {
+        Box::pin(HyperClient::request(self, req).map_err(HttpError::Hyper))
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl SendRequest for reqwest::Client {
+    fn request(&self, req: Request<Body>) -> HttpFuture {
+        let client = self.clone();
+
+        Box::pin(async move {
+            // Request bodies are always in memory so buffering them is cheap
+            // and lets reqwest set the content length.
+            let (parts, body) = req.into_parts();
+            let body = hyper::body::to_bytes(body)
+                .await
+                .map_err(HttpError::Hyper)?;
+            let req = reqwest::Request::try_from(Request::from_parts(parts, body))
+                .map_err(HttpError::Reqwest)?;
+
+            let mut response = client.execute(req).await.map_err(HttpError::Reqwest)?;
+
+            let status = response.status();
+            let version = response.version();
+            let headers = std::mem::take(response.headers_mut());
+
+            let mut response = Response::new(Body::wrap_stream(response.bytes_stream()));
+            *response.status_mut() = status;
+            *response.version_mut() = version;
+            *response.headers_mut() = headers;
+
+            Ok(response)
+        })
+    }
+}
+
+/// Error of the underlying HTTP client.
+#[derive(Debug)]
+pub(crate) enum HttpError {
+    Hyper(HyperError),
+    #[cfg(feature = "reqwest")]
+    Reqwest(reqwest::Error),
+    Timeout(Duration),
+}
+
+impl HttpError {
+    /// Whether the connection was closed before the response was complete.
+    pub(crate) fn is_incomplete_message(&self) -> bool {
+        match self {
+            Self::Hyper(err) => err.is_incomplete_message(),
+            #[cfg(feature = "reqwest")]
+            Self::Reqwest(err) => {
+                // reqwest wraps the error of its underlying hyper client
+                let mut source = std::error::Error::source(err);
+
+                while let Some(err) = source {
+                    if let Some(err) = err.downcast_ref::<HyperError>() {
+                        return err.is_incomplete_message();
+                    }
+
+                    source = err.source();
+                }
+
+                false
+            }
+            Self::Timeout(_) => false,
+        }
+    }
+
+    /// Whether the error occurred while connecting.
+    pub(crate) fn is_connect(&self) -> bool {
+        match self {
+            Self::Hyper(err) => err.is_connect(),
+            #[cfg(feature = "reqwest")]
+            Self::Reqwest(err) => err.is_connect(),
+            Self::Timeout(_) => false,
+        }
+    }
+}
+
+impl From<HttpError> for ClientError {
+    fn from(err: HttpError) -> Self {
+        match err {
+            HttpError::Hyper(source) => Self::RequestError { source },
+            #[cfg(feature = "reqwest")]
+            HttpError::Reqwest(source) => Self::Reqwest { source },
+            HttpError::Timeout(timeout) => Self::Timeout { timeout },
+        }
+    }
+}
+
+impl From<HttpError> for ReplayUrlIssue {
+    fn from(err: HttpError) -> Self {
+        match err {
+            HttpError::Hyper(source) => Self::Unreachable { source },
+            #[cfg(feature = "reqwest")]
+            HttpError::Reqwest(source) => Self::UnreachableReqwest { source },
+            HttpError::Timeout(timeout) => Self::Timeout { timeout },
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::borrow::Cow;
+
+use super::error::ErrorCode;
+
+/// Languages of [`ErrorCode::localized`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Lang {
+    #[default]
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+impl Lang {
+    /// Parse a locale such as `fr`, `de-DE`, or `es_ES`, e.g. a Discord user's locale.
+    ///
+    /// Only the language part is considered.
+    #[must_use]
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let lang = locale.split(['-', '_']).next()?;
+
+        match lang.to_ascii_lowercase().as_str() {
+            "en" => Some(Self::English),
+            "fr" => Some(Self::French),
+            "de" => Some(Self::German),
+            "es" => Some(Self::Spanish),
+            _ => None,
+        }
+    }
+}
+
+impl ErrorCode {
+    /// A user-facing message describing the error code in the given language.
+    #[must_use]
+    pub fn localized(self, lang: Lang) -> Cow<'static, str> {
+        let msg = match lang {
+            Lang::English => None,
+            Lang::French => french(self),
+            Lang::German => german(self),
+            Lang::Spanish => spanish(self),
+        };
+
+        if let Some(msg) = msg {
+            return Cow::Borrowed(msg);
+        }
+
+        match (lang, self) {
+            (Lang::French, Self::Other(code)) => format!("Code d'erreur inconnu {code}").into(),
+            (Lang::German, Self::Other(code)) => format!("Unbekannter Fehlercode {code}").into(),
+            (Lang::Spanish, Self::Other(code)) => {
+                format!("Código de error desconocido {code}").into()
+            }
+            _ => self.to_string().into(),
+        }
+    }
+}
+
+fn french(code: ErrorCode) -> Option<&'static str> {
+    let msg = match code {
+        ErrorCode::EmergencyStop => "Arrêt d'urgence (déclenché manuellement)",
+        ErrorCode::ReplayParsingError => "Erreur lors de la lecture du replay (mauvais envoi)",
+        ErrorCode::ReplayDownloadError => {
+            "Erreur lors du téléchargement du replay par le serveur, possiblement à cause de caractères invalides"
+        }
+        ErrorCode::MirrorsUnavailable => "Tous les miroirs de beatmaps sont indisponibles",
+        ErrorCode::ReplayFileCorrupted | ErrorCode::ReplayFileCorrupted2 => {
+            "Le fichier du replay est corrompu"
+        }
+        ErrorCode::InvalidGameMode => "Mode de jeu osu! invalide (seul osu!standard est pris en charge)",
+        ErrorCode::ReplayWithoutInputData | ErrorCode::ReplayMissingInputData => {
+            "Le replay ne contient pas de données d'entrée"
+        }
+        ErrorCode::BeatmapNotFound => {
+            "La beatmap n'existe pas sur osu! (difficulté personnalisée ou map non soumise)"
+        }
+        ErrorCode::BeatmapAudioUnavailable => {
+            "L'audio de la map est indisponible (à cause de droits d'auteur)"
+        }
+        ErrorCode::OsuApiConnection => "Impossible de se connecter à l'API d'osu!",
+        ErrorCode::ReplayIsAutoplay => "Le replay utilise le mod autoplay",
+        ErrorCode::InvalidReplayUsername => {
+            "Le nom d'utilisateur du replay contient des caractères invalides"
+        }
+        ErrorCode::BeatmapTooLong => "La beatmap dure plus de 15 minutes",
+        ErrorCode::PlayerBannedFromOrdr => "Ce joueur est banni de o!rdr",
+        ErrorCode::MapNotFound => "La beatmap est introuvable sur tous les miroirs",
+        ErrorCode::IpBannedFromOrdr => "Cette IP est bannie de o!rdr",
+        ErrorCode::UsernameBannedFromOrdr => "Ce nom d'utilisateur est banni de o!rdr",
+        ErrorCode::UnknownRendererError => "Erreur inconnue du renderer",
+        ErrorCode::CannotDownloadMap => "Le renderer ne peut pas télécharger la map",
+        ErrorCode::InconsistentMapVersion => {
+            "La version de la beatmap sur le miroir ne correspond pas à celle du replay"
+        }
+        ErrorCode::FailedFinalizing => {
+            "Problème côté serveur lors de la finalisation de la vidéo"
+        }
+        ErrorCode::ServerFailedPreparation => {
+            "Problème côté serveur lors de la préparation du rendu"
+        }
+        ErrorCode::BeatmapHasNoName => "La beatmap n'a pas de nom",
+        ErrorCode::ReplayIncompatibleMods => "Le replay contient des mods incompatibles",
+        ErrorCode::RendererIssue => {
+            "Un problème est survenu avec le renderer, probablement une connexion internet instable"
+        }
+        ErrorCode::CannotDownloadReplay => "Le renderer ne peut pas télécharger le replay",
+        ErrorCode::ReplayAlreadyInQueue => "Le replay est déjà en cours de rendu ou en file d'attente",
+        ErrorCode::StarRatingTooHigh => "La difficulté dépasse 20 étoiles",
+        ErrorCode::MapperIsBlacklisted => "Le mappeur est sur liste noire",
+        ErrorCode::BeatmapsetIsBlacklisted => "Le beatmapset est sur liste noire",
+        ErrorCode::ReplayErroredRecently => {
+            "Le replay a déjà échoué il y a moins d'une heure"
+        }
+        ErrorCode::Other(_) => return None,
+    };
+
+    Some(msg)
+}
+
+fn german(code: ErrorCode) -> Option<&'static str> {
+    let msg = match code {
+        ErrorCode::EmergencyStop => "Notstopp (manuell ausgelöst)",
+        ErrorCode::ReplayParsingError => "Fehler beim Lesen des Replays (fehlerhafter Upload)",
+        ErrorCode::ReplayDownloadError => {
+            "Fehler beim Herunterladen des Replays durch den Server, möglicherweise wegen ungültiger Zeichen"
+        }
+        ErrorCode::MirrorsUnavailable => "Alle Beatmap-Mirrors sind nicht erreichbar",
+        ErrorCode::ReplayFileCorrupted | ErrorCode::ReplayFileCorrupted2 => {
+            "Die Replay-Datei ist beschädigt"
+        }
+        ErrorCode::InvalidGameMode => "Ungültiger osu!-Spielmodus (nur osu!standard wird unterstützt)",
+        ErrorCode::ReplayWithoutInputData | ErrorCode::ReplayMissingInputData => {
+            "Das Replay enthält keine Eingabedaten"
+        }
+        ErrorCode::BeatmapNotFound => {
+            "Die Beatmap existiert nicht auf osu! (eigene Schwierigkeit oder nicht eingereichte Map)"
+        }
+        ErrorCode::BeatmapAudioUnavailable => {
+            "Das Audio der Map ist nicht verfügbar (wegen Urheberrechtsansprüchen)"
+        }
+        ErrorCode::OsuApiConnection => "Keine Verbindung zur osu!-API möglich",
+        ErrorCode::ReplayIsAutoplay => "Das Replay verwendet den Autoplay-Mod",
+        ErrorCode::InvalidReplayUsername => "Der Benutzername des Replays enthält ungültige Zeichen",
+        ErrorCode::BeatmapTooLong => "Die Beatmap ist länger als 15 Minuten",
+        ErrorCode::PlayerBannedFromOrdr => "Dieser Spieler ist von o!rdr gesperrt",
+        ErrorCode::MapNotFound => "Die Beatmap wurde auf keinem Mirror gefunden",
+        ErrorCode::IpBannedFromOrdr => "Diese IP ist von o!rdr gesperrt",
+        ErrorCode::UsernameBannedFromOrdr => "Dieser Benutzername ist von o!rdr gesperrt",
+        ErrorCode::UnknownRendererError => "Unbekannter Fehler des Renderers",
+        ErrorCode::CannotDownloadMap => "Der Renderer kann die Map nicht herunterladen",
+        ErrorCode::InconsistentMapVersion => {
+            "Die Version der Beatmap auf dem Mirror stimmt nicht mit der des Replays überein"
+        }
+        ErrorCode::FailedFinalizing => "Serverseitiges Problem beim Fertigstellen des Videos",
+        ErrorCode::ServerFailedPreparation => "Serverseitiges Problem beim Vorbereiten des Renders",
+        ErrorCode::BeatmapHasNoName => "Die Beatmap hat keinen Namen",
+        ErrorCode::ReplayIncompatibleMods => "Das Replay enthält inkompatible Mods",
+        ErrorCode::RendererIssue => {
+            "Beim Renderer ist etwas schiefgelaufen, vermutlich wegen einer instabilen Internetverbindung"
+        }
+        ErrorCode::CannotDownloadReplay => "Der Renderer kann das Replay nicht herunterladen",
+        ErrorCode::ReplayAlreadyInQueue => "Das Replay wird bereits gerendert oder ist in der Warteschlange",
+        ErrorCode::StarRatingTooHigh => "Die Sternebewertung ist höher als 20",
+        ErrorCode::MapperIsBlacklisted => "Der Mapper steht auf der Sperrliste",
+        ErrorCode::BeatmapsetIsBlacklisted => "Das Beatmapset steht auf der Sperrliste",
+        ErrorCode::ReplayErroredRecently => {
+            "Das Replay ist bereits vor weniger als einer Stunde fehlgeschlagen"
+        }
+        ErrorCode::Other(_) => return None,
+    };
+
+    Some(msg)
+}
+
+fn spanish(code: ErrorCode) -> Option<&'static str> {
+    let msg = match code {
+        ErrorCode::EmergencyStop => "Parada de emergencia (activada manualmente)",
+        ErrorCode::ReplayParsingError => "Error al leer el replay (subida incorrecta)",
+        ErrorCode::ReplayDownloadError => {
+            "Error al descargar el replay en el servidor, posiblemente por caracteres inválidos"
+        }
+        ErrorCode::MirrorsUnavailable => "Todos los mirrors de beatmaps no están disponibles",
+        ErrorCode::ReplayFileCorrupted | ErrorCode::ReplayFileCorrupted2 => {
+            "El archivo del replay está dañado"
+        }
+        ErrorCode::InvalidGameMode => {
+            "Modo de juego de osu! inválido (solo se admite osu!standard)"
+        }
+        ErrorCode::ReplayWithoutInputData | ErrorCode::ReplayMissingInputData => {
+            "El replay no contiene datos de entrada"
+        }
+        ErrorCode::BeatmapNotFound => {
+            "El beatmap no existe en osu! (dificultad personalizada o mapa no publicado)"
+        }
+        ErrorCode::BeatmapAudioUnavailable => {
+            "El audio del mapa no está disponible (por derechos de autor)"
+        }
+        ErrorCode::OsuApiConnection => "No se puede conectar con la API de osu!",
+        ErrorCode::ReplayIsAutoplay => "El replay tiene el mod autoplay",
+        ErrorCode::InvalidReplayUsername => {
+            "El nombre de usuario del replay contiene caracteres inválidos"
+        }
+        ErrorCode::BeatmapTooLong => "El beatmap dura más de 15 minutos",
+        ErrorCode::PlayerBannedFromOrdr => "Este jugador está baneado de o!rdr",
+        ErrorCode::MapNotFound => "El beatmap no se encontró en ningún mirror",
+        ErrorCode::IpBannedFromOrdr => "Esta IP está baneada de o!rdr",
+        ErrorCode::UsernameBannedFromOrdr => "Este nombre de usuario está baneado de o!rdr",
+        ErrorCode::UnknownRendererError => "Error desconocido del renderer",
+        ErrorCode::CannotDownloadMap => "El renderer no puede descargar el mapa",
+        ErrorCode::InconsistentMapVersion => {
+            "La versión del beatmap en el mirror no coincide con la del replay"
+        }
+        ErrorCode::FailedFinalizing => "Problema del servidor al finalizar el video",
+        ErrorCode::ServerFailedPreparation => "Problema del servidor al preparar el render",
+        ErrorCode::BeatmapHasNoName => "El beatmap no tiene nombre",
+        ErrorCode::ReplayIncompatibleMods => "El replay tiene mods incompatibles",
+        ErrorCode::RendererIssue => {
+            "Algo salió mal con el renderer, probablemente por una conexión a internet inestable"
+        }
+        ErrorCode::CannotDownloadReplay => "El renderer no puede descargar el replay",
+        ErrorCode::ReplayAlreadyInQueue => "El replay ya se está renderizando o está en cola",
+        ErrorCode::StarRatingTooHigh => "La dificultad supera las 20 estrellas",
+        ErrorCode::MapperIsBlacklisted => "El mapper está en la lista negra",
+        ErrorCode::BeatmapsetIsBlacklisted => "El beatmapset está en la lista negra",
+        ErrorCode::ReplayErroredRecently => "El replay ya falló hace menos de una hora",
+        ErrorCode::Other(_) => return None,
+    };
+
+    Some(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorCode, Lang};
+
+    #[test]
+    fn localized_error_codes() {
+        let code = ErrorCode::BeatmapTooLong;
+
+        assert_eq!(code.localized(Lang::English), code.to_string());
+        assert_eq!(
+            code.localized(Lang::German),
+            "Die Beatmap ist länger als 15 Minuten"
+        );
+        assert_eq!(
+            ErrorCode::Other(99).localized(Lang::French),
+            "Code d'erreur inconnu 99"
+        );
+    }
+
+    #[test]
+    fn parse_locale() {
+        assert_eq!(Lang::from_locale("es-ES"), Some(Lang::Spanish));
+        assert_eq!(Lang::from_locale("FR"), Some(Lang::French));
+        assert_eq!(Lang::from_locale("en_US"), Some(Lang::English));
+        assert_eq!(Lang::from_locale("ja"), None);
+    }
+}
@@ -0,0 +1,51 @@
+use std::error::Error as StdError;
+
+use hyper::{Body, Request as HyperRequest, Response};
+
+/// Hooks that are called for every request of an [`OrdrClient`](crate::OrdrClient).
+///
+/// Add middleware through [`OrdrClientBuilder::layer`](crate::client::OrdrClientBuilder::layer).
+/// Multiple middlewares are called in the order they were added.
+///
+/// # Example
+/// ```
+/// use rosu_render::{client::Middleware, OrdrClient};
+/// use hyper::{Body, Request, Response};
+///
+/// struct Logger;
+///
+/// impl Middleware for Logger {
+///     fn before_request(
+///         &self,
+///         req: &mut Request<Body>,
+///     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+///         println!("{} {}", req.method(), req.uri());
+///
+///         Ok(())
+///     }
+///
+///     fn after_response(&self, response: &Response<Body>) {
+///         println!("Status: {}", response.status());
+///     }
+/// }
+///
+/// let client = OrdrClient::builder().layer(Logger).build();
+/// ```
+pub trait Middleware: Send + Sync {
+    /// Called when the request is built, before it is ratelimited and sent.
+    ///
+    /// Returning an error aborts the request with [`ClientError::Middleware`](crate::ClientError::Middleware).
+    fn before_request(
+        &self,
+        req: &mut HyperRequest<Body>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let _ = req;
+
+        Ok(())
+    }
+
+    /// Called once the response status and headers have been received.
+    fn after_response(&self, response: &Response<Body>) {
+        let _ = response;
+    }
+}
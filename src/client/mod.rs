@@ -1,35 +1,93 @@
 mod builder;
-mod connector;
+#[cfg(feature = "cache")]
+pub(crate) mod cache;
+mod circuit;
+mod config;
+pub(crate) mod connector;
+mod error_rate;
+mod failover;
+mod http;
+#[cfg(feature = "i18n")]
+mod i18n;
+mod middleware;
+#[cfg(feature = "rosu-v2")]
+mod osu_score;
+mod queue;
 mod ratelimiter;
+#[cfg(feature = "tower")]
+mod service;
+mod stats;
 
 pub mod error;
 
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use hyper::{
-    client::ResponseFuture,
-    header::{CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT},
+    body::Bytes,
+    header::{CONTENT_LENGTH, CONTENT_TYPE, LOCATION, USER_AGENT},
     http::HeaderValue,
-    Body, Client as HyperClient, Method, Request as HyperRequest,
+    Body, Method, Request as HyperRequest, Response,
 };
-
-pub use self::builder::OrdrClientBuilder;
-pub(crate) use self::ratelimiter::RatelimiterKind;
-use self::{connector::Connector, error::ClientError, ratelimiter::Ratelimiter};
+use url::Url;
+
+#[cfg(feature = "cache")]
+use self::cache::CacheLookup;
+#[cfg(feature = "cache")]
+pub use self::cache::{CacheStats, CachedRoute};
+#[cfg(feature = "tower")]
+pub use self::service::OrdrRequest;
+pub use self::{
+    builder::OrdrClientBuilder,
+    config::{
+        CircuitBreakerConfig, ErrorRateLimitConfig, FailoverConfig, OrdrConfig, RatelimitConfig,
+        ReconnectConfig, UserAgentConfig,
+    },
+    middleware::Middleware,
+    queue::{Priority, QueuedRender, RequestQueue},
+    ratelimiter::RatelimitBucket,
+    stats::RouteStats,
+};
+pub(crate) use self::{
+    circuit::CircuitBreaker,
+    error_rate::ErrorRate,
+    failover::FailoverSlot,
+    http::{HttpClient, HttpFuture},
+    ratelimiter::{Acquire, RatelimiterKind},
+    stats::StatsRecorder,
+};
+use self::{
+    connector::TlsConfig,
+    error::{ClientError, ReplayUrlIssue},
+    failover::Failover,
+    ratelimiter::Ratelimiter,
+    stats::RequestStats,
+};
+pub use crate::routing::Route;
 
 use crate::{
-    model::{RenderSkinOption, Verification},
+    dns::DnsResolver,
+    model::{
+        PreviewKind, Render, RenderOptions, RenderRequirements, RenderSkinOption, RenderStatus,
+        ServerOnlineCount, Skin, Verification,
+    },
+    multipart::Form,
     request::{
-        CommissionRender, GetRenderList, GetServerList, GetServerOnlineCount, GetSkinCustom,
-        GetSkinList, OrdrFuture, Request,
+        check_replay_head, chunk_response, CommissionRender, Download, GetRenderList,
+        GetServerList, GetServerOnlineCount, GetSkinCustom, GetSkinList, OrdrFuture, RawRequest,
+        Request, Requestable, Retry, ServerCountWatcher, SkinArchive, Username,
     },
-    util::multipart::Form,
 };
 
 const BASE_URL: &str = "https://apis.issou.best/ordr/";
-const ROSU_RENDER_USER_AGENT: &str = concat!("rosu-render (", env!("CARGO_PKG_VERSION"), ")");
+pub(crate) const ROSU_RENDER_USER_AGENT: &str =
+    concat!("rosu-render (", env!("CARGO_PKG_VERSION"), ")");
 
-type HttpClient = HyperClient<Connector>;
+pub(crate) type Middlewares = Arc<[Box<dyn Middleware>]>;
 
 /// Client to access the o!rdr API.
 ///
@@ -41,8 +99,25 @@ pub struct OrdrClient {
 
 struct OrdrRef {
     pub(super) http: HttpClient,
+    #[allow(unused)]
+    pub(super) resolver: DnsResolver,
+    #[allow(unused)]
+    pub(super) tls: TlsConfig,
+    pub(super) base_url: Box<str>,
+    pub(super) failover: Option<Arc<Failover>>,
     pub(super) ratelimiter: Ratelimiter,
     pub(super) verification: Option<Verification>,
+    pub(super) default_render_options: Option<RenderOptions>,
+    pub(super) compact_render_options: bool,
+    pub(super) log_bodies: bool,
+    pub(super) default_skin: Option<RenderSkinOption<'static>>,
+    pub(super) user_agent: HeaderValue,
+    pub(super) middlewares: Option<Middlewares>,
+    pub(super) stats: Arc<RequestStats>,
+    pub(super) circuit: Option<Arc<CircuitBreaker>>,
+    pub(super) error_rate: Arc<ErrorRate>,
+    #[cfg(feature = "cache")]
+    pub(super) cache: Option<Arc<self::cache::ResponseCache>>,
 }
 
 impl OrdrClient {
@@ -57,6 +132,18 @@ impl OrdrClient {
         OrdrClientBuilder::new()
     }
 
+    /// Check whether any render server can currently fulfill the given requirements.
+    ///
+    /// This requests the server list so it counts towards the servers ratelimit.
+    pub async fn check_capability(
+        &self,
+        requirements: RenderRequirements,
+    ) -> Result<bool, ClientError> {
+        self.server_list()
+            .await
+            .map(|servers| servers.can_fulfill(&requirements))
+    }
+
     /// Get info of a custom skin.
     ///
     /// You must provide the ID of the custom skin.
@@ -65,23 +152,27 @@ impl OrdrClient {
     }
 
     /// Send a render request to o!rdr via replay file.
-    pub const fn render_with_replay_file<'a>(
+    ///
+    /// If `skin` is `None`, the skin of [`OrdrClientBuilder::default_skin`] is used.
+    pub fn render_with_replay_file<'a>(
         &'a self,
         replay_file: &'a [u8],
-        username: &'a str,
-        skin: &'a RenderSkinOption<'a>,
+        username: impl Into<Username<'a>>,
+        skin: impl Into<Option<&'a RenderSkinOption<'a>>>,
     ) -> CommissionRender<'a> {
-        CommissionRender::with_file(self, replay_file, username, skin)
+        CommissionRender::with_file(self, replay_file, username.into(), skin.into())
     }
 
     /// Send a render request to o!rdr via replay url.
-    pub const fn render_with_replay_url<'a>(
+    ///
+    /// If `skin` is `None`, the skin of [`OrdrClientBuilder::default_skin`] is used.
+    pub fn render_with_replay_url<'a>(
         &'a self,
         url: &'a str,
-        username: &'a str,
-        skin: &'a RenderSkinOption<'a>,
+        username: impl Into<Username<'a>>,
+        skin: impl Into<Option<&'a RenderSkinOption<'a>>>,
     ) -> CommissionRender<'a> {
-        CommissionRender::with_url(self, url, username, skin)
+        CommissionRender::with_url(self, url, username.into(), skin.into())
     }
 
     /// Get a paginated list of all renders.
@@ -89,6 +180,61 @@ impl OrdrClient {
         GetRenderList::new(self)
     }
 
+    /// Get a single render, e.g. after receiving its
+    /// [`RenderDone`](crate::model::RenderDone) event.
+    ///
+    /// This requests the render list so it counts towards its ratelimit.
+    ///
+    /// Returns `None` if there is no render with the given id.
+    pub async fn fetch_render(&self, render_id: u32) -> Result<Option<Render>, ClientError> {
+        let mut req = self.render_list();
+        req.render_id(render_id).page_size(1);
+
+        let list = req.await?;
+
+        Ok(list
+            .renders
+            .into_iter()
+            .find(|render| render.id == render_id))
+    }
+
+    /// Get the [`RenderStatus`] of a render, e.g. to check whether its video
+    /// is available.
+    ///
+    /// This requests the render list so it counts towards its ratelimit.
+    pub async fn render_status(&self, render_id: u32) -> Result<RenderStatus, ClientError> {
+        self.fetch_render(render_id).await.map(RenderStatus::from)
+    }
+
+    /// Send a request to an endpoint that is not (yet) supported by this crate.
+    ///
+    /// The path is relative to the o!rdr API base url, e.g. `"servers/onlinecount"`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hyper::Method;
+    /// use rosu_render::OrdrClient;
+    ///
+    /// # async fn example() -> Result<(), rosu_render::ClientError> {
+    /// let client = OrdrClient::new();
+    ///
+    /// let (status, body) = client
+    ///     .raw(Method::GET, "renders")
+    ///     .query(&[("pageSize", 5)])?
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn raw(&self, method: Method, path: impl Into<String>) -> RawRequest<'_> {
+        RawRequest::new(self, method, path.into())
+    }
+
+    /// Send a raw request to the given [`Route`].
+    ///
+    /// Same as [`OrdrClient::raw`] with the route's method and path.
+    pub fn raw_route(&self, route: Route) -> RawRequest<'_> {
+        RawRequest::new(self, route.method(), route.path().to_owned())
+    }
+
     /// Get a list of available servers.
     pub const fn server_list(&self) -> GetServerList<'_> {
         GetServerList::new(self)
@@ -99,11 +245,176 @@ impl OrdrClient {
         GetServerOnlineCount::new(self)
     }
 
+    /// Poll the amount of online servers every `interval` and get notified
+    /// when it drops to zero or recovers, e.g. to pause accepting render
+    /// commands during outages.
+    ///
+    /// See [`GetServerOnlineCount::watch`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn watch_server_count(&self, interval: Duration) -> ServerCountWatcher<'_> {
+        self.server_online_count().watch(interval)
+    }
+
+    /// Measure the latency of the API by requesting the amount of online servers.
+    ///
+    /// The returned duration is the time until the response status and headers
+    /// were received. Waiting for the ratelimiter is not included and the
+    /// response cache is bypassed.
+    pub async fn ping(&self) -> Result<Duration, ClientError> {
+        let route = Route::ServerOnlineCount;
+        let (base_url, failover) = self.base_url();
+        let req = self.try_build_request(base_url, None, route.method(), route.path())?;
+
+        if let Some(ref circuit) = self.inner.circuit {
+            circuit.check()?;
+        }
+
+        if let Some(acquire) = self.inner.ratelimiter.acquire(route.ratelimiter()) {
+            acquire.await;
+        }
+
+        let mut stats = StatsRecorder::new(Arc::clone(&self.inner.stats), route);
+        stats.start();
+        let start = Instant::now();
+
+        let response = match self.inner.http.request(req).await {
+            Ok(response) => response,
+            Err(source) => {
+                stats.finish(None);
+
+                if let Some(failover) = failover {
+                    failover.record_error(&source);
+                }
+
+                return Err(source.into());
+            }
+        };
+
+        let latency = start.elapsed();
+        let status = response.status();
+        stats.finish(Some(status));
+
+        if let Some(failover) = failover {
+            failover.record(status);
+        }
+
+        if let Some(ref circuit) = self.inner.circuit {
+            circuit.record(status);
+        }
+
+        if let Some(ref middlewares) = self.inner.middlewares {
+            for middleware in middlewares.iter() {
+                middleware.after_response(&response);
+            }
+        }
+
+        // Consume the body so that the connection is returned to the pool
+        let (_, bytes) = chunk_response(response).await?;
+
+        if status.is_success() {
+            Ok(latency)
+        } else {
+            Err(ServerOnlineCount::response_error(status, bytes))
+        }
+    }
+
+    /// Establish a connection to the API ahead of time so that the first
+    /// actual request, e.g. a render submission, does not have to wait for
+    /// the TCP and TLS handshakes.
+    ///
+    /// Connections are kept in the pool for as long as configured through
+    /// [`OrdrClientBuilder::pool_idle_timeout`].
+    ///
+    /// This sends a request through [`OrdrClient::ping`].
+    pub async fn warm_up(&self) -> Result<(), ClientError> {
+        self.ping().await.map(|_| ())
+    }
+
+    /// Download a preview image of the given skin.
+    ///
+    /// Previews are not served by the o!rdr API so ratelimits don't apply.
+    pub async fn download_skin_preview(
+        &self,
+        skin: &Skin,
+        kind: PreviewKind,
+    ) -> Result<Download, ClientError> {
+        self.download(skin.preview_url(kind)).await
+    }
+
+    /// Download the `.osk` archive of a custom skin.
+    ///
+    /// This first requests the skin's [`SkinInfo`](crate::model::SkinInfo) so it
+    /// counts towards the skins ratelimit, the download itself is not ratelimited.
+    pub async fn download_custom_skin(&self, id: u32) -> Result<SkinArchive, ClientError> {
+        let info = self.custom_skin_info(id).await?;
+        let download = self.download(&info.download_link).await?;
+
+        Ok(SkinArchive::new(info, download))
+    }
+
     /// Get a paginated list of all available skins.
     pub const fn skin_list(&self) -> GetSkinList<'_> {
         GetSkinList::new(self)
     }
 
+    /// Statistics of the response cache.
+    ///
+    /// Returns `None` if no route is cached, see
+    /// [`OrdrClientBuilder::cache_ttl`].
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache.as_ref().map(|cache| cache.stats())
+    }
+
+    #[allow(unused)]
+    pub(crate) fn resolver(&self) -> &DnsResolver {
+        &self.inner.resolver
+    }
+
+    #[allow(unused)]
+    pub(crate) fn tls_config(&self) -> &TlsConfig {
+        &self.inner.tls
+    }
+
+    /// Statistics of the requests sent to each [`Route`].
+    ///
+    /// Routes without any sent request are not included.
+    #[must_use]
+    pub fn stats(&self) -> HashMap<Route, RouteStats> {
+        self.inner.stats.snapshot()
+    }
+
+    /// The share of render submissions that o!rdr rejected within the window
+    /// configured through [`OrdrClientBuilder::error_rate_window`].
+    ///
+    /// Submissions that failed without a response of o!rdr, e.g. due to
+    /// connection issues, or that used a dev mode [`Verification`] are not
+    /// included. Returns `0.0` if no render was submitted within the window.
+    #[must_use]
+    pub fn error_rate(&self) -> f64 {
+        self.inner.error_rate.rate()
+    }
+
+    pub(crate) fn error_rate_tracker(&self) -> &Arc<ErrorRate> {
+        &self.inner.error_rate
+    }
+
+    pub(crate) fn default_render_options(&self) -> Option<&RenderOptions> {
+        self.inner.default_render_options.as_ref()
+    }
+
+    pub(crate) fn compact_render_options(&self) -> bool {
+        self.inner.compact_render_options
+    }
+
+    pub(crate) fn default_skin(&self) -> Option<&RenderSkinOption<'static>> {
+        self.inner.default_skin.as_ref()
+    }
+
     pub(crate) fn verification(&self) -> Option<&Verification> {
         self.inner.verification.as_ref()
     }
@@ -118,28 +429,82 @@ impl OrdrClient {
             method,
             path,
             ratelimiter,
+            route,
         } = req;
 
-        let inner = self.try_request_raw(form, method, &path)?;
+        #[cfg(feature = "cache")]
+        let cache_slot = match self.inner.cache {
+            Some(ref cache) => match cache.lookup(route, &path) {
+                CacheLookup::Fresh(bytes) => {
+                    trace!(%route, "Cache hit");
+
+                    return Ok(OrdrFuture::cached(bytes));
+                }
+                CacheLookup::Miss(slot) => Some(slot),
+                CacheLookup::Uncached => None,
+            },
+            None => None,
+        };
+
+        if let Some(ref circuit) = self.inner.circuit {
+            circuit.check()?;
+        }
+
+        let (base_url, failover) = self.base_url();
+
+        #[allow(unused_mut)]
+        let mut req = self.try_build_request(base_url, form, method, &path)?;
+
+        #[cfg(feature = "cache")]
+        if let Some(ref slot) = cache_slot {
+            slot.add_conditional_headers(req.headers_mut());
+        }
+
+        let retry = Retry::new(&self.inner.http, &req);
+
+        let fut = OrdrFuture::new(
+            self.inner.http.request(req),
+            ratelimiter.and_then(|kind| self.inner.ratelimiter.acquire(kind)),
+            self.inner.middlewares.clone(),
+            StatsRecorder::new(Arc::clone(&self.inner.stats), route),
+            self.inner.circuit.clone(),
+        )
+        .retry(retry)
+        .failover(failover);
+
+        #[cfg(feature = "cache")]
+        let fut = fut.cache_slot(cache_slot);
+
+        Ok(fut)
+    }
 
-        Ok(OrdrFuture::new(
-            Box::pin(inner),
-            self.inner.ratelimiter.get(ratelimiter).acquire_owned(1),
-        ))
+    /// The base url that requests should currently be sent to.
+    fn base_url(&self) -> (&str, Option<FailoverSlot>) {
+        match self.inner.failover {
+            Some(ref failover) => {
+                let (base_url, slot) = failover.base_url();
+
+                (base_url, Some(slot))
+            }
+            None => (&self.inner.base_url, None),
+        }
     }
 
-    fn try_request_raw(
+    fn try_build_request(
         &self,
+        base_url: &str,
         form: Option<Form>,
         method: Method,
         path: &str,
-    ) -> Result<ResponseFuture, ClientError> {
-        let mut url = String::with_capacity(BASE_URL.len() + path.len());
-        url.push_str(BASE_URL);
+    ) -> Result<HyperRequest<Body>, ClientError> {
+        let mut url = String::with_capacity(base_url.len() + path.len());
+        url.push_str(base_url);
         url.push_str(path);
         debug!(?url);
 
-        debug_assert!(method != Method::POST || form.is_some());
+        if let Some(form) = form.as_ref().filter(|_| self.inner.log_bodies) {
+            debug!(?form, "Request body");
+        }
 
         let mut builder = HyperRequest::builder().method(method).uri(&url);
 
@@ -152,7 +517,13 @@ impl OrdrClient {
                 }
             }
 
-            headers.insert(USER_AGENT, HeaderValue::from_static(ROSU_RENDER_USER_AGENT));
+            headers.insert(USER_AGENT, self.inner.user_agent.clone());
+
+            #[cfg(feature = "compression")]
+            headers.insert(
+                hyper::header::ACCEPT_ENCODING,
+                HeaderValue::from_static(crate::util::compression::ACCEPT_ENCODING),
+            );
         }
 
         let try_req = if let Some(form) = form {
@@ -161,11 +532,164 @@ impl OrdrClient {
             builder.body(Body::empty())
         };
 
-        let req = try_req.map_err(|source| ClientError::BuildingRequest {
+        let mut req = try_req.map_err(|source| ClientError::BuildingRequest {
             source: Box::new(source),
         })?;
 
-        Ok(self.inner.http.request(req))
+        if let Some(ref middlewares) = self.inner.middlewares {
+            for middleware in middlewares.iter() {
+                middleware
+                    .before_request(&mut req)
+                    .map_err(|source| ClientError::Middleware { source })?;
+            }
+        }
+
+        Ok(req)
+    }
+
+    /// Send a `GET` request to the given url, resolved relative to the base
+    /// url, and return its body without collecting it.
+    pub(crate) async fn download(&self, url: &str) -> Result<Download, ClientError> {
+        let url = Url::parse(self.base_url().0)
+            .and_then(|base| base.join(url))
+            .map_err(|source| ClientError::BuildingRequest {
+                source: Box::new(source),
+            })?;
+
+        let req = HyperRequest::get(url.as_str())
+            .header(USER_AGENT, self.inner.user_agent.clone())
+            .body(Body::empty())
+            .map_err(|source| ClientError::BuildingRequest {
+                source: Box::new(source),
+            })?;
+
+        let response = self
+            .inner
+            .http
+            .request(req)
+            .await
+            .map_err(ClientError::from)?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            return Err(ClientError::Download {
+                url: url.as_str().into(),
+                status_code: status.as_u16(),
+            });
+        }
+
+        let content_length = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok()?.parse().ok());
+
+        Ok(Download::new(response.into_body()).content_length_fallback(content_length))
+    }
+
+    /// Send a `HEAD` request to the given replay url, following redirects,
+    /// and check whether the response looks like a replay file.
+    pub(crate) async fn validate_replay_url(&self, replay_url: &str) -> Result<(), ClientError> {
+        const MAX_REDIRECTS: usize = 5;
+
+        let invalid = |issue| ClientError::InvalidReplayUrl {
+            url: replay_url.into(),
+            issue,
+        };
+
+        let mut url = Url::parse(replay_url).map_err(|_| invalid(ReplayUrlIssue::Malformed))?;
+
+        for _ in 0..=MAX_REDIRECTS {
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(invalid(ReplayUrlIssue::Malformed));
+            }
+
+            let req = HyperRequest::head(url.as_str())
+                .header(USER_AGENT, self.inner.user_agent.clone())
+                .body(Body::empty())
+                .map_err(|source| ClientError::BuildingRequest {
+                    source: Box::new(source),
+                })?;
+
+            let response = self
+                .inner
+                .http
+                .request(req)
+                .await
+                .map_err(|source| invalid(source.into()))?;
+
+            if response.status().is_redirection() {
+                let location = response
+                    .headers()
+                    .get(LOCATION)
+                    .and_then(|location| location.to_str().ok())
+                    .and_then(|location| url.join(location).ok());
+
+                if let Some(location) = location {
+                    url = location;
+
+                    continue;
+                }
+            }
+
+            return check_replay_head(&response).map_err(invalid);
+        }
+
+        Err(invalid(ReplayUrlIssue::TooManyRedirects))
+    }
+
+    /// Send a request and collect its response without deserializing it.
+    pub(crate) fn raw_request(
+        &self,
+        form: Option<Form>,
+        method: Method,
+        path: &str,
+        ratelimiter: RatelimiterKind,
+    ) -> impl Future<Output = Result<Response<Bytes>, ClientError>> + Send + 'static {
+        let (base_url, failover) = self.base_url();
+        let try_req = self.try_build_request(base_url, form, method, path);
+        let ordr = self.clone();
+
+        async move {
+            let req = try_req?;
+
+            if let Some(ref circuit) = ordr.inner.circuit {
+                circuit.check()?;
+            }
+
+            if let Some(acquire) = ordr.inner.ratelimiter.acquire(ratelimiter) {
+                acquire.await;
+            }
+
+            let response = match ordr.inner.http.request(req).await {
+                Ok(response) => response,
+                Err(source) => {
+                    if let Some(failover) = failover {
+                        failover.record_error(&source);
+                    }
+
+                    return Err(source.into());
+                }
+            };
+
+            if let Some(ref circuit) = ordr.inner.circuit {
+                circuit.record(response.status());
+            }
+
+            if let Some(failover) = failover {
+                failover.record(response.status());
+            }
+
+            if let Some(ref middlewares) = ordr.inner.middlewares {
+                for middleware in middlewares.iter() {
+                    middleware.after_response(&response);
+                }
+            }
+
+            let (parts, bytes) = chunk_response(response).await?;
+
+            Ok(Response::from_parts(parts, bytes))
+        }
     }
 }
 
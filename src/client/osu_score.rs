@@ -0,0 +1,52 @@
+use rosu_v2::{model::score::Score, Osu};
+
+use crate::{
+    model::{RenderAdded, RenderSkinOption},
+    ClientError, OrdrClient,
+};
+
+impl OrdrClient {
+    /// Download the replay of a [`rosu_v2`] score and commission its render.
+    ///
+    /// The replay is downloaded through the given [`Osu`] client so it counts
+    /// towards its ratelimit as well as the render ratelimit of this client.
+    ///
+    /// Returns [`ClientError::ReplayUnavailable`] if the score has no replay.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use rosu_render::{model::RenderSkinOption, OrdrClient};
+    /// use rosu_v2::Osu;
+    ///
+    /// # async fn example(osu: Osu) -> Result<(), Box<dyn std::error::Error>> {
+    /// let ordr = OrdrClient::new();
+    /// let score = osu.score(4_382_000_000).await?;
+    /// let skin = RenderSkinOption::default();
+    ///
+    /// let render = ordr.render_osu_score(&osu, &score, "my-bot", &skin).await?;
+    /// println!("Commissioned render {}", render.render_id);
+    /// # Ok(()) }
+    /// ```
+    pub async fn render_osu_score(
+        &self,
+        osu: &Osu,
+        score: &Score,
+        username: &str,
+        skin: &RenderSkinOption<'_>,
+    ) -> Result<RenderAdded, ClientError> {
+        if !score.replay && !score.has_replay {
+            return Err(ClientError::ReplayUnavailable { score_id: score.id });
+        }
+
+        let replay = osu
+            .replay_raw(score.id)
+            .await
+            .map_err(|source| ClientError::OsuApi { source })?;
+
+        if replay.is_empty() {
+            return Err(ClientError::ReplayUnavailable { score_id: score.id });
+        }
+
+        self.render_with_replay_file(&replay, username, skin).await
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+};
+
+use tokio::{
+    sync::{oneshot, Notify},
+    task::JoinHandle,
+};
+
+use crate::{model::RenderAdded, request::RenderJob, ClientError, OrdrClient};
+
+use super::RatelimiterKind;
+
+/// The priority of a render submitted to a [`RequestQueue`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+type QueueKey = (Priority, u64);
+
+struct QueuedJob {
+    job: RenderJob,
+    tx: oneshot::Sender<Result<RenderAdded, ClientError>>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    jobs: Mutex<QueueJobs>,
+    notify: Notify,
+}
+
+#[derive(Default)]
+struct QueueJobs {
+    queued: BTreeMap<QueueKey, QueuedJob>,
+    next_seq: u64,
+}
+
+/// Queues render submissions and commissions them by [`Priority`] whenever
+/// the render ratelimit of the [`OrdrClient`] allows it.
+///
+/// Jobs of the same priority are commissioned in submission order.
+///
+/// Cheap to clone. The dispatching task is aborted once all clones are dropped.
+///
+/// # Example
+/// ```no_run
+/// use rosu_render::{
+///     client::{Priority, RequestQueue},
+///     model::RenderSkinOption,
+///     request::RenderJob,
+///     OrdrClient,
+/// };
+///
+/// # async fn example(replay: Vec<u8>) -> Result<(), rosu_render::ClientError> {
+/// let queue = RequestQueue::new(OrdrClient::new());
+///
+/// let job = RenderJob::with_file(replay, "my-bot", RenderSkinOption::default());
+/// let render = queue.submit(job, Priority::High).await?;
+/// println!("Commissioned render {}", render.render_id);
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct RequestQueue {
+    inner: Arc<QueueRef>,
+}
+
+struct QueueRef {
+    state: Arc<QueueState>,
+    task: JoinHandle<()>,
+}
+
+impl Drop for QueueRef {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl RequestQueue {
+    /// Create a new queue that commissions renders through the given client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    #[must_use]
+    pub fn new(ordr: OrdrClient) -> Self {
+        let state = Arc::new(QueueState::default());
+        let task = tokio::spawn(dispatch(ordr, Arc::clone(&state)));
+
+        Self {
+            inner: Arc::new(QueueRef { state, task }),
+        }
+    }
+
+    /// Queue a render job.
+    ///
+    /// Await the returned [`QueuedRender`] to get the result of commissioning
+    /// or cancel it while it's still queued.
+    pub fn submit(&self, job: RenderJob, priority: Priority) -> QueuedRender {
+        let (tx, rx) = oneshot::channel();
+        let state = &self.inner.state;
+
+        let key = {
+            let mut jobs = state.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+            let key = (priority, jobs.next_seq);
+            jobs.next_seq += 1;
+            jobs.queued.insert(key, QueuedJob { job, tx });
+
+            key
+        };
+
+        state.notify.notify_one();
+
+        QueuedRender {
+            key,
+            state: Arc::clone(state),
+            rx,
+        }
+    }
+
+    /// The amount of jobs that have not been dispatched yet.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner
+            .state
+            .jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .queued
+            .len()
+    }
+
+    /// Whether no jobs are waiting to be dispatched.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+async fn dispatch(ordr: OrdrClient, state: Arc<QueueState>) {
+    let ratelimiter = ordr.inner.ratelimiter.get(RatelimiterKind::SendRender);
+
+    loop {
+        let notified = state.notify.notified();
+
+        if state
+            .jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .queued
+            .is_empty()
+        {
+            notified.await;
+
+            continue;
+        }
+
+        if let Some(ref ratelimiter) = ratelimiter {
+            ratelimiter.acquire_one().await;
+        }
+
+        // Jobs may have been submitted or cancelled in the meanwhile so only
+        // pick the job once the permit is acquired.
+        let Some((_, queued)) = state
+            .jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .queued
+            .pop_first()
+        else {
+            continue;
+        };
+
+        let ordr = ordr.clone();
+
+        tokio::spawn(async move {
+            let QueuedJob { job, tx } = queued;
+            let res = job.commission(&ordr).without_ratelimit().await;
+            let _ = tx.send(res);
+        });
+    }
+}
+
+/// A render that was submitted to a [`RequestQueue`].
+///
+/// Resolves once the render was commissioned.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct QueuedRender {
+    key: QueueKey,
+    state: Arc<QueueState>,
+    rx: oneshot::Receiver<Result<RenderAdded, ClientError>>,
+}
+
+impl QueuedRender {
+    /// The priority with which the render was submitted.
+    #[must_use]
+    pub fn priority(&self) -> Priority {
+        self.key.0
+    }
+
+    /// Remove the render from the queue.
+    ///
+    /// Returns `false` if the render was already dispatched, in which case it
+    /// will be commissioned regardless. Otherwise awaiting this
+    /// [`QueuedRender`] will return [`ClientError::Cancelled`].
+    #[allow(clippy::must_use_candidate)]
+    pub fn cancel(&self) -> bool {
+        self.state
+            .jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .queued
+            .remove(&self.key)
+            .is_some()
+    }
+}
+
+impl Future for QueuedRender {
+    type Output = Result<RenderAdded, ClientError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.rx).poll(cx) {
+            Poll::Ready(Ok(res)) => Poll::Ready(res),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(ClientError::Cancelled)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -1,55 +1,302 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
 
-use leaky_bucket::RateLimiter;
+use hyper::Method;
+use leaky_bucket::{AcquireOwned, RateLimiter};
+use pin_project::pin_project;
+use serde::Deserialize;
 
 use super::builder::RatelimitBuilder;
 
-pub(super) struct Ratelimiter {
-    pub(super) general: Arc<RateLimiter>,
-    pub(super) send_render: Arc<RateLimiter>,
+/// Leaky buckets for all [`RatelimiterKind`]s or `None` if ratelimiting is disabled.
+pub(super) struct Ratelimiter(Option<Buckets>);
+
+struct Buckets {
+    total: Arc<RateLimiter>,
+    general: Arc<RateLimiter>,
+    render_list: Arc<RateLimiter>,
+    servers: Arc<RateLimiter>,
+    skins: Arc<RateLimiter>,
+    send_render: Arc<RateLimiter>,
 }
 
 #[derive(Copy, Clone)]
 pub(crate) enum RatelimiterKind {
     General,
+    RenderList,
+    Servers,
+    Skins,
     SendRender,
 }
 
+impl RatelimiterKind {
+    /// The ratelimiter for a request that does not belong to a known route.
+    ///
+    /// `POST` requests count towards the render ratelimit, all others towards
+    /// the bucket matching their path or the general bucket.
+    pub(crate) fn from_path(method: &Method, path: &str) -> Self {
+        if method == Method::POST {
+            return Self::SendRender;
+        }
+
+        let path = path.trim_start_matches('/');
+
+        if path.starts_with("renders") {
+            Self::RenderList
+        } else if path.starts_with("servers") {
+            Self::Servers
+        } else if path.starts_with("skins") {
+            Self::Skins
+        } else {
+            Self::General
+        }
+    }
+}
+
+/// Ratelimit buckets of GET endpoints.
+///
+/// Each GET request counts towards the bucket of its endpoint and
+/// additionally towards [`RatelimitBucket::Total`] which caps the combined
+/// rate of all GET requests.
+///
+/// Buckets can be configured through
+/// [`OrdrClientBuilder::ratelimit`](crate::client::OrdrClientBuilder::ratelimit).
+/// By default, the skin, server, and general buckets together only use up 8
+/// of the total 10 requests per minute so that the render list always keeps
+/// a share of the total.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum RatelimitBucket {
+    /// Requests through [`OrdrClient::raw`](crate::OrdrClient::raw) or the
+    /// [`tower`](https://docs.rs/tower) service that don't belong to any other bucket.
+    ///
+    /// Defaults to 2 requests per minute.
+    General,
+    /// [`OrdrClient::render_list`](crate::OrdrClient::render_list)
+    ///
+    /// Defaults to 10 requests per minute.
+    RenderList,
+    /// [`OrdrClient::server_list`](crate::OrdrClient::server_list) and
+    /// [`OrdrClient::server_online_count`](crate::OrdrClient::server_online_count)
+    ///
+    /// Defaults to 3 requests per minute.
+    Servers,
+    /// [`OrdrClient::skin_list`](crate::OrdrClient::skin_list) and
+    /// [`OrdrClient::custom_skin_info`](crate::OrdrClient::custom_skin_info)
+    ///
+    /// Defaults to 3 requests per minute.
+    Skins,
+    /// All GET requests combined, on top of the bucket of their endpoint.
+    ///
+    /// Defaults to 10 requests per minute.
+    Total,
+}
+
 impl Ratelimiter {
-    pub fn new(builder: &RatelimitBuilder) -> Self {
+    /// The skin, server, and general buckets add up to 8 per minute which
+    /// leaves 2 of the total for the render list.
+    const fn default_bucket(bucket: RatelimitBucket) -> RatelimitBuilder {
+        let (interval, max) = match bucket {
+            RatelimitBucket::General => (30_000, 2),
+            RatelimitBucket::Servers | RatelimitBucket::Skins => (20_000, 3),
+            RatelimitBucket::RenderList | RatelimitBucket::Total => (6000, 10),
+        };
+
+        RatelimitBuilder {
+            interval,
+            refill: 1,
+            max,
+        }
+    }
+
+    pub fn new(
+        send_render: &RatelimitBuilder,
+        buckets: &HashMap<RatelimitBucket, RatelimitBuilder>,
+    ) -> Self {
         let RatelimitBuilder {
             interval,
             refill,
             max,
-        } = builder;
+        } = send_render;
 
         info!("o!rdr ratelimit: Refill {refill} every {interval}ms, up to {max}");
 
-        Self {
-            general: Arc::new(
-                // 10 per minute
-                RateLimiter::builder()
-                    .max(10)
-                    .initial(10)
-                    .refill(1)
-                    .interval(Duration::from_secs(6))
-                    .build(),
-            ),
-            send_render: Arc::new(
-                RateLimiter::builder()
-                    .max(*max as usize)
-                    .initial(*max as usize)
-                    .refill(*refill as usize)
-                    .interval(Duration::from_millis(*interval))
-                    .build(),
-            ),
+        let bucket = |bucket| match buckets.get(&bucket) {
+            Some(builder) => build(builder),
+            None => build(&Self::default_bucket(bucket)),
+        };
+
+        Self(Some(Buckets {
+            total: bucket(RatelimitBucket::Total),
+            general: bucket(RatelimitBucket::General),
+            render_list: bucket(RatelimitBucket::RenderList),
+            servers: bucket(RatelimitBucket::Servers),
+            skins: bucket(RatelimitBucket::Skins),
+            send_render: build(send_render),
+        }))
+    }
+
+    pub fn disabled() -> Self {
+        warn!("o!rdr ratelimit: Disabled");
+
+        Self(None)
+    }
+
+    /// Acquire an allowance of the bucket and, unless it's the render
+    /// ratelimit, of the total bucket.
+    ///
+    /// Returns `None` if ratelimiting is disabled.
+    pub fn acquire(&self, kind: RatelimiterKind) -> Option<Acquire> {
+        let buckets = self.0.as_ref()?;
+
+        let total = match kind {
+            RatelimiterKind::SendRender => None,
+            _ => Some(Arc::clone(&buckets.total).acquire_owned(1)),
+        };
+
+        let bucket = self.get(kind)?.acquire_owned(1);
+
+        Some(Acquire {
+            bucket: Some(bucket),
+            total,
+        })
+    }
+
+    /// Returns `None` if ratelimiting is disabled.
+    pub fn get(&self, kind: RatelimiterKind) -> Option<Arc<RateLimiter>> {
+        let buckets = self.0.as_ref()?;
+
+        let bucket = match kind {
+            RatelimiterKind::General => &buckets.general,
+            RatelimiterKind::RenderList => &buckets.render_list,
+            RatelimiterKind::Servers => &buckets.servers,
+            RatelimiterKind::Skins => &buckets.skins,
+            RatelimiterKind::SendRender => &buckets.send_render,
+        };
+
+        Some(Arc::clone(bucket))
+    }
+}
+
+/// Future that resolves once an allowance of the request's bucket and then
+/// one of the total bucket were acquired.
+#[pin_project]
+pub(crate) struct Acquire {
+    #[pin]
+    bucket: Option<AcquireOwned>,
+    #[pin]
+    total: Option<AcquireOwned>,
+}
+
+impl Future for Acquire {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if let Some(bucket) = this.bucket.as_mut().as_pin_mut() {
+            match bucket.poll(cx) {
+                Poll::Ready(()) => this.bucket.set(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        if let Some(total) = this.total.as_mut().as_pin_mut() {
+            match total.poll(cx) {
+                Poll::Ready(()) => this.total.set(None),
+                Poll::Pending => return Poll::Pending,
+            }
         }
+
+        Poll::Ready(())
+    }
+}
+
+fn build(builder: &RatelimitBuilder) -> Arc<RateLimiter> {
+    let RatelimitBuilder {
+        interval,
+        refill,
+        max,
+    } = builder;
+
+    Arc::new(
+        RateLimiter::builder()
+            .max(*max as usize)
+            .initial(*max as usize)
+            .refill(*refill as usize)
+            .interval(Duration::from_millis(*interval))
+            .build(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::FutureExt;
+
+    use crate::client::builder::RatelimitBuilder;
+
+    use super::{RatelimitBucket, Ratelimiter, RatelimiterKind};
+
+    #[tokio::test]
+    async fn total_bucket_is_shared() {
+        let buckets = HashMap::from([(
+            RatelimitBucket::Total,
+            RatelimitBuilder {
+                interval: 60_000,
+                refill: 1,
+                max: 2,
+            },
+        )]);
+        let ratelimiter = Ratelimiter::new(
+            &RatelimitBuilder {
+                interval: 1000,
+                refill: 1,
+                max: 1,
+            },
+            &buckets,
+        );
+
+        let acquire = |kind| ratelimiter.acquire(kind).unwrap().now_or_never();
+
+        assert!(acquire(RatelimiterKind::Skins).is_some());
+        assert!(acquire(RatelimiterKind::RenderList).is_some());
+        assert!(acquire(RatelimiterKind::Servers).is_none());
+
+        // The render ratelimit is separate
+        assert!(acquire(RatelimiterKind::SendRender).is_some());
     }
 
-    pub fn get(&self, kind: RatelimiterKind) -> Arc<RateLimiter> {
-        match kind {
-            RatelimiterKind::General => Arc::clone(&self.general),
-            RatelimiterKind::SendRender => Arc::clone(&self.send_render),
+    #[tokio::test]
+    async fn render_list_keeps_share_by_default() {
+        let ratelimiter = Ratelimiter::new(
+            &RatelimitBuilder {
+                interval: 1000,
+                refill: 1,
+                max: 1,
+            },
+            &HashMap::new(),
+        );
+
+        let acquire = |kind| ratelimiter.acquire(kind).unwrap().now_or_never();
+
+        for kind in [
+            RatelimiterKind::Skins,
+            RatelimiterKind::Servers,
+            RatelimiterKind::General,
+        ] {
+            while acquire(kind).is_some() {}
         }
+
+        assert!(acquire(RatelimiterKind::RenderList).is_some());
+        assert!(acquire(RatelimiterKind::RenderList).is_some());
     }
 }
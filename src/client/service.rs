@@ -0,0 +1,94 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use hyper::{body::Bytes, Method, Response};
+use serde::Serialize;
+use tower_service::Service;
+
+use crate::{request::append_query, ClientError, OrdrClient};
+
+use super::RatelimiterKind;
+
+/// A request that can be sent through the [`Service`] implementation of [`OrdrClient`].
+///
+/// Requests still respect the client's ratelimits: `POST` requests count
+/// towards the render ratelimit, all others towards the [`RatelimitBucket`]
+/// matching their path or the general bucket.
+///
+/// [`RatelimitBucket`]: crate::client::RatelimitBucket
+///
+/// # Example
+/// ```no_run
+/// use rosu_render::{client::OrdrRequest, OrdrClient};
+/// use tower_service::Service;
+///
+/// # async fn example() -> Result<(), rosu_render::ClientError> {
+/// let mut client = OrdrClient::new();
+///
+/// let response = client.call(OrdrRequest::get("servers/onlinecount")).await?;
+/// println!("{}: {:?}", response.status(), response.body());
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct OrdrRequest {
+    method: Method,
+    path: String,
+}
+
+impl OrdrRequest {
+    /// Create a new request for the given path relative to the o!rdr API base url.
+    pub fn new(method: Method, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+        }
+    }
+
+    /// Create a new `GET` request for the given path relative to the o!rdr API base url.
+    pub fn get(path: impl Into<String>) -> Self {
+        Self::new(Method::GET, path)
+    }
+
+    /// Append the serialized query to the path. Be sure this is only called once!
+    pub fn query(mut self, query: impl Serialize) -> Result<Self, ClientError> {
+        append_query(&mut self.path, query)?;
+
+        Ok(self)
+    }
+
+    /// The method of the request.
+    #[must_use]
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// The path of the request including its query.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn ratelimiter(&self) -> RatelimiterKind {
+        RatelimiterKind::from_path(&self.method, &self.path)
+    }
+}
+
+impl Service<OrdrRequest> for OrdrClient {
+    type Response = Response<Bytes>;
+    type Error = ClientError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Ratelimits are awaited within the returned future
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: OrdrRequest) -> Self::Future {
+        let ratelimiter = req.ratelimiter();
+
+        Box::pin(self.raw_request(None, req.method, &req.path, ratelimiter))
+    }
+}
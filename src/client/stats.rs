@@ -0,0 +1,220 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use hyper::StatusCode;
+
+use crate::routing::Route;
+
+/// Statistics of the requests sent to a [`Route`].
+///
+/// Requests that were answered by the response cache or aborted before
+/// being sent are not included.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouteStats {
+    /// Amount of sent requests.
+    pub requests: u64,
+    /// Amount of requests that failed to send or whose response
+    /// had a client or server error status.
+    pub errors: u64,
+    /// Average time until the response status and headers were received.
+    pub average_latency: Duration,
+    /// Time until the response status and headers of the latest request were received.
+    pub last_latency: Option<Duration>,
+    /// Status code of the latest response.
+    pub last_status: Option<u16>,
+    /// Average time that requests waited on the client's own ratelimiter
+    /// before being sent.
+    pub average_ratelimit_wait: Duration,
+    /// Time that the latest request waited on the client's own ratelimiter.
+    pub last_ratelimit_wait: Option<Duration>,
+}
+
+/// Keeps track of [`RouteStats`] for all routes.
+#[derive(Default)]
+pub(crate) struct RequestStats {
+    routes: Mutex<HashMap<Route, RouteCounters>>,
+}
+
+#[derive(Default)]
+struct RouteCounters {
+    requests: u64,
+    errors: u64,
+    responses: u32,
+    total_latency: Duration,
+    last_latency: Option<Duration>,
+    last_status: Option<u16>,
+    total_ratelimit_wait: Duration,
+    last_ratelimit_wait: Option<Duration>,
+}
+
+impl RequestStats {
+    pub(crate) fn snapshot(&self) -> HashMap<Route, RouteStats> {
+        self.routes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(route, counters)| {
+                let average_latency = counters
+                    .total_latency
+                    .checked_div(counters.responses)
+                    .unwrap_or_default();
+
+                let average_ratelimit_wait = u32::try_from(counters.requests)
+                    .ok()
+                    .and_then(|requests| counters.total_ratelimit_wait.checked_div(requests))
+                    .unwrap_or_default();
+
+                let stats = RouteStats {
+                    requests: counters.requests,
+                    errors: counters.errors,
+                    average_latency,
+                    last_latency: counters.last_latency,
+                    last_status: counters.last_status,
+                    average_ratelimit_wait,
+                    last_ratelimit_wait: counters.last_ratelimit_wait,
+                };
+
+                (*route, stats)
+            })
+            .collect()
+    }
+
+    /// Record a sent request.
+    ///
+    /// `status` is `None` if no response was received.
+    fn record(
+        &self,
+        route: Route,
+        ratelimit_wait: Duration,
+        latency: Duration,
+        status: Option<StatusCode>,
+    ) {
+        let mut routes = self.routes.lock().unwrap_or_else(PoisonError::into_inner);
+        let counters = routes.entry(route).or_default();
+
+        counters.requests += 1;
+        counters.total_ratelimit_wait += ratelimit_wait;
+        counters.last_ratelimit_wait = Some(ratelimit_wait);
+
+        let Some(status) = status else {
+            counters.errors += 1;
+
+            return;
+        };
+
+        if status.is_client_error() || status.is_server_error() {
+            counters.errors += 1;
+        }
+
+        counters.responses += 1;
+        counters.total_latency += latency;
+        counters.last_latency = Some(latency);
+        counters.last_status = Some(status.as_u16());
+    }
+}
+
+/// Measures the ratelimit wait and latency of a single request.
+pub(crate) struct StatsRecorder {
+    stats: Arc<RequestStats>,
+    route: Route,
+    waiting: Option<Instant>,
+    ratelimit_wait: Duration,
+    start: Option<Instant>,
+}
+
+impl StatsRecorder {
+    pub(crate) fn new(stats: Arc<RequestStats>, route: Route) -> Self {
+        Self {
+            stats,
+            route,
+            waiting: None,
+            ratelimit_wait: Duration::ZERO,
+            start: None,
+        }
+    }
+
+    /// Start measuring the ratelimit wait if not already started.
+    pub(crate) fn wait(&mut self) {
+        if self.start.is_none() {
+            self.waiting.get_or_insert_with(Instant::now);
+        }
+    }
+
+    /// Start measuring the latency if not already started.
+    pub(crate) fn start(&mut self) {
+        if self.start.is_none() {
+            self.ratelimit_wait = self
+                .waiting
+                .map_or(Duration::ZERO, |waiting| waiting.elapsed());
+            self.start = Some(Instant::now());
+        }
+    }
+
+    pub(crate) fn finish(self, status: Option<StatusCode>) {
+        let latency = self.start.map_or(Duration::ZERO, |start| start.elapsed());
+
+        trace!(
+            route = %self.route,
+            ratelimit_wait = ?self.ratelimit_wait,
+            ?latency,
+            "Finished request"
+        );
+
+        self.stats
+            .record(self.route, self.ratelimit_wait, latency, status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use hyper::StatusCode;
+
+    use crate::routing::Route;
+
+    use super::{RequestStats, RouteStats};
+
+    #[test]
+    fn record_route_stats() {
+        let stats = RequestStats::default();
+
+        stats.record(
+            Route::SkinList,
+            Duration::from_millis(60),
+            Duration::from_millis(100),
+            Some(StatusCode::OK),
+        );
+        stats.record(
+            Route::SkinList,
+            Duration::ZERO,
+            Duration::from_millis(300),
+            Some(StatusCode::SERVICE_UNAVAILABLE),
+        );
+        stats.record(
+            Route::SkinList,
+            Duration::from_millis(30),
+            Duration::ZERO,
+            None,
+        );
+
+        let snapshot = stats.snapshot();
+
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(
+            snapshot[&Route::SkinList],
+            RouteStats {
+                requests: 3,
+                errors: 2,
+                average_latency: Duration::from_millis(200),
+                last_latency: Some(Duration::from_millis(300)),
+                last_status: Some(503),
+                average_ratelimit_wait: Duration::from_millis(30),
+                last_ratelimit_wait: Some(Duration::from_millis(30)),
+            }
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Resolving hostnames to IP addresses.
+//!
+//! By default, the system's resolver is used. A custom [`Resolve`]
+//! implementation and an [`IpPreference`] can be specified through
+//! [`OrdrClientBuilder`](crate::client::OrdrClientBuilder) and, for the
+//! websocket, through `OrdrWebsocketBuilder`.
+
+use std::{
+    future::Future,
+    io::{Error as IoError, ErrorKind},
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    vec::IntoIter,
+};
+
+use hyper::{client::connect::dns::Name, service::Service};
+use serde::Deserialize;
+
+/// Future returned by [`Resolve::resolve`].
+pub type ResolveFuture = Pin<Box<dyn Future<Output = Result<Vec<IpAddr>, IoError>> + Send>>;
+
+/// Resolves hostnames to IP addresses, e.g. through `hickory-dns`.
+///
+/// # Example
+/// ```
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// use rosu_render::dns::{Resolve, ResolveFuture};
+///
+/// struct Localhost;
+///
+/// impl Resolve for Localhost {
+///     fn resolve(&self, _host: &str) -> ResolveFuture {
+///         Box::pin(async { Ok(vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]) })
+///     }
+/// }
+/// ```
+pub trait Resolve: Send + Sync + 'static {
+    /// Resolve the given hostname to its IP addresses.
+    fn resolve(&self, host: &str) -> ResolveFuture;
+}
+
+/// Resolves hostnames through the system's resolver.
+///
+/// Outside of a tokio runtime, e.g. for a websocket on a different runtime,
+/// the blocking lookup runs on a separate thread.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemResolver;
+
+impl Resolve for SystemResolver {
+    fn resolve(&self, host: &str) -> ResolveFuture {
+        // The port is irrelevant but required for the lookup
+        let host = format!("{host}:0");
+
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Box::pin(async move {
+                let addrs = tokio::net::lookup_host(host).await?;
+
+                Ok(addrs.map(|addr| addr.ip()).collect())
+            });
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        std::thread::spawn(move || {
+            let addrs = std::net::ToSocketAddrs::to_socket_addrs(host.as_str())
+                .map(|addrs| addrs.map(|addr| addr.ip()).collect());
+
+            // Only fails if the future was dropped
+            let _ = tx.send(addrs);
+        });
+
+        Box::pin(async move {
+            rx.await
+                .map_err(|_| IoError::other("lookup thread panicked"))?
+        })
+    }
+}
+
+/// Which IP versions to connect through.
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IpPreference {
+    /// Use addresses in the order provided by the resolver.
+    #[default]
+    Any,
+    /// Attempt IPv4 addresses before IPv6 addresses.
+    PreferV4,
+    /// Attempt IPv6 addresses before IPv4 addresses.
+    PreferV6,
+    /// Only use IPv4 addresses.
+    OnlyV4,
+    /// Only use IPv6 addresses.
+    OnlyV6,
+}
+
+impl IpPreference {
+    fn apply(self, addrs: &mut Vec<IpAddr>) {
+        match self {
+            Self::Any => {}
+            Self::PreferV4 => addrs.sort_by_key(IpAddr::is_ipv6),
+            Self::PreferV6 => addrs.sort_by_key(IpAddr::is_ipv4),
+            Self::OnlyV4 => addrs.retain(IpAddr::is_ipv4),
+            Self::OnlyV6 => addrs.retain(IpAddr::is_ipv6),
+        }
+    }
+}
+
+/// A [`Resolve`] implementation combined with an [`IpPreference`].
+#[derive(Clone)]
+pub(crate) struct DnsResolver {
+    resolver: Arc<dyn Resolve>,
+    preference: IpPreference,
+}
+
+impl DnsResolver {
+    pub(crate) fn new(resolver: Arc<dyn Resolve>, preference: IpPreference) -> Self {
+        Self {
+            resolver,
+            preference,
+        }
+    }
+
+    /// Resolve the host and order the addresses by preference.
+    pub(crate) fn lookup(&self, host: &str) -> ResolveFuture {
+        let fut = self.resolver.resolve(host);
+        let preference = self.preference;
+        let host = host.to_owned();
+
+        Box::pin(async move {
+            let mut addrs = fut.await?;
+            preference.apply(&mut addrs);
+
+            if addrs.is_empty() {
+                let msg = format!("no suitable address found for host `{host}`");
+
+                return Err(IoError::new(ErrorKind::NotFound, msg));
+            }
+
+            Ok(addrs)
+        })
+    }
+}
+
+impl Default for DnsResolver {
+    fn default() -> Self {
+        Self::new(Arc::new(SystemResolver), IpPreference::default())
+    }
+}
+
+impl Service<Name> for DnsResolver {
+    type Response = IntoIter<SocketAddr>;
+    type Error = IoError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, IoError>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let fut = self.lookup(name.as_str());
+
+        Box::pin(async move {
+            let addrs = fut.await?;
+
+            // The connector overrides the port
+            let addrs: Vec<_> = addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)).collect();
+
+            Ok(addrs.into_iter())
+        })
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl reqwest::dns::Resolve for DnsResolver {
+    fn resolve(&self, name: Name) -> reqwest::dns::Resolving {
+        let fut = self.lookup(name.as_str());
+
+        Box::pin(async move {
+            let addrs = fut.await?;
+
+            // reqwest overrides the port
+            let addrs: Vec<_> = addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)).collect();
+
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use super::IpPreference;
+
+    #[test]
+    fn apply_ip_preference() {
+        let v4 = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        let addrs = vec![v6, v4, v6];
+
+        let apply = |preference: IpPreference| {
+            let mut addrs = addrs.clone();
+            preference.apply(&mut addrs);
+
+            addrs
+        };
+
+        assert_eq!(apply(IpPreference::Any), [v6, v4, v6]);
+        assert_eq!(apply(IpPreference::PreferV4), [v4, v6, v6]);
+        assert_eq!(apply(IpPreference::PreferV6), [v6, v6, v4]);
+        assert_eq!(apply(IpPreference::OnlyV4), [v4]);
+        assert_eq!(apply(IpPreference::OnlyV6), [v6, v6]);
+    }
+}
@@ -1,4 +1,11 @@
-#![doc = include_str!("../README.md")]
+#![cfg_attr(
+    any(
+        feature = "ws-native",
+        feature = "ws-rustls-native-roots",
+        feature = "ws-rustls-webpki-roots"
+    ),
+    doc = include_str!("../README.md")
+)]
 #![warn(clippy::pedantic, nonstandard_style, rust_2018_idioms, unused)]
 #![allow(
     clippy::module_name_repetitions,
@@ -12,25 +19,66 @@
 mod routing;
 mod util;
 
+pub mod analytics;
 pub mod client;
+pub mod dns;
 pub mod model;
+pub mod multipart;
+pub mod prelude;
 pub mod request;
 
 #[cfg(any(
-    feature = "native",
-    feature = "rustls-native-roots",
-    feature = "rustls-webpki-roots"
+    feature = "ws-native",
+    feature = "ws-rustls-native-roots",
+    feature = "ws-rustls-webpki-roots"
 ))]
 pub mod websocket;
 
+#[cfg(any(
+    feature = "ws-native",
+    feature = "ws-rustls-native-roots",
+    feature = "ws-rustls-webpki-roots"
+))]
+pub mod service;
+
+#[cfg(any(
+    feature = "ws-native",
+    feature = "ws-rustls-native-roots",
+    feature = "ws-rustls-webpki-roots"
+))]
+pub mod tracker;
+
+#[cfg(all(
+    feature = "webhook",
+    any(
+        feature = "ws-native",
+        feature = "ws-rustls-native-roots",
+        feature = "ws-rustls-webpki-roots"
+    )
+))]
+pub mod webhook;
+
+#[cfg(all(
+    feature = "mock-server",
+    any(
+        feature = "ws-native",
+        feature = "ws-rustls-native-roots",
+        feature = "ws-rustls-webpki-roots"
+    )
+))]
+pub mod mock;
+
 #[macro_use]
 extern crate tracing;
 
 pub use self::client::{error::ClientError, OrdrClient};
 
 #[cfg(any(
-    feature = "native",
-    feature = "rustls-native-roots",
-    feature = "rustls-webpki-roots"
+    feature = "ws-native",
+    feature = "ws-rustls-native-roots",
+    feature = "ws-rustls-webpki-roots"
 ))]
-pub use self::websocket::{error::WebsocketError, OrdrWebsocket};
+pub use self::{
+    service::OrdrService,
+    websocket::{error::WebsocketError, OrdrWebsocket},
+};
@@ -0,0 +1,498 @@
+//! A local emulation of o!rdr for integration tests.
+//!
+//! [`MockServer`] serves the render submission endpoint over HTTP and emits the
+//! events of commissioned renders through an engine.io / socket.io websocket so
+//! that an [`OrdrClient`] and an [`OrdrWebsocket`] can be tested end-to-end
+//! without network access.
+//!
+//! With the `vcr` feature, responses of o!rdr can be recorded into a
+//! [`Cassette`] through a [`Recorder`] and later be replayed via
+//! [`MockServer::replay`].
+
+#[cfg(feature = "vcr")]
+mod vcr;
+
+#[cfg(feature = "vcr")]
+pub use self::vcr::{Cassette, Interaction, Recorder};
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    convert::Infallible,
+    hash::{Hash, Hasher},
+    io::Error as IoError,
+    net::{Ipv4Addr, SocketAddr},
+    pin::pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use futures::{
+    future::{self, Either},
+    SinkExt, StreamExt,
+};
+use hyper::{
+    header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    server::conn::Http,
+    service::service_fn,
+    Body, Method, Request, Response, StatusCode,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast::{self, error::RecvError, Sender},
+    task::JoinHandle,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    client::{error::ErrorCode, OrdrClientBuilder},
+    websocket::{simulator::lifecycle_payloads, OrdrWebsocketBuilder},
+    OrdrClient, OrdrWebsocket,
+};
+
+/// Engine.io handshake that is sent to every websocket connection.
+const HANDSHAKE: &str = r#"0{"sid":"mock","upgrades":[],"pingInterval":25000,"pingTimeout":20000,"maxPayload":1000000}"#;
+
+/// A local o!rdr server for tests.
+///
+/// Commissioned renders get increasing render ids starting from 1 and emit
+/// their `render_added`, progress, and terminal events through all connected
+/// websockets.
+///
+/// The server shuts down once dropped.
+///
+/// # Example
+/// ```
+/// use rosu_render::{mock::MockServer, model::RenderSkinOption, websocket::event::RawEvent};
+///
+/// # #[tokio::main] async fn main() {
+/// let server = MockServer::start().await.unwrap();
+/// let client = server.client().build();
+/// let mut websocket = server.websocket().connect().await.unwrap();
+///
+/// let skin = RenderSkinOption::default();
+/// let added = client
+///     .render_with_replay_url("https://example.com/replay.osr", "user", &skin)
+///     .await
+///     .unwrap();
+///
+/// loop {
+///     if let RawEvent::RenderDone(done) = websocket.next_event().await.unwrap() {
+///         assert_eq!(done.render_id, added.render_id);
+///         break;
+///     }
+/// }
+/// # }
+/// ```
+pub struct MockServer {
+    http_addr: SocketAddr,
+    websocket_addr: SocketAddr,
+    state: Arc<MockState>,
+    tasks: [JoinHandle<()>; 2],
+}
+
+struct MockState {
+    next_render_id: AtomicU32,
+    rejections: Mutex<VecDeque<ErrorCode>>,
+    failures: Mutex<VecDeque<ErrorCode>>,
+    fail_renders: AtomicBool,
+    online_count: AtomicU32,
+    events: Sender<String>,
+    stubs: Mutex<HashMap<Box<str>, Box<str>>>,
+    requests: Mutex<HashMap<Box<str>, u32>>,
+    #[cfg(feature = "vcr")]
+    replays: Mutex<Vec<Replay>>,
+}
+
+#[cfg(feature = "vcr")]
+struct Replay {
+    interaction: Interaction,
+    played: bool,
+}
+
+impl MockServer {
+    const PROGRESS_STEPS: u8 = 3;
+    const EVENT_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Start the server on local ports.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub async fn start() -> Result<Self, IoError> {
+        Self::start_with(MockState::new()).await
+    }
+
+    /// Start the server on local ports and respond to requests with the
+    /// interactions of the [`Cassette`].
+    ///
+    /// Each request is answered by the first interaction with the same method
+    /// and uri that was not replayed yet. Once all matching interactions were
+    /// replayed, the last one is repeated. Requests without any matching
+    /// interaction are handled as usual.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    #[cfg(feature = "vcr")]
+    pub async fn replay(cassette: Cassette) -> Result<Self, IoError> {
+        let replays = cassette
+            .interactions
+            .into_iter()
+            .map(|interaction| Replay {
+                interaction,
+                played: false,
+            })
+            .collect();
+
+        let state = MockState {
+            replays: Mutex::new(replays),
+            ..MockState::new()
+        };
+
+        Self::start_with(state).await
+    }
+
+    async fn start_with(state: MockState) -> Result<Self, IoError> {
+        let localhost = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+        let http = TcpListener::bind(localhost).await?;
+        let websocket = TcpListener::bind(localhost).await?;
+
+        let http_addr = http.local_addr()?;
+        let websocket_addr = websocket.local_addr()?;
+
+        let state = Arc::new(state);
+
+        let tasks = [
+            tokio::spawn(accept_http(http, Arc::clone(&state))),
+            tokio::spawn(accept_websockets(websocket, state.events.clone())),
+        ];
+
+        Ok(Self {
+            http_addr,
+            websocket_addr,
+            state,
+            tasks,
+        })
+    }
+
+    /// The base url of the HTTP API, see [`OrdrClientBuilder::base_url`].
+    #[must_use]
+    pub fn base_url(&self) -> String {
+        format!("http://{}/", self.http_addr)
+    }
+
+    /// The url of the websocket, see [`OrdrWebsocketBuilder::url`].
+    #[must_use]
+    pub fn websocket_url(&self) -> String {
+        format!("ws://{}", self.websocket_addr)
+    }
+
+    /// A client builder that sends requests to this server without ratelimit.
+    pub fn client(&self) -> OrdrClientBuilder {
+        OrdrClient::builder()
+            .base_url(self.base_url())
+            .disable_ratelimit()
+    }
+
+    /// A websocket builder that connects to this server.
+    pub fn websocket(&self) -> OrdrWebsocketBuilder {
+        OrdrWebsocket::builder().url(self.websocket_url())
+    }
+
+    /// Reject the next render submission with the given error code.
+    ///
+    /// Multiple calls queue up rejections for consecutive submissions.
+    pub fn reject_next_render(&self, code: ErrorCode) {
+        self.state
+            .rejections
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back(code);
+    }
+
+    /// Let the next commissioned render end with a `render_failed` event of
+    /// the given error code.
+    ///
+    /// Multiple calls queue up failures for consecutive renders.
+    pub fn fail_next_render(&self, code: ErrorCode) {
+        self.state
+            .failures
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back(code);
+    }
+
+    /// Whether commissioned renders end with a `render_failed` instead of a
+    /// `render_done` event. Defaults to `false`.
+    pub fn fail_renders(&self, fail: bool) {
+        self.state.fail_renders.store(fail, Ordering::Relaxed);
+    }
+
+    /// The amount of online render servers. Defaults to 1.
+    pub fn set_online_count(&self, count: u32) {
+        self.state.online_count.store(count, Ordering::Relaxed);
+    }
+
+    /// Respond to `GET` requests of the given path and query, e.g.
+    /// `skins?page=1`, with the given JSON body.
+    ///
+    /// Like all successful `GET` responses, the response contains an `ETag`
+    /// header and requests with a matching `If-None-Match` header are
+    /// answered with `304 Not Modified`.
+    pub fn stub(&self, uri: &str, body: impl Into<Box<str>>) {
+        self.state
+            .stubs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(Box::from(uri.trim_start_matches('/')), body.into());
+    }
+
+    /// The amount of received requests for the given path and query, e.g.
+    /// `skins?page=1`.
+    #[must_use]
+    pub fn request_count(&self, uri: &str) -> u32 {
+        self.state
+            .requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(uri.trim_start_matches('/'))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        for task in self.tasks.iter() {
+            task.abort();
+        }
+    }
+}
+
+impl MockState {
+    fn new() -> Self {
+        Self {
+            next_render_id: AtomicU32::new(1),
+            rejections: Mutex::default(),
+            failures: Mutex::default(),
+            fail_renders: AtomicBool::new(false),
+            online_count: AtomicU32::new(1),
+            events: broadcast::channel(64).0,
+            stubs: Mutex::default(),
+            requests: Mutex::default(),
+            #[cfg(feature = "vcr")]
+            replays: Mutex::default(),
+        }
+    }
+
+    #[cfg(feature = "vcr")]
+    fn replay(&self, req: &Request<Body>) -> Option<Response<Body>> {
+        let mut replays = self.replays.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut matching = replays
+            .iter_mut()
+            .filter(|replay| replay.interaction.matches(req))
+            .peekable();
+
+        while let Some(replay) = matching.next() {
+            if !replay.played || matching.peek().is_none() {
+                replay.played = true;
+
+                return Some(replay.interaction.response());
+            }
+        }
+
+        None
+    }
+
+    fn commission(&self) -> Response<Body> {
+        let rejection = self
+            .rejections
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front();
+
+        if let Some(code) = rejection {
+            let body = format!(
+                r#"{{"message":"Rejected by the mock server","errorCode":{}}}"#,
+                code.to_u8()
+            );
+
+            return json(StatusCode::BAD_REQUEST, body);
+        }
+
+        let render_id = self.next_render_id.fetch_add(1, Ordering::Relaxed);
+        let failure = self
+            .failures
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+            .or_else(|| {
+                self.fail_renders
+                    .load(Ordering::Relaxed)
+                    .then_some(ErrorCode::ReplayParsingError)
+            });
+
+        let payloads = lifecycle_payloads(render_id, MockServer::PROGRESS_STEPS, failure);
+        let events = self.events.clone();
+
+        tokio::spawn(async move {
+            for payload in payloads {
+                tokio::time::sleep(MockServer::EVENT_INTERVAL).await;
+
+                // Only fails if no websocket is connected
+                let _ = events.send(payload);
+            }
+        });
+
+        let body = format!(r#"{{"message":"Render added","renderID":{render_id}}}"#);
+
+        json(StatusCode::CREATED, body)
+    }
+
+    fn handle(&self, req: &Request<Body>) -> Response<Body> {
+        let uri = req
+            .uri()
+            .path_and_query()
+            .map_or("", |uri| uri.as_str().trim_start_matches('/'));
+
+        *self
+            .requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(Box::from(uri))
+            .or_default() += 1;
+
+        #[cfg(feature = "vcr")]
+        if let Some(response) = self.replay(req) {
+            return response;
+        }
+
+        if req.method() == Method::GET {
+            let stub = self
+                .stubs
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(uri)
+                .map(ToString::to_string);
+
+            if let Some(body) = stub {
+                return with_etag(req, body);
+            }
+        }
+
+        match (req.method(), req.uri().path().trim_matches('/')) {
+            (&Method::POST, "renders") => self.commission(),
+            (&Method::GET, "servers/onlinecount") => {
+                let count = self.online_count.load(Ordering::Relaxed);
+
+                with_etag(req, count.to_string())
+            }
+            _ => json(
+                StatusCode::NOT_FOUND,
+                r#"{"message":"Not found"}"#.to_owned(),
+            ),
+        }
+    }
+}
+
+fn json(status: StatusCode, body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    response
+}
+
+/// A `200 OK` JSON response with an `ETag` header, or `304 Not Modified` if
+/// the request's `If-None-Match` header matches it.
+fn with_etag(req: &Request<Body>, body: String) -> Response<Body> {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let etag = format!("\"{:x}\"", hasher.finish());
+
+    let mut response = if req
+        .headers()
+        .get(IF_NONE_MATCH)
+        .is_some_and(|value| value.as_bytes() == etag.as_bytes())
+    {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_MODIFIED;
+
+        response
+    } else {
+        json(StatusCode::OK, body)
+    };
+
+    response.headers_mut().insert(ETAG, etag.parse().unwrap());
+
+    response
+}
+
+async fn accept_http(listener: TcpListener, state: Arc<MockState>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let state = Arc::clone(&state);
+
+        let service = service_fn(move |req| {
+            let response = state.handle(&req);
+
+            future::ready(Ok::<_, Infallible>(response))
+        });
+
+        tokio::spawn(async move {
+            if let Err(err) = Http::new().serve_connection(stream, service).await {
+                debug!(?err, "Mock server connection failed");
+            }
+        });
+    }
+}
+
+async fn accept_websockets(listener: TcpListener, events: Sender<String>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(serve_websocket(stream, events.subscribe()));
+    }
+}
+
+async fn serve_websocket(stream: TcpStream, mut events: broadcast::Receiver<String>) {
+    let Ok(websocket) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+
+    let (mut sink, mut stream) = websocket.split();
+
+    if sink
+        .send(Message::Text(HANDSHAKE.to_owned()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    loop {
+        let next_event = pin!(events.recv());
+
+        let msg = match future::select(stream.next(), next_event).await {
+            // socket.io connect
+            Either::Left((Some(Ok(Message::Text(text))), _)) if text == "40" => {
+                r#"40{"sid":"mock"}"#.to_owned()
+            }
+            // socket.io disconnect, engine.io close, or connection loss
+            Either::Left((Some(Ok(Message::Text(text))), _)) if text == "41" || text == "1" => {
+                return
+            }
+            Either::Left((Some(Ok(Message::Close(_)) | Err(_)) | None, _))
+            | Either::Right((Err(RecvError::Closed), _)) => return,
+            Either::Left(_) | Either::Right((Err(RecvError::Lagged(_)), _)) => continue,
+            Either::Right((Ok(payload), _)) => format!("42{payload}"),
+        };
+
+        if sink.send(Message::Text(msg)).await.is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    convert::Infallible,
+    fs,
+    io::Error as IoError,
+    net::{Ipv4Addr, SocketAddr},
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use hyper::{
+    body,
+    header::{ACCEPT_ENCODING, CONTENT_TYPE, HOST},
+    server::conn::Http,
+    service::service_fn,
+    Body, Client as HyperClient, Request, Response, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{
+    client::{connector, OrdrClientBuilder},
+    dns::DnsResolver,
+    OrdrClient,
+};
+
+/// Recorded HTTP interactions that can be replayed through
+/// [`MockServer::replay`](super::MockServer::replay).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// A single recorded request and its response.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Interaction {
+    /// HTTP method of the request, e.g. `GET`.
+    pub method: Box<str>,
+    /// Path and query of the request relative to the base url, e.g. `renders?page=1`.
+    pub uri: Box<str>,
+    /// Status code of the response.
+    pub status: u16,
+    /// Content type of the response, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<Box<str>>,
+    /// Body of the response.
+    pub body: Box<str>,
+}
+
+impl Cassette {
+    /// Read a cassette from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, IoError> {
+        let bytes = fs::read(path)?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Write the cassette as JSON into a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), IoError> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+
+        fs::write(path, bytes)
+    }
+}
+
+impl Interaction {
+    pub(super) fn matches(&self, req: &Request<Body>) -> bool {
+        let uri = req
+            .uri()
+            .path_and_query()
+            .map_or("", |path| path.as_str().trim_start_matches('/'));
+
+        *self.method == *req.method().as_str() && *self.uri == *uri
+    }
+
+    pub(super) fn response(&self) -> Response<Body> {
+        let mut response = Response::new(Body::from(self.body.to_string()));
+        *response.status_mut() =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        if let Some(content_type) = self.content_type.as_deref().and_then(|s| s.parse().ok()) {
+            response.headers_mut().insert(CONTENT_TYPE, content_type);
+        }
+
+        response
+    }
+}
+
+/// A local proxy that forwards requests to o!rdr and records the
+/// interactions into a [`Cassette`].
+///
+/// # Example
+/// ```no_run
+/// use rosu_render::mock::Recorder;
+///
+/// # #[tokio::main] async fn main() {
+/// let recorder = Recorder::start("https://apis.issou.best/ordr/").await.unwrap();
+/// let client = recorder.client().build();
+///
+/// client.server_online_count().await.unwrap();
+///
+/// recorder.cassette().save("tests/cassettes/online_count.json").unwrap();
+/// # }
+/// ```
+pub struct Recorder {
+    addr: SocketAddr,
+    cassette: Arc<Mutex<Cassette>>,
+    task: JoinHandle<()>,
+}
+
+impl Recorder {
+    /// Start the proxy on a local port, forwarding requests to `upstream`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub async fn start(upstream: impl Into<String>) -> Result<Self, IoError> {
+        let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).await?;
+        let addr = listener.local_addr()?;
+
+        let mut upstream = upstream.into();
+
+        if !upstream.ends_with('/') {
+            upstream.push('/');
+        }
+
+        let proxy = Arc::new(Proxy {
+            upstream: upstream.into_boxed_str(),
+            http: HyperClient::builder().build(connector::create(
+                None,
+                DnsResolver::default(),
+                &connector::TlsConfig::new(),
+            )),
+            cassette: Arc::default(),
+        });
+
+        let cassette = Arc::clone(&proxy.cassette);
+        let task = tokio::spawn(proxy.accept(listener));
+
+        Ok(Self {
+            addr,
+            cassette,
+            task,
+        })
+    }
+
+    /// The base url of the proxy, see [`OrdrClientBuilder::base_url`].
+    #[must_use]
+    pub fn base_url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// A client builder that sends requests through the proxy.
+    pub fn client(&self) -> OrdrClientBuilder {
+        OrdrClient::builder().base_url(self.base_url())
+    }
+
+    /// The interactions that were recorded so far.
+    #[must_use]
+    pub fn cassette(&self) -> Cassette {
+        self.cassette
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct Proxy {
+    upstream: Box<str>,
+    http: HyperClient<connector::Connector>,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl Proxy {
+    async fn accept(self: Arc<Self>, listener: TcpListener) {
+        while let Ok((stream, _)) = listener.accept().await {
+            let proxy = Arc::clone(&self);
+
+            let service = service_fn(move |req| {
+                let proxy = Arc::clone(&proxy);
+
+                async move { Ok::<_, Infallible>(proxy.forward(req).await) }
+            });
+
+            tokio::spawn(async move {
+                if let Err(err) = Http::new().serve_connection(stream, service).await {
+                    debug!(?err, "Recorder connection failed");
+                }
+            });
+        }
+    }
+
+    async fn forward(&self, req: Request<Body>) -> Response<Body> {
+        let (mut parts, body) = req.into_parts();
+
+        let uri = parts
+            .uri
+            .path_and_query()
+            .map_or("", |path| path.as_str().trim_start_matches('/'))
+            .to_owned();
+
+        let Ok(upstream_uri) = format!("{}{uri}", self.upstream).parse() else {
+            return error_response(StatusCode::BAD_REQUEST, "Invalid uri");
+        };
+
+        parts.uri = upstream_uri;
+        parts.headers.remove(HOST);
+        // Record bodies in plain text
+        parts.headers.remove(ACCEPT_ENCODING);
+
+        let method = parts.method.as_str().into();
+        let req = Request::from_parts(parts, body);
+
+        let response = match self.http.request(req).await {
+            Ok(response) => response,
+            Err(err) => {
+                warn!(?err, "Failed to forward request");
+
+                return error_response(StatusCode::BAD_GATEWAY, "Upstream unavailable");
+            }
+        };
+
+        let (parts, body) = response.into_parts();
+
+        let bytes = match body::to_bytes(body).await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!(?err, "Failed to read upstream response");
+
+                return error_response(StatusCode::BAD_GATEWAY, "Upstream unavailable");
+            }
+        };
+
+        let interaction = Interaction {
+            method,
+            uri: uri.into_boxed_str(),
+            status: parts.status.as_u16(),
+            content_type: parts
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(Box::from),
+            body: String::from_utf8_lossy(&bytes).into(),
+        };
+
+        let response = interaction.response();
+
+        self.cassette
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .interactions
+            .push(interaction);
+
+        response
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    let mut response = Response::new(Body::from(format!(r#"{{"message":"{message}"}}"#)));
+    *response.status_mut() = status;
+
+    response
+}
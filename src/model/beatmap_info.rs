@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use crate::ClientError;
+
+/// Properties of a replay's beatmap to check against o!rdr's limits before
+/// commissioning a render.
+///
+/// Since replays do not contain their beatmap's length, it must be provided
+/// by the caller, e.g. from the osu! API.
+///
+/// Checked automatically through
+/// [`CommissionRender::beatmap`](crate::request::CommissionRender::beatmap).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BeatmapInfo {
+    /// The length of the beatmap as it will be rendered.
+    pub length: Duration,
+    /// The star rating of the beatmap, if known.
+    pub star_rating: Option<f32>,
+}
+
+impl BeatmapInfo {
+    /// Beatmaps longer than this are rejected by o!rdr.
+    // `Duration::from_mins` is only stable since Rust 1.91
+    #[allow(clippy::duration_suboptimal_units)]
+    pub const MAX_LENGTH: Duration = Duration::from_secs(15 * 60);
+
+    /// Beatmaps with a higher star rating are rejected by o!rdr.
+    pub const MAX_STAR_RATING: f32 = 20.0;
+
+    const DOUBLE_TIME: u32 = 1 << 6;
+    const HALF_TIME: u32 = 1 << 8;
+
+    /// Create new [`BeatmapInfo`] for a beatmap of the given length.
+    #[must_use]
+    pub const fn new(length: Duration) -> Self {
+        Self {
+            length,
+            star_rating: None,
+        }
+    }
+
+    /// Specify the star rating of the beatmap.
+    #[must_use]
+    pub const fn star_rating(mut self, star_rating: f32) -> Self {
+        self.star_rating = Some(star_rating);
+
+        self
+    }
+
+    /// Adjust the length for the speed change of the given mods bitflags,
+    /// e.g. as stored in the replay's metadata.
+    ///
+    /// Double time and nightcore speed the beatmap up by 1.5x, half time
+    /// slows it down to 0.75x.
+    #[must_use]
+    pub fn mods(mut self, mods: u32) -> Self {
+        if mods & Self::DOUBLE_TIME > 0 {
+            self.length = self.length.div_f32(1.5);
+        } else if mods & Self::HALF_TIME > 0 {
+            self.length = self.length.div_f32(0.75);
+        }
+
+        self
+    }
+
+    /// Check whether o!rdr would reject the beatmap.
+    ///
+    /// Fails with [`ClientError::BeatmapTooLong`] or [`ClientError::StarRatingTooHigh`].
+    pub fn check(&self) -> Result<(), ClientError> {
+        if self.length > Self::MAX_LENGTH {
+            return Err(ClientError::BeatmapTooLong {
+                length: self.length,
+                max: Self::MAX_LENGTH,
+            });
+        }
+
+        match self.star_rating {
+            Some(star_rating) if star_rating > Self::MAX_STAR_RATING => {
+                Err(ClientError::StarRatingTooHigh {
+                    star_rating,
+                    max: Self::MAX_STAR_RATING,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+// `Duration::from_mins` is only stable since Rust 1.91
+#[allow(clippy::duration_suboptimal_units)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::ClientError;
+
+    use super::BeatmapInfo;
+
+    #[test]
+    fn check_limits() {
+        let long = BeatmapInfo::new(Duration::from_secs(20 * 60));
+
+        assert!(matches!(
+            long.check(),
+            Err(ClientError::BeatmapTooLong { .. })
+        ));
+
+        // Double time
+        assert!(long.mods(64).check().is_ok());
+
+        // Half time
+        let short = BeatmapInfo::new(Duration::from_secs(12 * 60));
+        assert!(short.check().is_ok());
+        assert!(matches!(
+            short.mods(256).check(),
+            Err(ClientError::BeatmapTooLong { .. })
+        ));
+
+        assert!(matches!(
+            short.star_rating(21.5).check(),
+            Err(ClientError::StarRatingTooHigh { .. })
+        ));
+        assert!(short.star_rating(7.2).check().is_ok());
+    }
+}
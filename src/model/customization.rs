@@ -0,0 +1,213 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error as ThisError;
+
+/// The background of a render server's card on the o!rdr website.
+///
+/// o!rdr does not document these values so unrecognized ones are kept as
+/// [`BackgroundType::Other`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BackgroundType {
+    /// A plain background without image.
+    Plain,
+    /// o!rdr's default background image.
+    Default,
+    /// A background image chosen by the server owner.
+    Custom,
+    /// A value that is not (yet) known to this crate.
+    Other(i32),
+}
+
+impl BackgroundType {
+    #[must_use]
+    pub const fn to_i32(self) -> i32 {
+        match self {
+            Self::Plain => 0,
+            Self::Default => 1,
+            Self::Custom => 2,
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl From<i32> for BackgroundType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::Plain,
+            1 => Self::Default,
+            2 => Self::Custom,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<BackgroundType> for i32 {
+    fn from(background: BackgroundType) -> Self {
+        background.to_i32()
+    }
+}
+
+impl<'de> Deserialize<'de> for BackgroundType {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        i32::deserialize(d).map(Self::from)
+    }
+}
+
+impl Serialize for BackgroundType {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_i32(self.to_i32())
+    }
+}
+
+/// A color as specified in a [`RenderServerOptions`](super::RenderServerOptions).
+///
+/// Parses from and displays as hex string, e.g. `#1e90ff`.
+///
+/// # Example
+/// ```
+/// use rosu_render::model::Rgb;
+///
+/// let color: Rgb = "#1e90ff".parse().unwrap();
+///
+/// assert_eq!(color, Rgb::new(30, 144, 255));
+/// assert_eq!(color.to_u32(), 0x1e90ff);
+/// assert_eq!(color.to_string(), "#1e90ff");
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    #[must_use]
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parse a hex string with or without leading `#`, e.g. `#1e90ff`.
+    ///
+    /// The shorthand `#fff` is supported as well.
+    pub fn from_hex(hex: &str) -> Result<Self, ParseRgbError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(ParseRgbError(hex.into()));
+        }
+
+        // All digits are ASCII so slicing is fine
+        let parse = |digits: &str| u8::from_str_radix(digits, 16).ok();
+
+        let rgb = match digits.len() {
+            3 => {
+                let expand = |idx: usize| parse(&digits[idx..=idx]).map(|digit| digit * 0x11);
+
+                expand(0).zip(expand(1)).zip(expand(2))
+            }
+            6 => parse(&digits[..2])
+                .zip(parse(&digits[2..4]))
+                .zip(parse(&digits[4..])),
+            _ => None,
+        };
+
+        rgb.map(|((r, g), b)| Self { r, g, b })
+            .ok_or_else(|| ParseRgbError(hex.into()))
+    }
+
+    /// The lowercase hex string with leading `#`, e.g. `#1e90ff`.
+    #[must_use]
+    pub fn to_hex(self) -> String {
+        self.to_string()
+    }
+
+    /// The color as `0xRRGGBB`, e.g. for Discord embeds.
+    #[must_use]
+    pub const fn to_u32(self) -> u32 {
+        ((self.r as u32) << 16) | ((self.g as u32) << 8) | self.b as u32
+    }
+
+    /// Create a color from `0xRRGGBB`, ignoring the highest byte.
+    #[must_use]
+    pub const fn from_u32(value: u32) -> Self {
+        let [_, r, g, b] = value.to_be_bytes();
+
+        Self { r, g, b }
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = ParseRgbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s.trim())
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl Serialize for Rgb {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rgb {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let hex = Box::<str>::deserialize(d)?;
+
+        hex.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Failed to parse an [`Rgb`] color.
+#[derive(Clone, Debug, ThisError, PartialEq, Eq)]
+#[error("invalid hex color `{0}`")]
+pub struct ParseRgbError(Box<str>);
+
+/// Deserialize a color leniently so that a server with a malformed color
+/// does not fail the whole server list.
+pub(super) fn deserialize_lenient_rgb<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Option<Rgb>, D::Error> {
+    let hex = Option::<Box<str>>::deserialize(d)?;
+
+    Ok(hex.and_then(|hex| hex.parse().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BackgroundType, Rgb};
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(Rgb::from_hex("#ffffff"), Ok(Rgb::new(255, 255, 255)));
+        assert_eq!(Rgb::from_hex("1E90FF"), Ok(Rgb::new(30, 144, 255)));
+        assert_eq!(Rgb::from_hex("#f80"), Ok(Rgb::new(255, 136, 0)));
+        assert!(Rgb::from_hex("#ff").is_err());
+        assert!(Rgb::from_hex("#gggggg").is_err());
+        assert!(Rgb::from_hex("+1+2+3").is_err());
+        assert!(Rgb::from_hex("").is_err());
+
+        let color = Rgb::from_u32(0x00ab_cdef);
+        assert_eq!(color.to_hex(), "#abcdef");
+        assert_eq!(color.to_u32(), 0x00ab_cdef);
+    }
+
+    #[test]
+    fn background_roundtrip() {
+        for value in -1..4 {
+            assert_eq!(BackgroundType::from(value).to_i32(), value);
+        }
+
+        assert_eq!(BackgroundType::from(7), BackgroundType::Other(7));
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use super::{
+    event::parse_percentage, CustomSkinProcessUpdate, Event, RenderAdded, RenderDone, RenderFailed,
+    RenderProgress,
+};
+
+const COLOR_PENDING: u32 = 0x00F5_A623;
+const COLOR_SUCCESS: u32 = 0x0043_B581;
+const COLOR_FAILURE: u32 = 0x00F0_4747;
+
+const PROGRESS_BAR_LEN: u8 = 10;
+
+/// A Discord embed payload.
+///
+/// Serializes into the JSON structure expected by the Discord API.
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+pub struct Embed {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<Box<str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Box<str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Box<str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<EmbedField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<EmbedImage>,
+}
+
+/// A field of an [`Embed`].
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct EmbedField {
+    pub name: Box<str>,
+    pub value: Box<str>,
+    pub inline: bool,
+}
+
+impl EmbedField {
+    fn new(name: &str, value: impl Into<Box<str>>, inline: bool) -> Self {
+        Self {
+            name: Box::from(name),
+            value: value.into(),
+            inline,
+        }
+    }
+}
+
+/// An image of an [`Embed`].
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct EmbedImage {
+    pub url: Box<str>,
+}
+
+impl Event {
+    /// Fields describing the event to be added to an [`Embed`].
+    #[must_use]
+    pub fn to_embed_fields(&self) -> Vec<EmbedField> {
+        match self {
+            Event::RenderAdded(event) => event.to_embed_fields(),
+            Event::RenderProgress(event) => event.to_embed_fields(),
+            Event::RenderFailed(event) => event.to_embed_fields(),
+            Event::RenderDone(event) => event.to_embed_fields(),
+            Event::CustomSkinProcessUpdate(event) => event.to_embed_fields(),
+        }
+    }
+
+    /// An [`Embed`] describing the event.
+    #[must_use]
+    pub fn to_embed(&self) -> Embed {
+        match self {
+            Event::RenderAdded(event) => event.to_embed(),
+            Event::RenderProgress(event) => event.to_embed(),
+            Event::RenderFailed(event) => event.to_embed(),
+            Event::RenderDone(event) => event.to_embed(),
+            Event::CustomSkinProcessUpdate(event) => event.to_embed(),
+        }
+    }
+}
+
+impl RenderAdded {
+    /// Fields describing the event to be added to an [`Embed`].
+    #[must_use]
+    pub fn to_embed_fields(&self) -> Vec<EmbedField> {
+        vec![EmbedField::new(
+            "Render ID",
+            self.render_id.to_string(),
+            true,
+        )]
+    }
+
+    /// An [`Embed`] describing the event.
+    #[must_use]
+    pub fn to_embed(&self) -> Embed {
+        Embed {
+            title: Some(Box::from("Render queued")),
+            color: Some(COLOR_PENDING),
+            fields: self.to_embed_fields(),
+            ..Default::default()
+        }
+    }
+}
+
+impl RenderProgress {
+    /// Fields describing the event to be added to an [`Embed`].
+    #[must_use]
+    pub fn to_embed_fields(&self) -> Vec<EmbedField> {
+        vec![
+            EmbedField::new("Render ID", self.render_id.to_string(), true),
+            EmbedField::new("Renderer", self.renderer.clone(), true),
+            EmbedField::new("Progress", progress_bar(&self.progress), false),
+        ]
+    }
+
+    /// An [`Embed`] describing the event.
+    #[must_use]
+    pub fn to_embed(&self) -> Embed {
+        Embed {
+            title: Some(Box::from("Rendering")),
+            description: Some(self.description.clone()),
+            color: Some(COLOR_PENDING),
+            fields: self.to_embed_fields(),
+            ..Default::default()
+        }
+    }
+}
+
+impl RenderFailed {
+    /// Fields describing the event to be added to an [`Embed`].
+    #[must_use]
+    pub fn to_embed_fields(&self) -> Vec<EmbedField> {
+        let mut fields = vec![EmbedField::new(
+            "Render ID",
+            self.render_id.to_string(),
+            true,
+        )];
+
+        if let Some(code) = self.error_code {
+            let value = format!("{code:?} ({})", code.to_u8());
+            fields.push(EmbedField::new("Error code", value, true));
+        }
+
+        fields
+    }
+
+    /// An [`Embed`] describing the event.
+    #[must_use]
+    pub fn to_embed(&self) -> Embed {
+        Embed {
+            title: Some(Box::from("Render failed")),
+            description: Some(self.error_message.clone()),
+            color: Some(COLOR_FAILURE),
+            fields: self.to_embed_fields(),
+            ..Default::default()
+        }
+    }
+}
+
+impl RenderDone {
+    /// Fields describing the event to be added to an [`Embed`].
+    #[must_use]
+    pub fn to_embed_fields(&self) -> Vec<EmbedField> {
+        vec![
+            EmbedField::new("Render ID", self.render_id.to_string(), true),
+            EmbedField::new("Video", self.video_url.as_str(), true),
+        ]
+    }
+
+    /// An [`Embed`] describing the event.
+    #[must_use]
+    pub fn to_embed(&self) -> Embed {
+        Embed {
+            title: Some(Box::from("Render done")),
+            url: Some(Box::from(self.video_url.as_str())),
+            color: Some(COLOR_SUCCESS),
+            fields: self.to_embed_fields(),
+            thumbnail: self.video_url.thumbnail_url().map(|url| EmbedImage {
+                url: url.into_boxed_str(),
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+impl CustomSkinProcessUpdate {
+    /// Fields describing the event to be added to an [`Embed`].
+    #[must_use]
+    pub fn to_embed_fields(&self) -> Vec<EmbedField> {
+        vec![EmbedField::new("Skin ID", self.skin_id.to_string(), true)]
+    }
+
+    /// An [`Embed`] describing the event.
+    #[must_use]
+    pub fn to_embed(&self) -> Embed {
+        Embed {
+            title: Some(Box::from("Custom skin processed")),
+            color: Some(COLOR_SUCCESS),
+            fields: self.to_embed_fields(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Turns a progress description such as `"Rendering... (42%)"` into a bar.
+/// If no percentage is found, the description is returned as is.
+fn progress_bar(progress: &str) -> String {
+    let Some(percent) = parse_percentage(progress) else {
+        return progress.to_owned();
+    };
+
+    // The percentage is clamped to 0..=100 so the cast is lossless
+    #[allow(clippy::cast_sign_loss)]
+    let filled = (percent * f32::from(PROGRESS_BAR_LEN) / 100.0).round() as usize;
+    let len = usize::from(PROGRESS_BAR_LEN);
+
+    let mut bar = String::with_capacity(len * 3 + 8);
+    bar.extend(std::iter::repeat_n('▰', filled));
+    bar.extend(std::iter::repeat_n('▱', len - filled));
+    let _ = write!(bar, " {percent:.0}%");
+
+    bar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::progress_bar;
+
+    #[test]
+    fn progress_bars() {
+        assert_eq!(progress_bar("Rendering... (42%)"), "▰▰▰▰▱▱▱▱▱▱ 42%");
+        assert_eq!(progress_bar("Done."), "Done.");
+    }
+}
@@ -4,6 +4,8 @@ use serde::Deserialize;
 
 use crate::{client::error::ErrorCode, request::Requestable, ClientError};
 
+use super::{Render, ServerName, VideoUrl};
+
 /// Deserialized [`Event`](crate::model::Event) received through the websocket.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -27,24 +29,35 @@ pub struct RenderAdded {
 
 impl Requestable for RenderAdded {
     fn response_error(status: StatusCode, bytes: Bytes) -> ClientError {
-        ClientError::response_error(bytes, status.as_u16())
+        ClientError::render_submission_error(bytes, status.as_u16())
     }
 }
 
 /// Data that is received in `render_done_json` websocket events.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct RenderDone {
     /// The id of the render.
     #[serde(rename = "renderID")]
     pub render_id: u32,
     /// The url of the rendered video.
     #[serde(rename = "videoUrl")]
-    pub video_url: Box<str>,
+    pub video_url: VideoUrl,
+}
+
+impl From<&Render> for RenderDone {
+    fn from(render: &Render) -> Self {
+        Self {
+            render_id: render.id,
+            video_url: render.video_url.clone(),
+        }
+    }
 }
 
 /// Data that is received in `render_failed_json` websocket events.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct RenderFailed {
     /// The id of the render.
     #[serde(rename = "renderID")]
@@ -55,8 +68,42 @@ pub struct RenderFailed {
     pub error_message: Box<str>,
 }
 
+impl RenderFailed {
+    /// Convert into a [`ClientError::RenderFailed`] to handle it the same way
+    /// as errors of HTTP requests.
+    #[must_use]
+    pub fn as_client_error(&self) -> ClientError {
+        ClientError::RenderFailed {
+            event: self.clone(),
+        }
+    }
+
+    /// Whether commissioning the render again later may succeed.
+    ///
+    /// See [`ErrorCode::is_retryable`].
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.error_code.is_some_and(ErrorCode::is_retryable)
+    }
+
+    /// Whether commissioning the render again will fail the same way.
+    ///
+    /// See [`ErrorCode::is_permanent`].
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        self.error_code.is_some_and(ErrorCode::is_permanent)
+    }
+}
+
+impl From<RenderFailed> for ClientError {
+    fn from(event: RenderFailed) -> Self {
+        Self::RenderFailed { event }
+    }
+}
+
 /// Data that is received in `render_progress_json` websocket events.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct RenderProgress {
     /// Description of the replay.
     pub description: Box<str>,
@@ -66,15 +113,54 @@ pub struct RenderProgress {
     #[serde(rename = "renderID")]
     pub render_id: u32,
     /// Server that renders the replay.
-    pub renderer: Box<str>,
+    pub renderer: ServerName,
     /// User that commissioned the render.
     pub username: Box<str>,
 }
 
+impl From<&Render> for RenderProgress {
+    fn from(render: &Render) -> Self {
+        Self {
+            description: render.description.clone(),
+            progress: render.progress.clone(),
+            render_id: render.id,
+            renderer: render.renderer.clone(),
+            username: render.username.clone(),
+        }
+    }
+}
+
+impl RenderProgress {
+    /// Parse the completion percentage from the progress description, e.g.
+    /// `42.0` for `"Rendering... (42%)"`.
+    ///
+    /// Returns `None` if the description does not contain a percentage, e.g.
+    /// for `"Finalizing..."`.
+    #[must_use]
+    pub fn percentage(&self) -> Option<f32> {
+        parse_percentage(&self.progress)
+    }
+}
+
+pub(crate) fn parse_percentage(progress: &str) -> Option<f32> {
+    let end = progress.rfind('%')?;
+    let digits = progress[..end].trim_end();
+
+    let start = digits
+        .rfind(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map_or(0, |idx| idx + 1);
+
+    digits[start..]
+        .parse::<f32>()
+        .ok()
+        .map(|percent| percent.clamp(0.0, 100.0))
+}
+
 /// Data that is received in `custom_skin_process_update` websocket events.
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct CustomSkinProcessUpdate {
     /// The id of the skin that was processed.
     #[serde(rename = "skinId")]
-    skin_id: u32,
+    pub skin_id: u32,
 }
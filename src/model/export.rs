@@ -0,0 +1,384 @@
+use std::{
+    borrow::Cow,
+    io::{Error as IoError, Write},
+};
+
+use futures::{Stream, StreamExt};
+use serde_json::Error as JsonError;
+use thiserror::Error as ThisError;
+use time::{error::Format as FormatError, format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::ClientError;
+
+use super::{Render, RenderList, RenderSkinOption};
+
+const CSV_HEADER: [&str; 25] = [
+    "id",
+    "date",
+    "username",
+    "progress",
+    "renderer",
+    "title",
+    "description",
+    "is_bot",
+    "is_verified",
+    "video_url",
+    "map_id",
+    "map_title",
+    "map_link",
+    "map_length",
+    "replay_username",
+    "replay_difficulty",
+    "replay_mods",
+    "resolution",
+    "skin",
+    "motion_blur",
+    "render_start_time",
+    "render_end_time",
+    "upload_end_time",
+    "render_total_time",
+    "upload_total_time",
+];
+
+/// The output format of a [`RenderExporter`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row.
+    ///
+    /// Only the most relevant fields of a [`Render`] are included, its
+    /// [`RenderOptions`](super::RenderOptions) are omitted.
+    Csv,
+    /// A single JSON array containing all fields of each [`Render`].
+    Json,
+}
+
+/// Writes [`Render`]s into a writer, e.g. to archive the render history.
+///
+/// Renders can be written one by one or straight from the pages of a
+/// [`GetRenderList`](crate::request::GetRenderList) through
+/// [`RenderExporter::write_pages`]. Be sure to call [`RenderExporter::finish`]
+/// once all renders are written.
+///
+/// # Example
+/// ```no_run
+/// use std::{fs::File, io::BufWriter};
+///
+/// use rosu_render::{model::RenderExporter, OrdrClient};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ordr = OrdrClient::new();
+/// let file = BufWriter::new(File::create("renders.csv")?);
+///
+/// let mut exporter = RenderExporter::csv(file);
+/// exporter
+///     .write_pages(ordr.render_list().ordr_username("my-bot").page_size(50).pages())
+///     .await?;
+///
+/// println!("Exported {} renders", exporter.count());
+/// exporter.finish()?;
+/// # Ok(()) }
+/// ```
+pub struct RenderExporter<W> {
+    writer: W,
+    format: ExportFormat,
+    count: usize,
+}
+
+impl<W: Write> RenderExporter<W> {
+    /// Create a new exporter for the given format.
+    pub fn new(writer: W, format: ExportFormat) -> Self {
+        Self {
+            writer,
+            format,
+            count: 0,
+        }
+    }
+
+    /// Create a new exporter that writes CSV.
+    pub fn csv(writer: W) -> Self {
+        Self::new(writer, ExportFormat::Csv)
+    }
+
+    /// Create a new exporter that writes JSON.
+    pub fn json(writer: W) -> Self {
+        Self::new(writer, ExportFormat::Json)
+    }
+
+    /// The number of renders written so far.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Write a single render.
+    pub fn write_render(&mut self, render: &Render) -> Result<(), ExportError> {
+        match self.format {
+            ExportFormat::Csv => {
+                if self.count == 0 {
+                    self.write_csv_header()?;
+                }
+
+                self.write_csv_row(render)?;
+            }
+            ExportFormat::Json => {
+                let separator: &[u8] = if self.count == 0 { b"[" } else { b"," };
+                self.writer.write_all(separator)?;
+                serde_json::to_writer(&mut self.writer, render)?;
+            }
+        }
+
+        self.count += 1;
+
+        Ok(())
+    }
+
+    /// Write all given renders.
+    pub fn write_renders<'r, I>(&mut self, renders: I) -> Result<(), ExportError>
+    where
+        I: IntoIterator<Item = &'r Render>,
+    {
+        renders
+            .into_iter()
+            .try_for_each(|render| self.write_render(render))
+    }
+
+    /// Write the renders of all pages as they come in.
+    ///
+    /// Stops at the first error. Renders of previous pages are written
+    /// nonetheless, so the exporter can still be [finished](RenderExporter::finish).
+    pub async fn write_pages<S>(&mut self, pages: S) -> Result<(), ExportError>
+    where
+        S: Stream<Item = Result<RenderList, ClientError>>,
+    {
+        let mut pages = std::pin::pin!(pages);
+
+        while let Some(page) = pages.next().await {
+            self.write_renders(&page?.renders)?;
+        }
+
+        Ok(())
+    }
+
+    /// Terminate the output, flush the writer, and return it.
+    pub fn finish(mut self) -> Result<W, ExportError> {
+        match self.format {
+            ExportFormat::Csv if self.count == 0 => self.write_csv_header()?,
+            ExportFormat::Csv => {}
+            ExportFormat::Json if self.count == 0 => self.writer.write_all(b"[]")?,
+            ExportFormat::Json => self.writer.write_all(b"]")?,
+        }
+
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    fn write_csv_header(&mut self) -> Result<(), ExportError> {
+        self.write_csv_record(&CSV_HEADER)
+    }
+
+    fn write_csv_row(&mut self, render: &Render) -> Result<(), ExportError> {
+        let skin = match render.skin {
+            RenderSkinOption::Official { ref name } => Cow::Borrowed(name.as_ref()),
+            RenderSkinOption::Custom { id } => Cow::Owned(id.to_string()),
+        };
+
+        let record: [Cow<'_, str>; CSV_HEADER.len()] = [
+            render.id.to_string().into(),
+            format_datetime(render.date)?.into(),
+            render.username.as_ref().into(),
+            render.progress.as_ref().into(),
+            render.renderer.as_ref().into(),
+            render.title.as_ref().into(),
+            render.description.as_ref().into(),
+            render.is_bot.to_string().into(),
+            render.is_verified.to_string().into(),
+            render.video_url.as_str().into(),
+            render.map_id.to_string().into(),
+            render.map_title.as_ref().into(),
+            render.map_link.as_ref().into(),
+            render.map_length.to_string().into(),
+            render.replay_username.as_ref().into(),
+            render.replay_difficulty.as_ref().into(),
+            render.replay_mods.as_ref().into(),
+            render.options.resolution.as_str().into(),
+            skin,
+            render.options.motion_blur.to_string().into(),
+            format_datetime(render.render_start_time)?.into(),
+            format_datetime(render.render_end_time)?.into(),
+            format_datetime(render.upload_end_time)?.into(),
+            render.render_total_time.to_string().into(),
+            render.upload_total_time.to_string().into(),
+        ];
+
+        self.write_csv_record(&record)
+    }
+
+    fn write_csv_record<S: AsRef<str>>(&mut self, record: &[S]) -> Result<(), ExportError> {
+        for (i, field) in record.iter().enumerate() {
+            if i > 0 {
+                self.writer.write_all(b",")?;
+            }
+
+            write_csv_field(&mut self.writer, field.as_ref())?;
+        }
+
+        self.writer.write_all(b"\n")?;
+
+        Ok(())
+    }
+}
+
+fn write_csv_field<W: Write>(writer: &mut W, field: &str) -> Result<(), IoError> {
+    if !field.contains([',', '"', '\n', '\r']) {
+        return writer.write_all(field.as_bytes());
+    }
+
+    writer.write_all(b"\"")?;
+
+    for (i, part) in field.split('"').enumerate() {
+        if i > 0 {
+            writer.write_all(b"\"\"")?;
+        }
+
+        writer.write_all(part.as_bytes())?;
+    }
+
+    writer.write_all(b"\"")
+}
+
+fn format_datetime(datetime: OffsetDateTime) -> Result<String, ExportError> {
+    datetime
+        .format(&Rfc3339)
+        .map_err(|source| ExportError::Formatting { source })
+}
+
+impl RenderList {
+    /// Write the renders as CSV with a header row.
+    ///
+    /// See [`ExportFormat::Csv`] for which fields are included.
+    pub fn to_csv<W: Write>(&self, writer: W) -> Result<(), ExportError> {
+        self.export(RenderExporter::csv(writer))
+    }
+
+    /// Write the renders as JSON array.
+    pub fn to_json<W: Write>(&self, writer: W) -> Result<(), ExportError> {
+        self.export(RenderExporter::json(writer))
+    }
+
+    fn export<W: Write>(&self, mut exporter: RenderExporter<W>) -> Result<(), ExportError> {
+        exporter.write_renders(&self.renders)?;
+        exporter.finish()?;
+
+        Ok(())
+    }
+}
+
+/// Error when exporting [`Render`]s.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum ExportError {
+    #[error("Failed to request a page of renders")]
+    Client {
+        #[from]
+        source: ClientError,
+    },
+    #[error("Failed to format a datetime")]
+    Formatting {
+        #[source]
+        source: FormatError,
+    },
+    #[error("Failed to write the export")]
+    Io {
+        #[from]
+        source: IoError,
+    },
+    #[error("Failed to serialize a render")]
+    Serializing {
+        #[from]
+        source: JsonError,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use crate::model::{Render, RenderList, RenderOptions, RenderSkinOption};
+
+    fn render() -> Render {
+        let date = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        Render {
+            id: 42,
+            date,
+            username: "my-bot".into(),
+            progress: "Done.".into(),
+            renderer: "server".into(),
+            description: "Player: mrekk, Map: \"Big Black\", Accuracy: 99%".into(),
+            title: "mrekk | Big Black".into(),
+            is_bot: true,
+            is_verified: false,
+            video_url: Box::<str>::from("https://link.issou.best/pov8n").into(),
+            map_link: "https://osu.ppy.sh/b/129891".into(),
+            map_title: "Big Black".into(),
+            replay_difficulty: "WHO'S AFRAID OF THE BIG BLACK".into(),
+            replay_username: "mrekk".into(),
+            map_id: 129_891,
+            need_to_redownload: false,
+            render_start_time: date,
+            render_end_time: date,
+            upload_end_time: date,
+            render_total_time: 60,
+            upload_total_time: 5,
+            map_length: 240,
+            replay_mods: "HDDT".into(),
+            removed: false,
+            options: RenderOptions::default(),
+            skin: RenderSkinOption::Custom { id: 7 },
+        }
+    }
+
+    #[test]
+    fn csv_escapes_fields() {
+        let list = RenderList {
+            renders: vec![render()],
+            max_renders: 1,
+        };
+
+        let mut bytes = Vec::new();
+        list.to_csv(&mut bytes).unwrap();
+        let csv = String::from_utf8(bytes).unwrap();
+        let mut lines = csv.lines();
+
+        assert!(lines.next().unwrap().starts_with("id,date,username,"));
+
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("42,2023-11-14T22:13:20Z,my-bot,Done.,server,mrekk | Big Black,"));
+        assert!(row.contains(r#","Player: mrekk, Map: ""Big Black"", Accuracy: 99%","#));
+        assert!(row.contains(",1280x720,7,false,"));
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let list = RenderList {
+            renders: vec![render(), render()],
+            max_renders: 2,
+        };
+
+        let mut bytes = Vec::new();
+        list.to_json(&mut bytes).unwrap();
+        let renders: Vec<Render> = serde_json::from_slice(&bytes).unwrap();
+
+        assert_eq!(renders.len(), 2);
+        assert_eq!(renders[0].id, 42);
+        assert_eq!(renders[0].date, list.renders[0].date);
+        assert_eq!(renders[0].skin, RenderSkinOption::Custom { id: 7 });
+        assert_eq!(
+            renders[0].options.resolution,
+            list.renders[0].options.resolution
+        );
+    }
+}
@@ -1,18 +1,46 @@
+mod beatmap_info;
+mod customization;
+#[cfg(feature = "discord")]
+mod discord;
 mod event;
+#[cfg(feature = "export")]
+mod export;
 mod render;
+mod render_status;
+#[cfg(feature = "replay-meta")]
+mod replay_meta;
+mod requirements;
+mod server_name;
 mod skin_custom;
 mod skin_list;
 mod verification;
+mod video_url;
 
 pub use self::{
+    beatmap_info::BeatmapInfo,
+    customization::{BackgroundType, ParseRgbError, Rgb},
     event::{
         CustomSkinProcessUpdate, Event, RenderAdded, RenderDone, RenderFailed, RenderProgress,
     },
     render::{
-        Render, RenderList, RenderOptions, RenderResolution, RenderServer, RenderServers,
-        RenderSkinOption, ServerOnlineCount,
+        ParseSkinError, RawRender, RawRenderList, Render, RenderList, RenderListRef, RenderOptions,
+        RenderRef, RenderResolution, RenderServer, RenderServers, RenderSkinOption,
+        ServerCountEvent, ServerOnlineCount,
     },
+    render_status::RenderStatus,
+    requirements::RenderRequirements,
+    server_name::ServerName,
     skin_custom::{SkinDeleted, SkinInfo},
-    skin_list::{Skin, SkinList},
+    skin_list::{PreviewKind, Skin, SkinChange, SkinList, SkinListRef, SkinRef},
     verification::Verification,
+    video_url::VideoUrl,
 };
+
+#[cfg(feature = "replay-meta")]
+pub use self::replay_meta::{GameMode, ReplayMeta, ReplayMetaError};
+
+#[cfg(feature = "export")]
+pub use self::export::{ExportError, ExportFormat, RenderExporter};
+
+#[cfg(feature = "discord")]
+pub use self::discord::{Embed, EmbedField, EmbedImage};
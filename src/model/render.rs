@@ -1,19 +1,33 @@
 use std::{
     borrow::Cow,
     fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
 };
 
 use hyper::{body::Bytes, StatusCode};
 use serde::{
     de::{Error as DeError, IgnoredAny, MapAccess, Unexpected, Visitor},
-    Deserialize, Deserializer, Serialize,
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
 };
+use serde_json::{value::RawValue, Error as SerdeError};
+use thiserror::Error as ThisError;
 use time::OffsetDateTime;
 
-use crate::{request::Requestable, util::datetime::deserialize_datetime, ClientError};
+use crate::{
+    request::Requestable,
+    util::datetime::{deserialize_datetime, serialize_datetime},
+    ClientError,
+};
+
+use super::{
+    customization::{deserialize_lenient_rgb, BackgroundType, Rgb},
+    ServerName, VideoUrl,
+};
 
 /// A list of [`Render`].
 #[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct RenderList {
     /// Array of renders returned by the api
     pub renders: Vec<Render>,
@@ -29,15 +43,76 @@ impl Requestable for RenderList {
     }
 }
 
+/// A [`RenderList`] whose renders have not been fully deserialized yet.
+///
+/// Created through [`GetRenderList::raw`](crate::request::GetRenderList::raw).
 #[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
+pub struct RawRenderList {
+    /// Array of renders returned by the api
+    pub renders: Vec<RawRender>,
+    /// The total number of renders on o!rdr,
+    /// but if search query the total numbers of renders corresponding to that query will be used.
+    #[serde(rename = "maxRenders")]
+    pub max_renders: u32,
+}
+
+impl Requestable for RawRenderList {
+    fn response_error(status: StatusCode, bytes: Bytes) -> ClientError {
+        ClientError::response_error(bytes, status.as_u16())
+    }
+}
+
+/// [`Render`] that has not been fully deserialized yet.
+/// This lets you check if you're interested in the render and only then deserialize it.
+///
+/// Fields other than the id can be inspected by deserializing `json` into
+/// a type that only contains those fields.
+#[derive(Clone, Debug)]
+pub struct RawRender {
+    pub id: u32,
+    pub json: Box<RawValue>,
+}
+
+impl RawRender {
+    /// Deserialize into a [`Render`].
+    pub fn deserialize(&self) -> Result<Render, SerdeError> {
+        serde_json::from_str(self.json.get())
+    }
+
+    /// Deserialize into a [`RenderRef`] that borrows from this [`RawRender`].
+    pub fn deserialize_ref(&self) -> Result<RenderRef<'_>, SerdeError> {
+        serde_json::from_str(self.json.get())
+    }
+}
+
+impl<'de> Deserialize<'de> for RawRender {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct RenderId {
+            #[serde(rename = "renderID")]
+            id: u32,
+        }
+
+        let json = Box::<RawValue>::deserialize(d)?;
+        let RenderId { id } = serde_json::from_str(json.get()).map_err(DeError::custom)?;
+
+        Ok(Self { id, json })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Render {
     #[serde(rename = "renderID")]
     pub id: u32,
-    #[serde(deserialize_with = "deserialize_datetime")]
+    #[serde(
+        deserialize_with = "deserialize_datetime",
+        serialize_with = "serialize_datetime"
+    )]
     pub date: OffsetDateTime,
     pub username: Box<str>,
     pub progress: Box<str>,
-    pub renderer: Box<str>,
+    pub renderer: ServerName,
     pub description: Box<str>,
     pub title: Box<str>,
     #[serde(rename = "isBot")]
@@ -45,7 +120,7 @@ pub struct Render {
     #[serde(rename = "isVerified")]
     pub is_verified: bool,
     #[serde(rename = "videoUrl")]
-    pub video_url: Box<str>,
+    pub video_url: VideoUrl,
     #[serde(rename = "mapLink")]
     pub map_link: Box<str>,
     #[serde(rename = "mapTitle")]
@@ -58,8 +133,93 @@ pub struct Render {
     pub map_id: u32,
     #[serde(rename = "needToRedownload")]
     pub need_to_redownload: bool,
-    #[serde(rename = "motionBlur960fps")]
-    pub motion_blur: bool,
+    #[serde(
+        rename = "renderStartTime",
+        deserialize_with = "deserialize_datetime",
+        serialize_with = "serialize_datetime"
+    )]
+    pub render_start_time: OffsetDateTime,
+    #[serde(
+        rename = "renderEndTime",
+        deserialize_with = "deserialize_datetime",
+        serialize_with = "serialize_datetime"
+    )]
+    pub render_end_time: OffsetDateTime,
+    #[serde(
+        rename = "uploadEndTime",
+        deserialize_with = "deserialize_datetime",
+        serialize_with = "serialize_datetime"
+    )]
+    pub upload_end_time: OffsetDateTime,
+    #[serde(rename = "renderTotalTime")]
+    pub render_total_time: u32,
+    #[serde(rename = "uploadTotalTime")]
+    pub upload_total_time: u32,
+    #[serde(rename = "mapLength")]
+    pub map_length: u32,
+    #[serde(rename = "replayMods")]
+    pub replay_mods: Box<str>,
+    pub removed: bool,
+    #[serde(flatten)]
+    pub options: RenderOptions,
+    #[serde(flatten)]
+    pub skin: RenderSkinOption<'static>,
+}
+
+/// A [`Render`] that borrows its strings from the deserialized buffer where possible.
+///
+/// Strings that contain escape sequences can not be borrowed and are allocated
+/// regardless. Use [`RenderRef::to_owned`] to convert it into a [`Render`].
+///
+/// # Example
+/// ```no_run
+/// use rosu_render::{model::RenderListRef, OrdrClient};
+/// use hyper::Method;
+///
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = OrdrClient::new();
+/// let (_, bytes) = client.raw(Method::GET, "renders?pageSize=50").await?;
+/// let list: RenderListRef<'_> = serde_json::from_slice(&bytes)?;
+///
+/// for render in list.renders.iter().filter(|render| render.map_id == 129_891) {
+///     println!("{}", render.video_url);
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct RenderRef<'a> {
+    #[serde(rename = "renderID")]
+    pub id: u32,
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub date: OffsetDateTime,
+    #[serde(borrow)]
+    pub username: Cow<'a, str>,
+    #[serde(borrow)]
+    pub progress: Cow<'a, str>,
+    #[serde(borrow)]
+    pub renderer: Cow<'a, str>,
+    #[serde(borrow)]
+    pub description: Cow<'a, str>,
+    #[serde(borrow)]
+    pub title: Cow<'a, str>,
+    #[serde(rename = "isBot")]
+    pub is_bot: bool,
+    #[serde(rename = "isVerified")]
+    pub is_verified: bool,
+    #[serde(rename = "videoUrl", borrow)]
+    pub video_url: Cow<'a, str>,
+    #[serde(rename = "mapLink", borrow)]
+    pub map_link: Cow<'a, str>,
+    #[serde(rename = "mapTitle", borrow)]
+    pub map_title: Cow<'a, str>,
+    #[serde(rename = "replayDifficulty", borrow)]
+    pub replay_difficulty: Cow<'a, str>,
+    #[serde(rename = "replayUsername", borrow)]
+    pub replay_username: Cow<'a, str>,
+    #[serde(rename = "mapID")]
+    pub map_id: u32,
+    #[serde(rename = "needToRedownload")]
+    pub need_to_redownload: bool,
     #[serde(rename = "renderStartTime", deserialize_with = "deserialize_datetime")]
     pub render_start_time: OffsetDateTime,
     #[serde(rename = "renderEndTime", deserialize_with = "deserialize_datetime")]
@@ -72,8 +232,8 @@ pub struct Render {
     pub upload_total_time: u32,
     #[serde(rename = "mapLength")]
     pub map_length: u32,
-    #[serde(rename = "replayMods")]
-    pub replay_mods: Box<str>,
+    #[serde(rename = "replayMods", borrow)]
+    pub replay_mods: Cow<'a, str>,
     pub removed: bool,
     #[serde(flatten)]
     pub options: RenderOptions,
@@ -81,31 +241,128 @@ pub struct Render {
     pub skin: RenderSkinOption<'static>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+impl RenderRef<'_> {
+    /// Allocate all borrowed strings to create a [`Render`].
+    #[must_use]
+    pub fn to_owned(&self) -> Render {
+        Render {
+            id: self.id,
+            date: self.date,
+            username: Box::from(&*self.username),
+            progress: Box::from(&*self.progress),
+            renderer: ServerName::from(&*self.renderer),
+            description: Box::from(&*self.description),
+            title: Box::from(&*self.title),
+            is_bot: self.is_bot,
+            is_verified: self.is_verified,
+            video_url: VideoUrl::from(String::from(&*self.video_url)),
+            map_link: Box::from(&*self.map_link),
+            map_title: Box::from(&*self.map_title),
+            replay_difficulty: Box::from(&*self.replay_difficulty),
+            replay_username: Box::from(&*self.replay_username),
+            map_id: self.map_id,
+            need_to_redownload: self.need_to_redownload,
+            render_start_time: self.render_start_time,
+            render_end_time: self.render_end_time,
+            upload_end_time: self.upload_end_time,
+            render_total_time: self.render_total_time,
+            upload_total_time: self.upload_total_time,
+            map_length: self.map_length,
+            replay_mods: Box::from(&*self.replay_mods),
+            removed: self.removed,
+            options: self.options.clone(),
+            skin: self.skin.clone(),
+        }
+    }
+}
+
+/// A [`RenderList`] of [`RenderRef`]s.
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
+pub struct RenderListRef<'a> {
+    /// Array of renders returned by the api
+    #[serde(borrow)]
+    pub renders: Vec<RenderRef<'a>>,
+    /// The total number of renders on o!rdr,
+    /// but if search query the total numbers of renders corresponding to that query will be used.
+    #[serde(rename = "maxRenders")]
+    pub max_renders: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum RenderResolution {
     /// 720x480 (30fps)
-    #[serde(rename = "720x480")]
     SD480,
     /// 960x540 (30fps)
-    #[serde(rename = "960x540")]
     SD960,
     /// 1280x720 (60fps)
-    #[serde(rename = "1280x720")]
     HD720,
     /// 1920x1080 (60fps)
-    #[serde(rename = "1920x1080")]
     HD1080,
+    /// 3840x2160 (60fps)
+    ///
+    /// Only available on UHD capable servers.
+    UHD2160,
+    /// A resolution that is not (yet) known to this crate.
+    Other(Box<str>),
 }
 
 impl RenderResolution {
     #[must_use]
-    pub fn as_str(self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::SD480 => "720x480",
             Self::SD960 => "960x540",
             Self::HD720 => "1280x720",
             Self::HD1080 => "1920x1080",
+            Self::UHD2160 => "3840x2160",
+            Self::Other(resolution) => resolution,
+        }
+    }
+
+    /// Whether the resolution requires a UHD capable server.
+    #[must_use]
+    pub fn is_uhd(&self) -> bool {
+        matches!(self, Self::UHD2160)
+    }
+}
+
+impl From<&str> for RenderResolution {
+    fn from(resolution: &str) -> Self {
+        match resolution {
+            "720x480" => Self::SD480,
+            "960x540" => Self::SD960,
+            "1280x720" => Self::HD720,
+            "1920x1080" => Self::HD1080,
+            "3840x2160" => Self::UHD2160,
+            other => Self::Other(Box::from(other)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RenderResolution {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct ResolutionVisitor;
+
+        impl Visitor<'_> for ResolutionVisitor {
+            type Value = RenderResolution;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+                f.write_str("a resolution string")
+            }
+
+            fn visit_str<E: DeError>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(RenderResolution::from(v))
+            }
         }
+
+        d.deserialize_str(ResolutionVisitor)
+    }
+}
+
+impl Serialize for RenderResolution {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(self.as_str())
     }
 }
 
@@ -118,6 +375,9 @@ impl Display for RenderResolution {
 /// Customize danser settings when rendering.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RenderOptions {
+    /// The resolution of the video, defaults to 1280x720.
+    ///
+    /// [`RenderResolution::UHD2160`] requires a UHD capable server.
     pub resolution: RenderResolution,
     /// The global volume for the video, in percent, from 0 to 100.
     #[serde(rename = "globalVolume")]
@@ -267,6 +527,11 @@ pub struct RenderOptions {
     /// Ignores fail in the replay or not.
     #[serde(rename = "ignoreFail")]
     pub ignore_fail: bool,
+    /// Render with motion blur at 960fps.
+    ///
+    /// Requires a server that is capable of motion blur.
+    #[serde(rename = "motionBlur960fps")]
+    pub motion_blur: bool,
 }
 
 impl Default for RenderOptions {
@@ -322,6 +587,7 @@ impl Default for RenderOptions {
             show_strain_graph: false,
             show_slider_breaks: false,
             ignore_fail: false,
+            motion_blur: false,
         }
     }
 }
@@ -332,6 +598,81 @@ pub enum RenderSkinOption<'a> {
     Custom { id: u32 },
 }
 
+impl<'a> RenderSkinOption<'a> {
+    /// Parse user input, e.g. the argument of a chat command.
+    ///
+    /// `custom:<id>` and plain numbers are custom skin IDs, anything else is
+    /// the name of an official skin. Surrounding whitespace is ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use rosu_render::model::RenderSkinOption;
+    ///
+    /// assert_eq!(RenderSkinOption::parse("custom:812"), Ok(RenderSkinOption::Custom { id: 812 }));
+    /// assert_eq!(RenderSkinOption::parse("812"), Ok(RenderSkinOption::Custom { id: 812 }));
+    /// assert_eq!(RenderSkinOption::parse("whitecat"), Ok(RenderSkinOption::from("whitecat")));
+    /// ```
+    pub fn parse(input: &'a str) -> Result<Self, ParseSkinError> {
+        let input = input.trim();
+
+        if input.is_empty() {
+            return Err(ParseSkinError::Empty);
+        }
+
+        let custom_id = input
+            .get(..7)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("custom:"))
+            .map(|_| input[7..].trim());
+
+        if let Some(id) = custom_id {
+            return id
+                .parse()
+                .map(|id| Self::Custom { id })
+                .map_err(|_| ParseSkinError::InvalidCustomId(Box::from(id)));
+        }
+
+        if input.bytes().all(|byte| byte.is_ascii_digit()) {
+            return input
+                .parse()
+                .map(|id| Self::Custom { id })
+                .map_err(|_| ParseSkinError::InvalidCustomId(Box::from(input)));
+        }
+
+        Ok(Self::Official {
+            name: Cow::Borrowed(input),
+        })
+    }
+
+    /// Convert into a [`RenderSkinOption`] that does not borrow any data.
+    #[must_use]
+    pub fn into_owned(self) -> RenderSkinOption<'static> {
+        match self {
+            Self::Official { name } => RenderSkinOption::Official {
+                name: Cow::Owned(name.into_owned()),
+            },
+            Self::Custom { id } => RenderSkinOption::Custom { id },
+        }
+    }
+}
+
+impl FromStr for RenderSkinOption<'static> {
+    type Err = ParseSkinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RenderSkinOption::parse(s).map(RenderSkinOption::into_owned)
+    }
+}
+
+/// Error when parsing a [`RenderSkinOption`] through [`RenderSkinOption::parse`].
+#[derive(Clone, Debug, ThisError, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseSkinError {
+    #[error("No skin was specified")]
+    Empty,
+    #[error("Invalid custom skin ID `{0}`")]
+    InvalidCustomId(Box<str>),
+}
+
 impl<'a> Default for RenderSkinOption<'a> {
     fn default() -> Self {
         Self::Official {
@@ -360,6 +701,25 @@ macro_rules! impl_from_name {
 
 impl_from_name!(&'a str, &'a String, String, Cow<'a, str>);
 
+impl Serialize for RenderSkinOption<'_> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut map = s.serialize_map(Some(2))?;
+
+        match self {
+            Self::Official { name } => {
+                map.serialize_entry("skin", name)?;
+                map.serialize_entry("customSkin", &false)?;
+            }
+            Self::Custom { id } => {
+                map.serialize_entry("skin", &id.to_string())?;
+                map.serialize_entry("customSkin", &true)?;
+            }
+        }
+
+        map.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for RenderSkinOption<'static> {
     fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
         struct SkinVisitor;
@@ -409,7 +769,8 @@ impl<'de> Deserialize<'de> for RenderSkinOption<'static> {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct RenderServers {
     pub servers: Vec<RenderServer>,
 }
@@ -420,12 +781,17 @@ impl Requestable for RenderServers {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct RenderServer {
     pub enabled: bool,
-    #[serde(rename = "lastSeen", deserialize_with = "deserialize_datetime")]
+    #[serde(
+        rename = "lastSeen",
+        deserialize_with = "deserialize_datetime",
+        serialize_with = "serialize_datetime"
+    )]
     pub last_seen: OffsetDateTime,
-    pub name: Box<str>,
+    pub name: ServerName,
     pub priority: f32,
     #[serde(rename = "oldScore")]
     pub old_score: f32,
@@ -460,12 +826,14 @@ pub struct RenderServer {
     pub customization: RenderServerOptions,
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct RenderServerOptions {
-    #[serde(rename = "textColor")]
-    pub text_color: Box<str>,
+    /// `None` if the server specified no valid color.
+    #[serde(rename = "textColor", deserialize_with = "deserialize_lenient_rgb")]
+    pub text_color: Option<Rgb>,
     #[serde(rename = "backgroundType")]
-    pub background_type: i32,
+    pub background_type: BackgroundType,
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -476,3 +844,60 @@ impl Requestable for ServerOnlineCount {
         ClientError::response_error(bytes, status.as_u16())
     }
 }
+
+/// The online server count crossed a threshold.
+///
+/// Returned by [`ServerCountWatcher::next`](crate::request::ServerCountWatcher::next).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ServerCountEvent {
+    /// The count dropped from at least `threshold` to less.
+    Dropped { threshold: u32, count: u32 },
+    /// The count rose from less than `threshold` to at least that.
+    Recovered { threshold: u32, count: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseSkinError, RenderResolution, RenderSkinOption};
+
+    #[test]
+    fn parse_skin_option() {
+        assert_eq!(
+            " Custom: 42 ".parse(),
+            Ok(RenderSkinOption::Custom { id: 42 })
+        );
+        assert_eq!(
+            "Danser Default".parse(),
+            Ok(RenderSkinOption::from("Danser Default"))
+        );
+        assert_eq!(
+            "custom:abc".parse::<RenderSkinOption<'_>>(),
+            Err(ParseSkinError::InvalidCustomId("abc".into()))
+        );
+        assert_eq!(
+            "99999999999".parse::<RenderSkinOption<'_>>(),
+            Err(ParseSkinError::InvalidCustomId("99999999999".into()))
+        );
+        assert_eq!(
+            "  ".parse::<RenderSkinOption<'_>>(),
+            Err(ParseSkinError::Empty)
+        );
+    }
+
+    #[test]
+    fn resolution_roundtrip() {
+        for resolution in [
+            RenderResolution::SD480,
+            RenderResolution::SD960,
+            RenderResolution::HD720,
+            RenderResolution::HD1080,
+            RenderResolution::UHD2160,
+            RenderResolution::Other("2560x1440".into()),
+        ] {
+            let json = serde_json::to_string(&resolution).unwrap();
+            let deserialized: RenderResolution = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(deserialized, resolution);
+        }
+    }
+}
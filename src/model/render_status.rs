@@ -0,0 +1,118 @@
+use super::{event::parse_percentage, Render, VideoUrl};
+
+/// The state of a render, classified from its progress description.
+///
+/// Retrieved through [`OrdrClient::render_status`](crate::OrdrClient::render_status).
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum RenderStatus {
+    /// Waiting for a render server to pick up the render.
+    Queued,
+    /// A render server is working on the render, e.g. rendering or uploading.
+    InProgress {
+        /// The progress description, e.g. `"Rendering... (42%)"`.
+        progress: Box<str>,
+        /// The percentage parsed from the progress description.
+        percentage: Option<f32>,
+    },
+    /// The video is available.
+    Done { video_url: VideoUrl },
+    /// The render failed.
+    Failed {
+        /// The progress description containing the error.
+        message: Box<str>,
+    },
+    /// The render was removed.
+    Removed,
+    /// There is no render with the requested id.
+    NotFound,
+}
+
+impl RenderStatus {
+    /// Whether the render will not change its status anymore.
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, Self::Queued | Self::InProgress { .. })
+    }
+
+    /// Whether the render finished without classifying it in full.
+    pub(crate) fn is_done(render: &Render) -> bool {
+        !render.removed && Self::is_done_progress(&render.progress, &render.video_url)
+    }
+
+    fn is_done_progress(progress: &str, video_url: &VideoUrl) -> bool {
+        progress
+            .get(..4)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("done"))
+            && !video_url.as_str().is_empty()
+    }
+
+    fn classify(progress: Box<str>, video_url: VideoUrl) -> Self {
+        let lowercase = progress.to_ascii_lowercase();
+
+        if Self::is_done_progress(&progress, &video_url) {
+            Self::Done { video_url }
+        } else if lowercase.starts_with("error") || lowercase.contains("failed") {
+            Self::Failed { message: progress }
+        } else if lowercase.contains("queue") {
+            Self::Queued
+        } else {
+            let percentage = parse_percentage(&progress);
+
+            Self::InProgress {
+                progress,
+                percentage,
+            }
+        }
+    }
+}
+
+impl From<Render> for RenderStatus {
+    fn from(render: Render) -> Self {
+        if render.removed {
+            Self::Removed
+        } else {
+            Self::classify(render.progress, render.video_url)
+        }
+    }
+}
+
+impl From<Option<Render>> for RenderStatus {
+    fn from(render: Option<Render>) -> Self {
+        render.map_or(Self::NotFound, Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::VideoUrl;
+
+    use super::RenderStatus;
+
+    fn classify(progress: &str, video_url: &str) -> RenderStatus {
+        RenderStatus::classify(Box::from(progress), VideoUrl::from(Box::from(video_url)))
+    }
+
+    #[test]
+    fn classify_progress() {
+        assert_eq!(classify("In queue...", ""), RenderStatus::Queued);
+        assert_eq!(
+            classify("Rendering... (42%)", ""),
+            RenderStatus::InProgress {
+                progress: Box::from("Rendering... (42%)"),
+                percentage: Some(42.0),
+            }
+        );
+        assert_eq!(
+            classify("Done.", "https://link.issou.best/a"),
+            RenderStatus::Done {
+                video_url: VideoUrl::from(Box::from("https://link.issou.best/a")),
+            }
+        );
+        assert!(matches!(
+            classify("Error: replay is corrupted", ""),
+            RenderStatus::Failed { .. }
+        ));
+        assert_eq!(RenderStatus::from(None), RenderStatus::NotFound);
+    }
+}
@@ -0,0 +1,200 @@
+use std::str::{from_utf8 as str_from_utf8, Utf8Error};
+
+use thiserror::Error as ThisError;
+
+/// The game mode of a replay.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GameMode {
+    Osu,
+    Taiko,
+    Catch,
+    Mania,
+}
+
+impl TryFrom<u8> for GameMode {
+    type Error = ReplayMetaError;
+
+    fn try_from(mode: u8) -> Result<Self, Self::Error> {
+        match mode {
+            0 => Ok(Self::Osu),
+            1 => Ok(Self::Taiko),
+            2 => Ok(Self::Catch),
+            3 => Ok(Self::Mania),
+            _ => Err(ReplayMetaError::InvalidMode(mode)),
+        }
+    }
+}
+
+/// Metadata of an `.osr` replay file.
+///
+/// Only the header of the replay is parsed, the compressed replay data is skipped.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReplayMeta {
+    /// The game mode of the replay.
+    pub mode: GameMode,
+    /// The osu! version in which the replay was created.
+    pub version: u32,
+    /// MD5 hash of the beatmap.
+    pub beatmap_hash: Box<str>,
+    /// The name of the player.
+    pub player_name: Box<str>,
+    /// MD5 hash of the replay.
+    pub replay_hash: Box<str>,
+    /// The total score.
+    pub score: u32,
+    /// The maximum combo.
+    pub max_combo: u16,
+    /// The mods as bitflags.
+    pub mods: u32,
+}
+
+/// Error when parsing [`ReplayMeta`].
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum ReplayMetaError {
+    #[error("Invalid game mode {0}")]
+    InvalidMode(u8),
+    #[error("Invalid string indicator {0:#x}")]
+    InvalidStringIndicator(u8),
+    #[error("Failed to decode string as UTF-8")]
+    InvalidUtf8(#[from] Utf8Error),
+    #[error("The replay was specified as url instead of as file")]
+    MissingReplayFile,
+    #[error("Unexpected end of replay file")]
+    UnexpectedEof,
+}
+
+impl ReplayMeta {
+    /// Parse the metadata of an `.osr` replay file.
+    pub fn parse(bytes: &[u8]) -> Result<Self, ReplayMetaError> {
+        let mut reader = Reader { bytes };
+
+        let mode = GameMode::try_from(reader.u8()?)?;
+        let version = reader.u32()?;
+        let beatmap_hash = reader.string()?;
+        let player_name = reader.string()?;
+        let replay_hash = reader.string()?;
+
+        // count300, count100, count50, count_geki, count_katu, count_miss
+        reader.skip(6 * 2)?;
+
+        let score = reader.u32()?;
+        let max_combo = reader.u16()?;
+
+        // perfect
+        reader.skip(1)?;
+
+        let mods = reader.u32()?;
+
+        Ok(Self {
+            mode,
+            version,
+            beatmap_hash,
+            player_name,
+            replay_hash,
+            score,
+            max_combo,
+            mods,
+        })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], ReplayMetaError> {
+        let (chunk, rest) = self
+            .bytes
+            .split_first_chunk::<N>()
+            .ok_or(ReplayMetaError::UnexpectedEof)?;
+
+        self.bytes = rest;
+
+        Ok(*chunk)
+    }
+
+    fn take_slice(&mut self, len: usize) -> Result<&'a [u8], ReplayMetaError> {
+        if self.bytes.len() < len {
+            return Err(ReplayMetaError::UnexpectedEof);
+        }
+
+        let (slice, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(slice)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), ReplayMetaError> {
+        self.take_slice(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, ReplayMetaError> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    fn u16(&mut self) -> Result<u16, ReplayMetaError> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, ReplayMetaError> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn uleb128(&mut self) -> Result<usize, ReplayMetaError> {
+        let mut value = 0;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+            value |= usize::from(byte & 0x7F) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+
+            shift += 7;
+
+            if shift >= usize::BITS {
+                return Err(ReplayMetaError::UnexpectedEof);
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<Box<str>, ReplayMetaError> {
+        match self.u8()? {
+            0x00 => Ok(Box::default()),
+            0x0B => {
+                let len = self.uleb128()?;
+                let bytes = self.take_slice(len)?;
+
+                Ok(Box::from(str_from_utf8(bytes)?))
+            }
+            other => Err(ReplayMetaError::InvalidStringIndicator(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GameMode, ReplayMeta, ReplayMetaError};
+
+    #[test]
+    fn parse_replay() {
+        let bytes = std::fs::read("./assets/2283307549.osr").unwrap();
+        let meta = ReplayMeta::parse(&bytes).unwrap();
+
+        assert_eq!(meta.mode, GameMode::Osu);
+        assert_eq!(&*meta.beatmap_hash, "d57d77d4ebe8b09ddc7d868e5f38e038");
+        assert_eq!(&*meta.player_name, "chocomint");
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = std::fs::read("./assets/2283307549.osr").unwrap();
+        let err = ReplayMeta::parse(&bytes[..40]).unwrap_err();
+
+        assert!(matches!(err, ReplayMetaError::UnexpectedEof));
+    }
+}
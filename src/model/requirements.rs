@@ -0,0 +1,103 @@
+use super::{RenderOptions, RenderServer, RenderServers};
+
+/// Server capabilities that are required for a render.
+///
+/// Can be checked against the live server list through
+/// [`OrdrClient::check_capability`](crate::OrdrClient::check_capability) or
+/// automatically before commissioning through
+/// [`CommissionRender::requirements`](crate::request::CommissionRender::requirements).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RenderRequirements {
+    /// The render requires a server that is capable of motion blur.
+    pub motion_blur: bool,
+    /// The render requires a server that is capable of UHD resolutions.
+    pub uhd: bool,
+}
+
+impl RenderRequirements {
+    /// Create new [`RenderRequirements`] without any requirement.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            motion_blur: false,
+            uhd: false,
+        }
+    }
+
+    /// Create [`RenderRequirements`] based on the given [`RenderOptions`].
+    #[must_use]
+    pub fn from_options(options: &RenderOptions) -> Self {
+        Self::new()
+            .motion_blur(options.motion_blur)
+            .uhd(options.resolution.is_uhd())
+    }
+
+    /// Require a server that is capable of motion blur.
+    #[must_use]
+    pub const fn motion_blur(mut self, motion_blur: bool) -> Self {
+        self.motion_blur = motion_blur;
+
+        self
+    }
+
+    /// Require a server that is capable of UHD resolutions.
+    #[must_use]
+    pub const fn uhd(mut self, uhd: bool) -> Self {
+        self.uhd = uhd;
+
+        self
+    }
+}
+
+impl RenderServer {
+    /// Whether the server is currently available and fulfills the given requirements.
+    #[must_use]
+    pub fn can_fulfill(&self, requirements: &RenderRequirements) -> bool {
+        self.enabled
+            && !self.status.eq_ignore_ascii_case("offline")
+            && (!requirements.motion_blur || self.motion_blur_capable)
+            && (!requirements.uhd || self.uhd_capable)
+    }
+}
+
+impl RenderServers {
+    /// Whether any server can currently fulfill the given requirements.
+    #[must_use]
+    pub fn can_fulfill(&self, requirements: &RenderRequirements) -> bool {
+        self.servers
+            .iter()
+            .any(|server| server.can_fulfill(requirements))
+    }
+
+    /// The servers that can currently fulfill the given requirements,
+    /// fastest first based on their average total render time.
+    ///
+    /// Servers without average time are put last.
+    #[must_use]
+    pub fn ranked(&self, requirements: &RenderRequirements) -> Vec<&RenderServer> {
+        let mut servers: Vec<_> = self
+            .servers
+            .iter()
+            .filter(|server| server.can_fulfill(requirements))
+            .collect();
+
+        servers.sort_by(|a, b| {
+            let key = |server: &RenderServer| {
+                (server.total_avg_time > 0.0).then_some(server.total_avg_time)
+            };
+
+            match (key(a), key(b)) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            }
+        });
+
+        servers
+    }
+
+    /// The fastest server that can currently fulfill the given requirements.
+    #[must_use]
+    pub fn fastest(&self, requirements: &RenderRequirements) -> Option<&RenderServer> {
+        self.ranked(requirements).first().copied()
+    }
+}
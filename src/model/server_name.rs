@@ -0,0 +1,113 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::{Hash, Hasher},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The name of a render server.
+///
+/// Comparing and hashing ignores ASCII case so that the renderer of a
+/// [`Render`](super::Render) or [`RenderProgress`](super::RenderProgress)
+/// can be reliably joined against [`RenderServer::name`](super::RenderServer::name).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ServerName {
+    name: Box<str>,
+}
+
+impl ServerName {
+    /// Return the name as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq for ServerName {
+    fn eq(&self, other: &Self) -> bool {
+        self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Eq for ServerName {}
+
+impl PartialEq<str> for ServerName {
+    fn eq(&self, other: &str) -> bool {
+        self.name.eq_ignore_ascii_case(other)
+    }
+}
+
+impl PartialEq<&str> for ServerName {
+    fn eq(&self, other: &&str) -> bool {
+        self.name.eq_ignore_ascii_case(other)
+    }
+}
+
+impl Hash for ServerName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.name.bytes() {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+
+        // Same as `str` to prevent prefix collisions in composite keys
+        state.write_u8(0xff);
+    }
+}
+
+impl AsRef<str> for ServerName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for ServerName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Box<str>> for ServerName {
+    fn from(name: Box<str>) -> Self {
+        Self { name }
+    }
+}
+
+impl From<String> for ServerName {
+    fn from(name: String) -> Self {
+        Self {
+            name: name.into_boxed_str(),
+        }
+    }
+}
+
+impl From<&str> for ServerName {
+    fn from(name: &str) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl From<ServerName> for Box<str> {
+    fn from(name: ServerName) -> Self {
+        name.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::ServerName;
+
+    #[test]
+    fn ignore_case() {
+        let name = ServerName::from("Some-Server");
+        assert_eq!(name, ServerName::from("some-server"));
+        assert_eq!(name, "SOME-SERVER");
+        assert_ne!(name, ServerName::from("other-server"));
+
+        let names: HashSet<_> = [name, ServerName::from("some-SERVER")].into();
+        assert_eq!(names.len(), 1);
+        assert_eq!(names.iter().next().unwrap().as_str(), "Some-Server");
+    }
+}
@@ -1,10 +1,11 @@
 use hyper::{body::Bytes, StatusCode};
 use serde::Deserialize;
 
-use crate::{request::Requestable, ClientError};
+use crate::{request::Requestable, util::json, ClientError};
 
 /// Information about a custom skin.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct SkinInfo {
     /// The name of the skin.
     #[serde(rename = "skinName")]
@@ -20,12 +21,9 @@ pub struct SkinInfo {
 impl Requestable for SkinInfo {
     fn response_error(status: StatusCode, bytes: Bytes) -> ClientError {
         if status == StatusCode::NOT_FOUND {
-            match serde_json::from_slice(&bytes) {
+            match json::from_bytes(&bytes) {
                 Ok(error) => ClientError::SkinDeleted { error },
-                Err(source) => ClientError::Parsing {
-                    body: bytes.into(),
-                    source,
-                },
+                Err(err) => err,
             }
         } else {
             ClientError::response_error(bytes, status.as_u16())
@@ -34,6 +32,7 @@ impl Requestable for SkinInfo {
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct SkinDeleted {
     /// true if found, false if not.
     pub found: bool,
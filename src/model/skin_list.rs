@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use hyper::{body::Bytes, StatusCode};
 use serde::Deserialize;
 
@@ -5,6 +7,7 @@ use crate::{request::Requestable, ClientError};
 
 /// A list of [`Skin`].
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct SkinList {
     /// Array of skins returned by the api.
     pub skins: Vec<Skin>,
@@ -22,6 +25,7 @@ impl Requestable for SkinList {
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
 pub struct Skin {
     pub skin: Box<str>,
     pub presentation_name: Box<str>,
@@ -36,3 +40,143 @@ pub struct Skin {
     pub alphabetical_id: u32,
     pub times_used: u32,
 }
+
+impl Skin {
+    /// Whether both skins are the same apart from how often they were used.
+    pub(crate) fn same_content(&self, other: &Self) -> bool {
+        let Self {
+            skin,
+            presentation_name,
+            url,
+            high_res_preview,
+            low_res_preview,
+            grid_preview,
+            id,
+            author,
+            modified,
+            version,
+            alphabetical_id,
+            times_used: _,
+        } = self;
+
+        *skin == other.skin
+            && *presentation_name == other.presentation_name
+            && *url == other.url
+            && *high_res_preview == other.high_res_preview
+            && *low_res_preview == other.low_res_preview
+            && *grid_preview == other.grid_preview
+            && *id == other.id
+            && *author == other.author
+            && *modified == other.modified
+            && *version == other.version
+            && *alphabetical_id == other.alphabetical_id
+    }
+
+    /// The url of the given preview image.
+    #[must_use]
+    pub fn preview_url(&self, kind: PreviewKind) -> &str {
+        match kind {
+            PreviewKind::HighRes => &self.high_res_preview,
+            PreviewKind::LowRes => &self.low_res_preview,
+            PreviewKind::Grid => &self.grid_preview,
+        }
+    }
+}
+
+/// A [`Skin`] that borrows its strings from the deserialized buffer where possible.
+///
+/// Strings that contain escape sequences can not be borrowed and are allocated
+/// regardless. Use [`SkinRef::to_owned`] to convert it into a [`Skin`].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
+pub struct SkinRef<'a> {
+    #[serde(borrow)]
+    pub skin: Cow<'a, str>,
+    #[serde(borrow)]
+    pub presentation_name: Cow<'a, str>,
+    #[serde(borrow)]
+    pub url: Cow<'a, str>,
+    #[serde(borrow)]
+    pub high_res_preview: Cow<'a, str>,
+    #[serde(borrow)]
+    pub low_res_preview: Cow<'a, str>,
+    #[serde(borrow)]
+    pub grid_preview: Cow<'a, str>,
+    pub id: u32,
+    #[serde(borrow)]
+    pub author: Cow<'a, str>,
+    pub modified: bool,
+    #[serde(borrow)]
+    pub version: Cow<'a, str>,
+    pub alphabetical_id: u32,
+    pub times_used: u32,
+}
+
+impl SkinRef<'_> {
+    /// Allocate all borrowed strings to create a [`Skin`].
+    #[must_use]
+    pub fn to_owned(&self) -> Skin {
+        Skin {
+            skin: Box::from(&*self.skin),
+            presentation_name: Box::from(&*self.presentation_name),
+            url: Box::from(&*self.url),
+            high_res_preview: Box::from(&*self.high_res_preview),
+            low_res_preview: Box::from(&*self.low_res_preview),
+            grid_preview: Box::from(&*self.grid_preview),
+            id: self.id,
+            author: Box::from(&*self.author),
+            modified: self.modified,
+            version: Box::from(&*self.version),
+            alphabetical_id: self.alphabetical_id,
+            times_used: self.times_used,
+        }
+    }
+}
+
+/// A [`SkinList`] of [`SkinRef`]s.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema-audit", serde(deny_unknown_fields))]
+pub struct SkinListRef<'a> {
+    /// Array of skins returned by the api.
+    #[serde(borrow)]
+    pub skins: Vec<SkinRef<'a>>,
+    /// The total number of skins that are available on o!rdr,
+    /// but if search query the total numbers of renders corresponding to that query will be used.
+    #[serde(rename = "maxSkins")]
+    pub max_skins: u32,
+}
+
+/// A change in the skin list, see [`GetSkinList::watch`](crate::request::GetSkinList::watch).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SkinChange {
+    /// The skin was not in the list before.
+    Added(Skin),
+    /// The skin was in the list before but its content changed, e.g. its
+    /// [`version`](Skin::version) or [`presentation_name`](Skin::presentation_name).
+    ///
+    /// Changes of [`times_used`](Skin::times_used) are not considered.
+    Updated { old: Skin, new: Skin },
+}
+
+impl SkinChange {
+    /// The current state of the changed skin.
+    #[must_use]
+    pub fn skin(&self) -> &Skin {
+        match self {
+            Self::Added(skin) | Self::Updated { new: skin, .. } => skin,
+        }
+    }
+}
+
+/// The preview images of a [`Skin`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PreviewKind {
+    /// [`Skin::high_res_preview`]
+    HighRes,
+    /// [`Skin::low_res_preview`]
+    LowRes,
+    /// [`Skin::grid_preview`]
+    Grid,
+}
@@ -1,5 +1,7 @@
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 
+use serde::{Deserialize, Deserializer};
+
 /// Specifying your verification key will allow you to bypass the default ratelimit of one render per five minutes.
 /// Alternatively, you can specify a dev mode to simulate events and not spam the real backend with requests while testing things.
 #[derive(Clone)]
@@ -25,6 +27,27 @@ impl Verification {
             Self::DevModeWsFail => "devmode_wsfail",
         }
     }
+
+    /// Whether this is one of the dev mode verifications which only simulate renders.
+    #[must_use]
+    pub fn is_dev_mode(&self) -> bool {
+        !matches!(self, Self::Key(_))
+    }
+}
+
+/// Deserializes from the same string as [`Verification::as_str`] so that
+/// dev modes are recognized and any other string is treated as key.
+impl<'de> Deserialize<'de> for Verification {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let verification = match String::deserialize(d)?.as_str() {
+            "devmode_success" => Self::DevModeSuccess,
+            "devmode_fail" => Self::DevModeFail,
+            "devmode_wsfail" => Self::DevModeWsFail,
+            key => Self::Key(Box::from(key)),
+        };
+
+        Ok(verification)
+    }
 }
 
 impl Debug for Verification {
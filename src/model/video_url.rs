@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The url of a rendered video.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct VideoUrl {
+    url: Box<str>,
+}
+
+impl VideoUrl {
+    const THUMBNAIL_BASE_URL: &'static str = "https://ordr-renders.issou.best/thumbnails/";
+    const RENDERS_HOST: &'static str = "ordr-renders.issou.best";
+    const FILE_PREFIX: &'static str = "ordr-render-";
+
+    /// Return the url as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.url
+    }
+
+    /// Parse the render id if the url points to a video file on o!rdr's
+    /// render host, e.g. `https://ordr-renders.issou.best/render/ordr-render-123456.mp4`.
+    ///
+    /// Shortlinks such as `https://link.issou.best/pov8n` do not contain the render id.
+    #[must_use]
+    pub fn render_id(&self) -> Option<u32> {
+        let url = Url::parse(&self.url).ok()?;
+
+        if url.host_str() != Some(Self::RENDERS_HOST) {
+            return None;
+        }
+
+        let segment = url.path_segments()?.next_back()?;
+        let stem = segment.split_once('.').map_or(segment, |(stem, _)| stem);
+        let digits = stem.strip_prefix(Self::FILE_PREFIX)?;
+
+        if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+
+        digits.parse().ok()
+    }
+
+    /// Derive the url of the video's thumbnail.
+    ///
+    /// Requires the render id to be embedded in the url, see [`VideoUrl::render_id`].
+    #[must_use]
+    pub fn thumbnail_url(&self) -> Option<String> {
+        self.render_id()
+            .map(|render_id| format!("{}{render_id}.jpg", Self::THUMBNAIL_BASE_URL))
+    }
+}
+
+impl AsRef<str> for VideoUrl {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for VideoUrl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Box<str>> for VideoUrl {
+    fn from(url: Box<str>) -> Self {
+        Self { url }
+    }
+}
+
+impl From<String> for VideoUrl {
+    fn from(url: String) -> Self {
+        Self {
+            url: url.into_boxed_str(),
+        }
+    }
+}
+
+impl From<VideoUrl> for Box<str> {
+    fn from(url: VideoUrl) -> Self {
+        url.url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VideoUrl;
+
+    #[test]
+    fn render_id() {
+        let url = VideoUrl::from(String::from(
+            "https://ordr-renders.issou.best/render/ordr-render-123456.mp4",
+        ));
+        assert_eq!(url.render_id(), Some(123_456));
+        assert_eq!(
+            url.thumbnail_url().as_deref(),
+            Some("https://ordr-renders.issou.best/thumbnails/123456.jpg")
+        );
+
+        let url = VideoUrl::from(String::from("https://link.issou.best/pov8n"));
+        assert_eq!(url.render_id(), None);
+        assert_eq!(url.thumbnail_url(), None);
+
+        let url = VideoUrl::from(String::from("https://link.issou.best/pov81"));
+        assert_eq!(url.render_id(), None);
+        assert_eq!(url.thumbnail_url(), None);
+
+        let url = VideoUrl::from(String::from(
+            "https://example.com/render/ordr-render-123.mp4",
+        ));
+        assert_eq!(url.render_id(), None);
+    }
+}
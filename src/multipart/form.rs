@@ -0,0 +1,487 @@
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Formatter, Result as FmtResult},
+    iter,
+};
+
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Serialize;
+
+use super::FormSerializer;
+
+/// A `multipart/form-data` body.
+///
+/// Parts are written into the body as they're added.
+///
+/// # Example
+/// ```
+/// use rosu_render::multipart::{Form, Part};
+///
+/// let replay: Vec<u8> = vec![/* ... */];
+///
+/// let mut form = Form::new();
+///
+/// form.text("username", "rosu-render")
+///     .part(
+///         "replayFile",
+///         Part::bytes(replay)
+///             .filename("replay.osr")
+///             .content_type("application/octet-stream"),
+///     );
+///
+/// let content_type = form.content_type();
+/// let body = form.build();
+/// # assert!(content_type.starts_with("multipart/form-data; boundary="));
+/// ```
+///
+/// Its [`Debug`] output lists the fields without file contents and with the
+/// verification key redacted so that it can be logged safely.
+pub struct Form {
+    pub(super) bytes: Vec<u8>,
+    pub(super) boundary: [u8; 16],
+}
+
+/// A single part of a [`Form`].
+///
+/// Data, filename, and content type can be either owned or borrowed.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Part<'a> {
+    data: Cow<'a, [u8]>,
+    filename: Option<Cow<'a, str>>,
+    content_type: Option<Cow<'a, str>>,
+}
+
+impl<'a> Part<'a> {
+    /// A text part.
+    pub fn text(value: impl Into<Cow<'a, str>>) -> Self {
+        let data = match value.into() {
+            Cow::Borrowed(value) => Cow::Borrowed(value.as_bytes()),
+            Cow::Owned(value) => Cow::Owned(value.into_bytes()),
+        };
+
+        Self::bytes(data)
+    }
+
+    /// A part containing arbitrary bytes, e.g. a file.
+    pub fn bytes(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        Self {
+            data: data.into(),
+            filename: None,
+            content_type: None,
+        }
+    }
+
+    /// Specify the part's filename.
+    pub fn filename(self, filename: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            filename: Some(filename.into()),
+            ..self
+        }
+    }
+
+    /// Specify the part's content type.
+    pub fn content_type(self, content_type: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            content_type: Some(content_type.into()),
+            ..self
+        }
+    }
+}
+
+impl Form {
+    pub(super) const BOUNDARY_TERMINATOR: &'static [u8; 2] = b"--";
+    pub(super) const NEWLINE: &'static [u8; 2] = b"\r\n";
+
+    /// Fields whose values are never included in the [`Debug`] output.
+    const REDACTED_FIELDS: &'static [&'static [u8]] = &[b"verificationKey"];
+
+    /// Create an empty form with a random boundary.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut boundary = [0; 16];
+        let mut rng = rand::thread_rng();
+
+        boundary
+            .iter_mut()
+            .for_each(|value| *value = rng.sample(Alphanumeric));
+
+        let mut bytes = Vec::with_capacity(1024);
+        bytes.extend_from_slice(Self::BOUNDARY_TERMINATOR);
+        bytes.extend_from_slice(&boundary);
+
+        Self { bytes, boundary }
+    }
+
+    /// Serialize a struct's fields as text parts.
+    pub(crate) fn serialize<T: Serialize>(value: &T) -> Self {
+        let mut serializer = FormSerializer::new();
+
+        // The error type is infallible
+        value.serialize(&mut serializer).unwrap();
+
+        serializer.form
+    }
+
+    /// Serialize only the fields of `value` that differ from `base`.
+    pub(crate) fn serialize_changed<T: Serialize>(value: &T, base: &T) -> Self {
+        let mut base_serializer = FormSerializer::new();
+
+        // The error type is infallible
+        base.serialize(&mut base_serializer).unwrap();
+
+        let mut serializer = FormSerializer::with_base(base_serializer);
+        value.serialize(&mut serializer).unwrap();
+
+        serializer.form
+    }
+
+    /// Finish the form and return its body.
+    #[must_use]
+    pub fn build(mut self) -> Vec<u8> {
+        self.bytes.extend_from_slice(Self::BOUNDARY_TERMINATOR);
+
+        self.bytes
+    }
+
+    /// The length of the body returned by [`Form::build`].
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.bytes.len() + Self::BOUNDARY_TERMINATOR.len()
+    }
+
+    /// Whether no part has been added yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.len() == Self::BOUNDARY_TERMINATOR.len() + self.boundary.len()
+    }
+
+    /// Add a text part.
+    pub fn text(&mut self, name: &str, value: &str) -> &mut Self {
+        self.part(name, Part::text(value))
+    }
+
+    /// Add a part.
+    ///
+    /// Quotes and line breaks in the name and filename are percent-encoded,
+    /// line breaks in the content type are removed.
+    pub fn part(&mut self, name: &str, part: Part<'_>) -> &mut Self {
+        let Part {
+            data,
+            filename,
+            content_type,
+        } = part;
+
+        self.write_field_headers(
+            name.as_bytes(),
+            filename.as_deref(),
+            content_type.as_deref(),
+        );
+        self.bytes.extend_from_slice(&data);
+
+        self.bytes.extend_from_slice(Self::NEWLINE);
+        self.bytes.extend_from_slice(Self::BOUNDARY_TERMINATOR);
+        self.bytes.extend_from_slice(&self.boundary);
+
+        self
+    }
+
+    /// The value of the `Content-Type` header for requests with this form.
+    #[must_use]
+    pub fn content_type(&self) -> String {
+        const NAME: &str = "multipart/form-data; boundary=";
+
+        let mut content_type = String::with_capacity(NAME.len() + self.boundary.len());
+        content_type.push_str(NAME);
+        // The boundary is alphanumeric
+        content_type.extend(self.boundary.iter().copied().map(char::from));
+
+        content_type
+    }
+
+    pub(super) fn write_field_headers(
+        &mut self,
+        name: &[u8],
+        filename: Option<&str>,
+        content_type: Option<&str>,
+    ) {
+        self.bytes.extend_from_slice(Self::NEWLINE);
+        self.bytes
+            .extend_from_slice(b"Content-Disposition: form-data; name=\"");
+        self.write_escaped(name);
+        self.bytes.extend_from_slice(b"\"");
+
+        if let Some(filename) = filename {
+            self.bytes.extend_from_slice(b"; filename=\"");
+            self.write_escaped(filename.as_bytes());
+            self.bytes.extend_from_slice(b"\"");
+        }
+
+        self.bytes.extend_from_slice(Self::NEWLINE);
+
+        if let Some(content_type) = content_type {
+            self.bytes.extend_from_slice(b"Content-Type: ");
+
+            // Line breaks would end the header
+            let content_type = content_type
+                .bytes()
+                .filter(|&byte| !matches!(byte, b'\r' | b'\n'));
+            self.bytes.extend(content_type);
+
+            self.bytes.extend_from_slice(Self::NEWLINE);
+        }
+
+        self.bytes.extend_from_slice(Self::NEWLINE);
+    }
+
+    /// Iterate over the headers and data of all parts written so far.
+    fn parts(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        let mut delimiter = Vec::with_capacity(4 + self.boundary.len());
+        delimiter.extend_from_slice(Self::NEWLINE);
+        delimiter.extend_from_slice(Self::BOUNDARY_TERMINATOR);
+        delimiter.extend_from_slice(&self.boundary);
+
+        let mut rest = &self.bytes[Self::BOUNDARY_TERMINATOR.len() + self.boundary.len()..];
+
+        iter::from_fn(move || {
+            // Headers are preceded by a line break and end with an empty line
+            let headers = rest.strip_prefix(Self::NEWLINE)?;
+            let headers_end = find(headers, b"\r\n\r\n")?;
+            let body = &headers[headers_end + 4..];
+            let data_end = find(body, &delimiter)?;
+            rest = &body[data_end + delimiter.len()..];
+
+            Some((&headers[..headers_end], &body[..data_end]))
+        })
+    }
+
+    /// Write a quoted header parameter such as a field name.
+    ///
+    /// As specified by RFC 7578 and the HTML standard, quotes and line breaks
+    /// are percent-encoded so they can't break out of the header. Everything
+    /// else, including non-ASCII UTF-8, is written as is.
+    ///
+    /// Field values don't need escaping since parts are only delimited by the
+    /// random boundary.
+    fn write_escaped(&mut self, value: &[u8]) {
+        for &byte in value {
+            match byte {
+                b'"' => self.bytes.extend_from_slice(b"%22"),
+                b'\r' => self.bytes.extend_from_slice(b"%0D"),
+                b'\n' => self.bytes.extend_from_slice(b"%0A"),
+                _ => self.bytes.push(byte),
+            }
+        }
+    }
+}
+
+impl Debug for Form {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        enum Value<'a> {
+            Text(&'a str),
+            Bytes(usize),
+            Redacted,
+        }
+
+        impl Debug for Value<'_> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+                match self {
+                    Value::Text(text) => Debug::fmt(text, f),
+                    Value::Bytes(len) => write!(f, "<{len} bytes>"),
+                    Value::Redacted => f.write_str("<redacted>"),
+                }
+            }
+        }
+
+        const NAME: &[u8] = b"name=\"";
+
+        let fields = self.parts().map(|(headers, data)| {
+            // The name always precedes the filename
+            let name = find(headers, NAME)
+                .map(|idx| &headers[idx + NAME.len()..])
+                .and_then(|name| name.split(|&byte| byte == b'"').next())
+                .unwrap_or_default();
+
+            let value = if Self::REDACTED_FIELDS.contains(&name) {
+                Value::Redacted
+            } else if find(headers, b"filename=").is_some() {
+                Value::Bytes(data.len())
+            } else {
+                std::str::from_utf8(data).map_or(Value::Bytes(data.len()), Value::Text)
+            };
+
+            (String::from_utf8_lossy(name), value)
+        });
+
+        f.debug_map().entries(fields).finish()
+    }
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8 as str_from_utf8;
+
+    use super::{Form, Part};
+
+    #[test]
+    fn empty() {
+        let form = Form::new();
+        assert!(form.is_empty());
+
+        let expect = format!("--{}--", str_from_utf8(&form.boundary).unwrap());
+
+        let form = String::from_utf8(form.build()).unwrap();
+
+        assert_eq!(form, expect);
+    }
+
+    #[test]
+    fn filled() {
+        let mut form = Form::new();
+
+        form.text("key1", "value1").text("key2", "value2").part(
+            "key3",
+            Part::bytes(b"replay data".as_slice())
+                .filename("replay.osr")
+                .content_type("application/octet-stream"),
+        );
+
+        assert!(!form.is_empty());
+
+        let boundary = str_from_utf8(&form.boundary).unwrap();
+
+        let expect = format!(
+            "--{boundary}\r\n\
+            Content-Disposition: form-data; name=\"key1\"\r\n\
+            \r\n\
+            value1\r\n\
+            --{boundary}\r\n\
+            Content-Disposition: form-data; name=\"key2\"\r\n\
+            \r\n\
+            value2\r\n\
+            --{boundary}\r\n\
+            Content-Disposition: form-data; name=\"key3\"; filename=\"replay.osr\"\r\n\
+            Content-Type: application/octet-stream\r\n\
+            \r\n\
+            replay data\r\n\
+            --{boundary}--"
+        );
+
+        let form = String::from_utf8(form.build()).unwrap();
+
+        assert_eq!(form, expect);
+    }
+
+    #[test]
+    fn debug_redacted() {
+        let mut form = Form::new();
+
+        form.text("username", "rosu-render")
+            .part(
+                "replayFile",
+                Part::bytes(b"replay data".as_slice()).filename("replay.osr"),
+            )
+            .text("verificationKey", "secret");
+
+        let debug = format!("{form:?}");
+
+        assert_eq!(
+            debug,
+            r#"{"username": "rosu-render", "replayFile": <11 bytes>, "verificationKey": <redacted>}"#
+        );
+        assert_eq!(format!("{:?}", Form::new()), "{}");
+    }
+
+    #[test]
+    fn escape_field_names() {
+        let mut form = Form::new();
+
+        form.text("na\"me\r\nX-Injected: 1", "value")
+            .text("名前", "value");
+
+        let boundary = str_from_utf8(&form.boundary).unwrap();
+
+        let expect = format!(
+            "--{boundary}\r\n\
+            Content-Disposition: form-data; name=\"na%22me%0D%0AX-Injected: 1\"\r\n\
+            \r\n\
+            value\r\n\
+            --{boundary}\r\n\
+            Content-Disposition: form-data; name=\"名前\"\r\n\
+            \r\n\
+            value\r\n\
+            --{boundary}--"
+        );
+
+        let form = String::from_utf8(form.build()).unwrap();
+
+        assert_eq!(form, expect);
+    }
+
+    #[test]
+    fn keep_values_verbatim() {
+        let usernames = [
+            "Ölmäüß",
+            "ユーザー名 🎵",
+            "quo\"te",
+            "line\r\nContent-Disposition: form-data; name=\"skin\"\r\n\r\nother",
+            "\r\n--\r\n",
+        ];
+
+        for username in usernames {
+            let mut form = Form::new();
+            form.text("username", username);
+
+            let boundary = str_from_utf8(&form.boundary).unwrap().to_owned();
+            let form = String::from_utf8(form.build()).unwrap();
+            let delimiter = format!("--{boundary}");
+
+            let parts: Vec<_> = form.split(&delimiter).collect();
+            assert_eq!(parts.len(), 3, "{username:?}");
+
+            let expect = format!(
+                "\r\nContent-Disposition: form-data; name=\"username\"\r\n\r\n{username}\r\n"
+            );
+
+            assert_eq!(parts[1], expect);
+        }
+    }
+
+    #[test]
+    fn custom_replay_file() {
+        let mut form = Form::new();
+
+        let part = Part::bytes(b"replay data".to_vec())
+            .filename(String::from("my \"best\"\r\nplay.osr"))
+            .content_type("application/x-osu-replay\r\nX-Injected: 1");
+
+        form.part("replayFile", part);
+
+        let boundary = str_from_utf8(&form.boundary).unwrap();
+
+        let expect = format!(
+            "--{boundary}\r\n\
+            Content-Disposition: form-data; name=\"replayFile\"; filename=\"my %22best%22%0D%0Aplay.osr\"\r\n\
+            Content-Type: application/x-osu-replayX-Injected: 1\r\n\
+            \r\n\
+            replay data\r\n\
+            --{boundary}--"
+        );
+
+        let form = String::from_utf8(form.build()).unwrap();
+
+        assert_eq!(form, expect);
+    }
+}
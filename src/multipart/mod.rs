@@ -0,0 +1,8 @@
+//! Building `multipart/form-data` request bodies.
+
+mod form;
+mod serializer;
+
+use self::serializer::FormSerializer;
+
+pub use self::form::{Form, Part};
@@ -1,6 +1,8 @@
 use std::{
     error::Error as StdError,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
+    mem,
+    ops::Range,
 };
 
 use serde::{
@@ -14,6 +16,12 @@ pub(crate) struct FormSerializer {
     pub(super) form: Form,
     float_buf: ryu::Buffer,
     int_buf: itoa::Buffer,
+    /// Byte range of each field's value within the form.
+    values: Vec<(&'static str, Range<usize>)>,
+    /// Fields that are serialized to the same value as in `base` are skipped.
+    base: Option<Box<FormSerializer>>,
+    /// Whether the current field's value is `None` or unit and should be skipped.
+    skip_field: bool,
 }
 
 impl FormSerializer {
@@ -22,8 +30,28 @@ impl FormSerializer {
             form: Form::new(),
             float_buf: ryu::Buffer::new(),
             int_buf: itoa::Buffer::new(),
+            values: Vec::new(),
+            base: None,
+            skip_field: false,
         }
     }
+
+    /// Skip fields whose value is the same as in the serialized `base`.
+    pub(super) fn with_base(base: Self) -> Self {
+        Self {
+            base: Some(Box::new(base)),
+            ..Self::new()
+        }
+    }
+
+    fn base_value(&self, key: &str) -> Option<&[u8]> {
+        let base = self.base.as_deref()?;
+
+        base.values
+            .iter()
+            .find(|(base_key, _)| *base_key == key)
+            .map(|(_, range)| &base.form.bytes[range.clone()])
+    }
 }
 
 impl Serializer for &mut FormSerializer {
@@ -114,8 +142,8 @@ impl Serializer for &mut FormSerializer {
         Ok(())
     }
 
-    fn serialize_char(self, _: char) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+    fn serialize_char(self, c: char) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(c.encode_utf8(&mut [0; 4]))
     }
 
     fn serialize_str(self, s: &str) -> Result<Self::Ok, Self::Error> {
@@ -131,19 +159,21 @@ impl Serializer for &mut FormSerializer {
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        self.skip_field = true;
+
+        Ok(())
     }
 
-    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        self.serialize_none()
     }
 
     fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        self.serialize_none()
     }
 
     fn serialize_unit_variant(
@@ -158,9 +188,9 @@ impl Serializer for &mut FormSerializer {
     fn serialize_newtype_struct<T: Serialize + ?Sized>(
         self,
         _: &'static str,
-        _: &T,
+        value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        value.serialize(self)
     }
 
     fn serialize_newtype_variant<T: Serialize + ?Sized>(
@@ -168,9 +198,9 @@ impl Serializer for &mut FormSerializer {
         _: &'static str,
         _: u32,
         _: &'static str,
-        _: &T,
+        value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        value.serialize(self)
     }
 
     fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
@@ -231,9 +261,22 @@ impl SerializeStruct for &mut FormSerializer {
         key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.form.write_field_headers(key.as_bytes(), false);
+        let start = self.form.bytes.len();
+        self.form.write_field_headers(key.as_bytes(), None, None);
+
+        let value_start = self.form.bytes.len();
         value.serialize(&mut **self)?;
+        let value_range = value_start..self.form.bytes.len();
 
+        if mem::take(&mut self.skip_field)
+            || self.base_value(key) == Some(&self.form.bytes[value_range.clone()])
+        {
+            self.form.bytes.truncate(start);
+
+            return Ok(());
+        }
+
+        self.values.push((key, value_range));
         self.form.bytes.extend_from_slice(Form::NEWLINE);
         self.form.bytes.extend_from_slice(Form::BOUNDARY_TERMINATOR);
         self.form.bytes.extend_from_slice(&self.form.boundary);
@@ -277,4 +320,69 @@ mod tests {
     fn test_form_serializer() {
         let _form = Form::serialize(&RenderOptions::default());
     }
+
+    #[test]
+    fn serialize_changed() {
+        let options = RenderOptions {
+            show_pp_counter: false,
+            global_volume: 70,
+            ..Default::default()
+        };
+
+        let form = Form::serialize_changed(&options, &RenderOptions::default());
+        let body = String::from_utf8(form.build()).unwrap();
+
+        assert_eq!(body.matches("Content-Disposition").count(), 2);
+        assert!(body.contains("name=\"showPPCounter\"\r\n\r\nfalse\r\n"));
+        assert!(body.contains("name=\"globalVolume\"\r\n\r\n70\r\n"));
+
+        let unchanged =
+            Form::serialize_changed(&RenderOptions::default(), &RenderOptions::default());
+
+        assert!(unchanged.is_empty());
+    }
+
+    #[derive(Serialize)]
+    struct Wrapper(u32);
+
+    #[derive(Serialize)]
+    enum Value {
+        Number(u8),
+        Text(&'static str),
+    }
+
+    #[derive(Serialize)]
+    struct Fields {
+        none: Option<u32>,
+        some: Option<u32>,
+        unit: (),
+        newtype: Wrapper,
+        variant: Value,
+        nested: Option<Value>,
+        character: char,
+    }
+
+    #[test]
+    fn optional_and_newtype_fields() {
+        let fields = Fields {
+            none: None,
+            some: Some(42),
+            unit: (),
+            newtype: Wrapper(7),
+            variant: Value::Number(3),
+            nested: Some(Value::Text("text")),
+            character: 'x',
+        };
+
+        let body = String::from_utf8(Form::serialize(&fields).build()).unwrap();
+
+        assert!(!body.contains("name=\"none\""));
+        assert!(!body.contains("name=\"unit\""));
+        assert!(body.contains("name=\"some\"\r\n\r\n42\r\n"));
+        assert!(body.contains("name=\"newtype\"\r\n\r\n7\r\n"));
+        assert!(body.contains("name=\"variant\"\r\n\r\n3\r\n"));
+        assert!(body.contains("name=\"nested\"\r\n\r\ntext\r\n"));
+        assert!(body.contains("name=\"character\"\r\n\r\nx\r\n"));
+        assert_eq!(body.matches("Content-Disposition").count(), 5);
+    }
 }
@@ -0,0 +1,7 @@
+//! Re-exports of foreign types that appear in the public API.
+//!
+//! Naming these through this module avoids having to depend on the exact
+//! same versions of `time` and `hyper` as this crate.
+
+pub use hyper::StatusCode;
+pub use time::OffsetDateTime;
@@ -0,0 +1,177 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+use hyper::{
+    body::{self, Bytes, HttpBody},
+    Body,
+};
+
+use crate::{model::SkinInfo, ClientError};
+
+/// The body of a downloaded file.
+///
+/// Chunks can be streamed as they arrive or all at once through [`Download::bytes`].
+pub struct Download {
+    body: Body,
+    content_length: Option<u64>,
+}
+
+impl Download {
+    pub(crate) fn new(body: Body) -> Self {
+        let content_length = HttpBody::size_hint(&body).exact();
+
+        Self {
+            body,
+            content_length,
+        }
+    }
+
+    /// Use the given content length if the body itself does not know its size,
+    /// e.g. because it was streamed through reqwest.
+    pub(crate) fn content_length_fallback(mut self, content_length: Option<u64>) -> Self {
+        self.content_length = self.content_length.or(content_length);
+
+        self
+    }
+
+    /// The size of the file in bytes if the server specified it.
+    #[must_use]
+    pub const fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    /// Await the whole file.
+    ///
+    /// Fails with [`ClientError::IncompleteDownload`] if fewer or more bytes
+    /// were received than the server specified.
+    pub async fn bytes(self) -> Result<Bytes, ClientError> {
+        let expected = self.content_length();
+
+        let bytes = body::to_bytes(self.body)
+            .await
+            .map_err(|source| ClientError::ChunkingResponse { source })?;
+
+        match expected {
+            Some(expected) if expected != bytes.len() as u64 => {
+                Err(ClientError::IncompleteDownload {
+                    expected,
+                    received: bytes.len() as u64,
+                })
+            }
+            _ => Ok(bytes),
+        }
+    }
+}
+
+impl Stream for Download {
+    type Item = Result<Bytes, ClientError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.body)
+            .poll_data(cx)
+            .map_err(|source| ClientError::ChunkingResponse { source })
+    }
+}
+
+/// The `.osk` archive of a custom skin.
+///
+/// Created through [`OrdrClient::download_custom_skin`](crate::OrdrClient::download_custom_skin).
+pub struct SkinArchive {
+    info: SkinInfo,
+    download: Download,
+}
+
+impl SkinArchive {
+    pub(crate) const fn new(info: SkinInfo, download: Download) -> Self {
+        Self { info, download }
+    }
+
+    /// Information about the skin.
+    #[must_use]
+    pub const fn info(&self) -> &SkinInfo {
+        &self.info
+    }
+
+    /// A filename for the archive based on the skin's name.
+    ///
+    /// Characters that are invalid in common file systems are replaced by `_`.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        let mut file_name: String = self
+            .info
+            .name
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c if c.is_control() => '_',
+                c => c,
+            })
+            .collect();
+
+        file_name.push_str(".osk");
+
+        file_name
+    }
+
+    /// The size of the archive in bytes if the server specified it.
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        self.download.content_length()
+    }
+
+    /// Await the whole archive.
+    ///
+    /// The o!rdr API does not provide a checksum so only the size is verified,
+    /// see [`Download::bytes`].
+    pub async fn bytes(self) -> Result<Bytes, ClientError> {
+        self.download.bytes().await
+    }
+
+    /// Stream the archive's chunks instead of collecting them.
+    #[must_use]
+    pub fn into_download(self) -> Download {
+        self.download
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::Body;
+
+    use crate::{model::SkinInfo, ClientError};
+
+    use super::{Download, SkinArchive};
+
+    #[test]
+    fn archive_file_name() {
+        let info = SkinInfo {
+            name: "- A/B: \"C\"? -".into(),
+            author: "author".into(),
+            download_link: "https://example.com/skin.osk".into(),
+        };
+
+        let archive = SkinArchive::new(info, Download::new(Body::empty()));
+
+        assert_eq!(archive.file_name(), "- A_B_ _C__ -.osk");
+    }
+
+    #[tokio::test]
+    async fn verify_download_size() {
+        let download = Download::new(Body::from("archive"));
+        assert_eq!(download.content_length(), Some(7));
+        assert_eq!(&download.bytes().await.unwrap()[..], b"archive");
+
+        let (mut sender, body) = Body::channel();
+
+        tokio::spawn(async move {
+            sender.send_data("arch".into()).await.unwrap();
+            sender.abort();
+        });
+
+        let err = Download::new(body).bytes().await.unwrap_err();
+        assert!(matches!(err, ClientError::ChunkingResponse { .. }));
+    }
+}
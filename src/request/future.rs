@@ -2,50 +2,167 @@ use std::{
     future::Future,
     marker::PhantomData,
     pin::{pin, Pin},
+    sync::Arc,
     task::{Context, Poll},
 };
 
+#[cfg(feature = "compression")]
+use hyper::header::CONTENT_ENCODING;
 use hyper::{
     body::{self, Bytes},
-    client::ResponseFuture as HyperResponseFuture,
-    StatusCode,
+    http::response::Parts,
+    Body, Method, Request as HyperRequest, Response, StatusCode,
 };
-use leaky_bucket::AcquireOwned;
 use pin_project::pin_project;
 use serde::de::DeserializeOwned;
 
-use crate::ClientError;
+#[cfg(feature = "cache")]
+use crate::client::cache::CacheSlot;
+use crate::{
+    client::{
+        Acquire, CircuitBreaker, ErrorRate, FailoverSlot, HttpClient, HttpFuture, Middlewares,
+        StatsRecorder,
+    },
+    util::json,
+    ClientError,
+};
 
 use super::requestable::Requestable;
 
+type Precheck = Pin<Box<dyn Future<Output = Result<(), ClientError>> + Send + 'static>>;
+
 #[pin_project(project = OrdrFutureProj)]
 pub struct OrdrFuture<T> {
+    precheck: Option<Precheck>,
+    map_err: Option<fn(ClientError) -> ClientError>,
+    error_rate: Option<Arc<ErrorRate>>,
     #[pin]
-    ratelimit: Option<AcquireOwned>,
+    ratelimit: Option<Acquire>,
     #[pin]
     state: OrdrFutureState<T>,
 }
 
 impl<T> OrdrFuture<T> {
-    pub(crate) const fn new(fut: Pin<Box<HyperResponseFuture>>, ratelimit: AcquireOwned) -> Self {
+    pub(crate) const fn new(
+        fut: HttpFuture,
+        ratelimit: Option<Acquire>,
+        middlewares: Option<Middlewares>,
+        stats: StatsRecorder,
+        circuit: Option<Arc<CircuitBreaker>>,
+    ) -> Self {
         Self {
-            ratelimit: Some(ratelimit),
+            precheck: None,
+            map_err: None,
+            error_rate: None,
+            ratelimit,
             state: OrdrFutureState::InFlight(InFlight {
                 fut,
+                middlewares,
+                stats: Some(stats),
+                circuit,
+                failover: None,
+                retry: None,
+                #[cfg(feature = "cache")]
+                cache_slot: None,
                 phantom: PhantomData,
             }),
         }
     }
 
+    /// Send the request once more if the connection was closed before
+    /// the response was received.
+    pub(crate) fn retry(mut self, retry: Option<Retry>) -> Self {
+        if let OrdrFutureState::InFlight(ref mut in_flight) = self.state {
+            in_flight.retry = retry.map(Box::new);
+        }
+
+        self
+    }
+
+    /// Create a future that deserializes cached response bytes
+    /// without sending a request.
+    #[cfg(feature = "cache")]
+    pub(crate) const fn cached(bytes: Bytes) -> Self {
+        Self {
+            precheck: None,
+            map_err: None,
+            error_rate: None,
+            ratelimit: None,
+            state: OrdrFutureState::Cached(Some(bytes)),
+        }
+    }
+
+    /// Record the outcome of the request in the given slot.
+    pub(crate) fn failover(mut self, failover: Option<FailoverSlot>) -> Self {
+        if let OrdrFutureState::InFlight(ref mut in_flight) = self.state {
+            in_flight.failover = failover;
+        }
+
+        self
+    }
+
+    /// Store the response bytes in the given slot if the request is successful.
+    #[cfg(feature = "cache")]
+    pub(crate) fn cache_slot(mut self, cache_slot: Option<CacheSlot>) -> Self {
+        if let OrdrFutureState::InFlight(ref mut in_flight) = self.state {
+            in_flight.cache_slot = cache_slot;
+        }
+
+        self
+    }
+
     pub(crate) const fn error(source: ClientError) -> Self {
         Self {
+            precheck: None,
+            map_err: None,
+            error_rate: None,
             ratelimit: None,
             state: OrdrFutureState::Failed(Some(source)),
         }
     }
 
+    /// Await the given future before doing anything else.
+    /// If it fails, its error will be returned instead of sending the request.
+    pub(crate) fn precheck(mut self, precheck: Precheck) -> Self {
+        self.precheck = Some(precheck);
+
+        self
+    }
+
+    /// Apply the given function on the error if the future fails.
+    pub(crate) fn map_err(mut self, map_err: fn(ClientError) -> ClientError) -> Self {
+        self.map_err = Some(map_err);
+
+        self
+    }
+
+    /// Record whether the request was rejected by o!rdr in the given tracker.
+    pub(crate) fn error_rate(mut self, error_rate: Arc<ErrorRate>) -> Self {
+        self.error_rate = Some(error_rate);
+
+        self
+    }
+
+    fn await_precheck(
+        precheck_opt: &mut Option<Precheck>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), ClientError>> {
+        if let Some(precheck) = precheck_opt.as_mut() {
+            let res = match precheck.as_mut().poll(cx) {
+                Poll::Ready(res) => res,
+                Poll::Pending => return Poll::Pending,
+            };
+
+            *precheck_opt = None;
+
+            return Poll::Ready(res);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
     fn await_ratelimit(
-        mut ratelimit_opt: Pin<&mut Option<AcquireOwned>>,
+        mut ratelimit_opt: Pin<&mut Option<Acquire>>,
         cx: &mut Context<'_>,
     ) -> Poll<()> {
         if let Some(ratelimit) = ratelimit_opt.as_mut().as_pin_mut() {
@@ -62,52 +179,105 @@ impl<T> OrdrFuture<T> {
 impl<T: DeserializeOwned + Requestable> Future for OrdrFuture<T> {
     type Output = Result<T, ClientError>;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let mut this = self.project();
-        let mut state = this.state.as_mut();
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let map_err = self.map_err;
 
-        match state.as_mut().project() {
-            OrdrFutureStateProj::InFlight(in_flight) => {
-                if Self::await_ratelimit(this.ratelimit, cx).is_pending() {
-                    return Poll::Pending;
-                }
+        let res = match poll_inner(self.as_mut(), cx) {
+            Poll::Ready(Err(err)) => match map_err {
+                Some(map_err) => Err(map_err(err)),
+                None => Err(err),
+            },
+            Poll::Ready(Ok(value)) => Ok(value),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        if let Some(error_rate) = self.project().error_rate.take() {
+            match res {
+                Ok(_) => error_rate.record(true),
+                Err(ClientError::RenderSubmission { .. }) => error_rate.record(false),
+                Err(_) => {}
+            }
+        }
+
+        Poll::Ready(res)
+    }
+}
 
-                match in_flight.poll(cx) {
-                    Poll::Ready(Ok(chunking)) => {
-                        state.set(OrdrFutureState::Chunking(chunking));
-                        cx.waker().wake_by_ref();
+fn poll_inner<T: DeserializeOwned + Requestable>(
+    fut: Pin<&mut OrdrFuture<T>>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<T, ClientError>> {
+    let mut this = fut.project();
+    let mut state = this.state.as_mut();
+
+    match state.as_mut().project() {
+        OrdrFutureStateProj::InFlight(mut in_flight) => {
+            match OrdrFuture::<T>::await_precheck(this.precheck, cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => {
+                    state.set(OrdrFutureState::Completed);
 
-                        Poll::Pending
-                    }
-                    Poll::Ready(Err(err)) => {
-                        state.set(OrdrFutureState::Completed);
+                    return Poll::Ready(Err(err));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
 
-                        Poll::Ready(Err(err))
-                    }
-                    Poll::Pending => Poll::Pending,
+            if this.ratelimit.is_some() {
+                if let Some(stats) = in_flight.as_mut().project().stats.as_mut() {
+                    stats.wait();
                 }
             }
-            OrdrFutureStateProj::Chunking(chunking) => match chunking.poll(cx) {
-                Poll::Ready(res) => {
+
+            if OrdrFuture::<T>::await_ratelimit(this.ratelimit, cx).is_pending() {
+                return Poll::Pending;
+            }
+
+            match in_flight.poll(cx) {
+                Poll::Ready(Ok(chunking)) => {
+                    state.set(OrdrFutureState::Chunking(chunking));
+                    cx.waker().wake_by_ref();
+
+                    Poll::Pending
+                }
+                Poll::Ready(Err(err)) => {
                     state.set(OrdrFutureState::Completed);
 
-                    Poll::Ready(res)
+                    Poll::Ready(Err(err))
                 }
                 Poll::Pending => Poll::Pending,
-            },
-            OrdrFutureStateProj::Failed(failed) => {
-                let err = failed.take().expect("error already taken");
+            }
+        }
+        OrdrFutureStateProj::Chunking(chunking) => match chunking.poll(cx) {
+            Poll::Ready(res) => {
                 state.set(OrdrFutureState::Completed);
 
-                Poll::Ready(Err(err))
+                Poll::Ready(res)
             }
-            OrdrFutureStateProj::Completed => panic!("future already completed"),
+            Poll::Pending => Poll::Pending,
+        },
+        #[cfg(feature = "cache")]
+        OrdrFutureStateProj::Cached(cached) => {
+            let bytes = cached.take().expect("bytes already taken");
+            state.set(OrdrFutureState::Completed);
+
+            let res = json::from_bytes(&bytes);
+
+            Poll::Ready(res)
+        }
+        OrdrFutureStateProj::Failed(failed) => {
+            let err = failed.take().expect("error already taken");
+            state.set(OrdrFutureState::Completed);
+
+            Poll::Ready(Err(err))
         }
+        OrdrFutureStateProj::Completed => panic!("future already completed"),
     }
 }
 
 #[pin_project(project = OrdrFutureStateProj)]
 enum OrdrFutureState<T> {
+    #[cfg(feature = "cache")]
+    Cached(Option<Bytes>),
     Chunking(#[pin] Chunking<T>),
     Completed,
     Failed(Option<ClientError>),
@@ -119,6 +289,8 @@ struct Chunking<T> {
     #[pin]
     fut: Pin<Box<dyn Future<Output = Result<Bytes, ClientError>> + Send + Sync + 'static>>,
     status: StatusCode,
+    #[cfg(feature = "cache")]
+    cache_slot: Option<CacheSlot>,
     phantom: PhantomData<T>,
 }
 
@@ -128,20 +300,31 @@ impl<T: DeserializeOwned + Requestable> Future for Chunking<T> {
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.project();
 
-        let bytes = match this.fut.poll(cx) {
+        #[allow(unused_mut)]
+        let mut bytes = match this.fut.poll(cx) {
             Poll::Ready(Ok(bytes)) => bytes,
             Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
             Poll::Pending => return Poll::Pending,
         };
 
+        #[cfg(feature = "cache")]
+        if *this.status == StatusCode::NOT_MODIFIED {
+            if let Some(stale) = this.cache_slot.take().and_then(CacheSlot::revalidate) {
+                *this.status = StatusCode::OK;
+                bytes = stale;
+            }
+        }
+
         let res = if this.status.is_success() {
-            match serde_json::from_slice(&bytes) {
-                Ok(this) => Ok(this),
-                Err(source) => Err(ClientError::Parsing {
-                    body: bytes.into(),
-                    source,
-                }),
+            let res = json::from_bytes(&bytes);
+
+            // Only cache responses that can be parsed
+            #[cfg(feature = "cache")]
+            if let (Some(cache_slot), Ok(_)) = (this.cache_slot.take(), &res) {
+                cache_slot.insert(bytes);
             }
+
+            res
         } else {
             Err(<T as Requestable>::response_error(*this.status, bytes))
         };
@@ -153,7 +336,14 @@ impl<T: DeserializeOwned + Requestable> Future for Chunking<T> {
 #[pin_project]
 struct InFlight<T> {
     #[pin]
-    fut: Pin<Box<HyperResponseFuture>>,
+    fut: HttpFuture,
+    middlewares: Option<Middlewares>,
+    stats: Option<StatsRecorder>,
+    circuit: Option<Arc<CircuitBreaker>>,
+    failover: Option<FailoverSlot>,
+    retry: Option<Box<Retry>>,
+    #[cfg(feature = "cache")]
+    cache_slot: Option<CacheSlot>,
     phantom: PhantomData<T>,
 }
 
@@ -161,16 +351,57 @@ impl<T: Requestable> Future for InFlight<T> {
     type Output = Result<Chunking<T>, ClientError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let this = self.project();
+        let mut this = self.project();
+
+        if let Some(stats) = this.stats.as_mut() {
+            stats.start();
+        }
 
-        let response = match this.fut.poll(cx) {
+        let response = match this.fut.as_mut().poll(cx) {
             Poll::Ready(Ok(response)) => response,
+            Poll::Ready(Err(source)) if source.is_incomplete_message() && this.retry.is_some() => {
+                debug!(?source, "Connection closed early, retrying request");
+
+                if let Some(retry) = this.retry.take() {
+                    this.fut.set(retry.send());
+                }
+
+                cx.waker().wake_by_ref();
+
+                return Poll::Pending;
+            }
             Poll::Ready(Err(source)) => {
-                return Poll::Ready(Err(ClientError::RequestError { source }))
+                if let Some(stats) = this.stats.take() {
+                    stats.finish(None);
+                }
+
+                if let Some(failover) = this.failover.take() {
+                    failover.record_error(&source);
+                }
+
+                return Poll::Ready(Err(source.into()));
             }
             Poll::Pending => return Poll::Pending,
         };
 
+        if let Some(stats) = this.stats.take() {
+            stats.finish(Some(response.status()));
+        }
+
+        if let Some(circuit) = this.circuit.take() {
+            circuit.record(response.status());
+        }
+
+        if let Some(failover) = this.failover.take() {
+            failover.record(response.status());
+        }
+
+        if let Some(middlewares) = this.middlewares.take() {
+            for middleware in middlewares.iter() {
+                middleware.after_response(&response);
+            }
+        }
+
         let status = response.status();
 
         match status {
@@ -181,19 +412,177 @@ impl<T: Requestable> Future for InFlight<T> {
             _ => {}
         };
 
-        // body::to_bytes returns an anonymous future so we need to Box::pin it
-        let fut = async {
-            let body = response.into_body();
+        #[cfg(feature = "cache")]
+        if let Some(slot) = this.cache_slot.as_mut() {
+            slot.set_validators(response.headers());
+        }
 
-            body::to_bytes(body)
-                .await
-                .map_err(|source| ClientError::ChunkingResponse { source })
-        };
+        // chunk_response returns an anonymous future so we need to Box::pin it
+        let fut = async move { chunk_response(response).await.map(|(_, bytes)| bytes) };
 
         Poll::Ready(Ok(Chunking {
             fut: Box::pin(fut),
             status,
+            #[cfg(feature = "cache")]
+            cache_slot: this.cache_slot.take(),
             phantom: PhantomData,
         }))
     }
 }
+
+/// A `GET` request that is sent once more if a pooled connection was
+/// closed before the response was received.
+pub(crate) struct Retry {
+    http: HttpClient,
+    req: HyperRequest<Body>,
+}
+
+impl Retry {
+    /// Returns `None` if the request is not a `GET` request.
+    pub(crate) fn new(http: &HttpClient, req: &HyperRequest<Body>) -> Option<Self> {
+        if req.method() != Method::GET {
+            return None;
+        }
+
+        // `GET` requests have an empty body so they can be re-created
+        let mut retry = HyperRequest::new(Body::empty());
+        *retry.method_mut() = Method::GET;
+        retry.uri_mut().clone_from(req.uri());
+        *retry.version_mut() = req.version();
+        retry.headers_mut().clone_from(req.headers());
+
+        Some(Self {
+            http: http.clone(),
+            req: retry,
+        })
+    }
+
+    fn send(self: Box<Self>) -> HttpFuture {
+        self.http.request(self.req)
+    }
+}
+
+/// Collect the body of the response and decompress it if necessary.
+pub(crate) async fn chunk_response(
+    response: Response<Body>,
+) -> Result<(Parts, Bytes), ClientError> {
+    let (parts, body) = response.into_parts();
+
+    let bytes = body::to_bytes(body)
+        .await
+        .map_err(|source| ClientError::ChunkingResponse { source })?;
+
+    #[cfg(feature = "compression")]
+    let bytes = crate::util::compression::decompress(bytes, parts.headers.get(CONTENT_ENCODING))
+        .map_err(|source| ClientError::Decompressing { source })?;
+
+    Ok((parts, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use crate::{model::ServerOnlineCount, ClientError, OrdrClient};
+
+    #[tokio::test]
+    async fn retry_closed_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut buf = [0; 1024];
+
+            // Close the first connection without responding
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut buf).await.unwrap();
+            drop(stream);
+
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut buf).await.unwrap();
+
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 1\r\n\r\n3")
+                .await
+                .unwrap();
+        });
+
+        let client = OrdrClient::builder()
+            .base_url(format!("http://{addr}"))
+            .build();
+
+        let count = client.server_online_count().await.unwrap();
+        assert_eq!(count, ServerOnlineCount(3));
+    }
+
+    #[tokio::test]
+    async fn failover_on_connection_error() {
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed_addr = closed.local_addr().unwrap();
+        drop(closed);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut buf = [0; 1024];
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut buf).await.unwrap();
+
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 1\r\n\r\n3")
+                .await
+                .unwrap();
+        });
+
+        let client = OrdrClient::builder()
+            .base_url(format!("http://{closed_addr}"))
+            .fallback_base_url(format!("http://{addr}"))
+            .build();
+
+        #[cfg(not(feature = "reqwest"))]
+        assert!(matches!(
+            client.server_online_count().await,
+            Err(ClientError::RequestError { .. })
+        ));
+        #[cfg(feature = "reqwest")]
+        assert!(matches!(
+            client.server_online_count().await,
+            Err(ClientError::Reqwest { .. })
+        ));
+
+        let count = client.server_online_count().await.unwrap();
+        assert_eq!(count, ServerOnlineCount(3));
+    }
+
+    #[tokio::test]
+    async fn timeout_without_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut buf = [0; 1024];
+
+            // Keep the connection open without ever responding
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut buf).await.unwrap();
+            std::future::pending::<()>().await;
+        });
+
+        let client = OrdrClient::builder()
+            .base_url(format!("http://{addr}"))
+            .timeout(Duration::from_millis(50))
+            .build();
+
+        let err = client.server_online_count().await.unwrap_err();
+        assert!(
+            matches!(err, ClientError::Timeout { timeout } if timeout == Duration::from_millis(50))
+        );
+        assert!(err.is_retryable());
+    }
+}
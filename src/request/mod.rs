@@ -1,4 +1,6 @@
+mod download;
 mod future;
+mod raw;
 mod render;
 mod render_list;
 mod requestable;
@@ -12,21 +14,32 @@ use hyper::Method;
 use serde::Serialize;
 use serde_urlencoded::Serializer as UrlSerializer;
 
-use crate::{client::RatelimiterKind, routing::Route, util::multipart::Form, ClientError};
+use crate::{client::RatelimiterKind, multipart::Form, routing::Route, ClientError};
 
-pub(crate) use self::requestable::Requestable;
+pub(crate) use self::{
+    future::{chunk_response, Retry},
+    render::check_replay_head,
+    requestable::Requestable,
+};
 
 pub use self::{
-    future::OrdrFuture, render::CommissionRender, render_list::GetRenderList,
-    server_list::GetServerList, server_online_count::GetServerOnlineCount,
-    skin_custom::GetSkinCustom, skin_list::GetSkinList,
+    download::{Download, SkinArchive},
+    future::OrdrFuture,
+    raw::RawRequest,
+    render::{CommissionRender, RenderJob, Username},
+    render_list::{GetRenderList, RenderListPages, RenderListWatcher},
+    server_list::GetServerList,
+    server_online_count::{GetServerOnlineCount, ServerCountWatcher},
+    skin_custom::GetSkinCustom,
+    skin_list::{GetSkinList, SkinListWatcher},
 };
 
 pub(crate) struct Request {
     pub(crate) form: Option<Form>,
     pub(crate) method: Method,
     pub(crate) path: String,
-    pub(crate) ratelimiter: RatelimiterKind,
+    pub(crate) ratelimiter: Option<RatelimiterKind>,
+    pub(crate) route: Route,
 }
 
 impl Request {
@@ -38,8 +51,9 @@ impl Request {
         Self {
             form: None,
             method: route.method(),
-            path: route.to_string(),
-            ratelimiter: route.ratelimiter(),
+            path: route.path().to_owned(),
+            ratelimiter: Some(route.ratelimiter()),
+            route,
         }
     }
 }
@@ -61,15 +75,30 @@ impl RequestBuilder {
         self
     }
 
+    /// Send the request without acquiring a ratelimit permit.
+    /// Only use this if a permit was already acquired elsewhere!
+    pub fn without_ratelimit(mut self) -> Self {
+        self.0.ratelimiter = None;
+
+        self
+    }
+
     /// Add a query to the end of the path. Be sure this is only called once!
     pub fn query(mut self, query: impl Serialize) -> Result<Self, ClientError> {
-        self.0.path.push('?');
-        let len = self.0.path.len();
-
-        let mut form_serializer = FormSerializer::for_suffix(&mut self.0.path, len);
-        let url_serializer = UrlSerializer::new(&mut form_serializer);
-        query.serialize(url_serializer).map_err(ClientError::from)?;
+        append_query(&mut self.0.path, query)?;
 
         Ok(self)
     }
 }
+
+/// Append a serialized query to the end of the path.
+pub(crate) fn append_query(path: &mut String, query: impl Serialize) -> Result<(), ClientError> {
+    path.push('?');
+    let len = path.len();
+
+    let mut form_serializer = FormSerializer::for_suffix(path, len);
+    let url_serializer = UrlSerializer::new(&mut form_serializer);
+    query.serialize(url_serializer).map_err(ClientError::from)?;
+
+    Ok(())
+}
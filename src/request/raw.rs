@@ -0,0 +1,73 @@
+use std::{
+    future::{Future, IntoFuture},
+    pin::Pin,
+};
+
+use hyper::{body::Bytes, Method, StatusCode};
+use serde::Serialize;
+
+use crate::{client::RatelimiterKind, multipart::Form, ClientError, OrdrClient};
+
+use super::append_query;
+
+/// A request to an arbitrary endpoint, created through [`OrdrClient::raw`].
+///
+/// Requests still respect the client's ratelimits: `POST` requests count
+/// towards the render ratelimit, all others towards the [`RatelimitBucket`]
+/// matching their path or the general bucket.
+///
+/// Responses are not checked for their status code.
+///
+/// [`RatelimitBucket`]: crate::client::RatelimitBucket
+#[must_use]
+pub struct RawRequest<'a> {
+    ordr: &'a OrdrClient,
+    method: Method,
+    path: String,
+    form: Option<Form>,
+}
+
+impl<'a> RawRequest<'a> {
+    pub(crate) const fn new(ordr: &'a OrdrClient, method: Method, path: String) -> Self {
+        Self {
+            ordr,
+            method,
+            path,
+            form: None,
+        }
+    }
+
+    /// Append the serialized query to the path. Be sure this is only called once!
+    pub fn query(mut self, query: impl Serialize) -> Result<Self, ClientError> {
+        append_query(&mut self.path, query)?;
+
+        Ok(self)
+    }
+
+    /// Send the given form as body.
+    pub fn form(self, form: Form) -> Self {
+        Self {
+            form: Some(form),
+            ..self
+        }
+    }
+}
+
+impl IntoFuture for RawRequest<'_> {
+    type Output = Result<(StatusCode, Bytes), ClientError>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let ratelimiter = RatelimiterKind::from_path(&self.method, &self.path);
+
+        let fut = self
+            .ordr
+            .raw_request(self.form, self.method, &self.path, ratelimiter);
+
+        Box::pin(async move {
+            let response = fut.await?;
+
+            Ok((response.status(), response.into_body()))
+        })
+    }
+}
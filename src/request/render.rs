@@ -1,14 +1,51 @@
-use std::future::IntoFuture;
+use std::{future::IntoFuture, sync::Arc};
+
+use hyper::{
+    body::Bytes,
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    Body, Response, StatusCode,
+};
 
 use crate::{
-    model::{RenderAdded, RenderOptions, RenderSkinOption},
+    client::error::ReplayUrlIssue,
+    model::{
+        BeatmapInfo, RenderAdded, RenderOptions, RenderRequirements, RenderSkinOption, Verification,
+    },
+    multipart::{Form, Part},
     routing::Route,
-    util::multipart::Form,
     ClientError, OrdrClient,
 };
 
+#[cfg(feature = "replay-meta")]
+use crate::model::{ReplayMeta, ReplayMetaError};
+
 use super::{OrdrFuture, Request};
 
+/// The username that commissions a render.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Username<'a> {
+    /// Use the given name.
+    Name(&'a str),
+    /// Use the player name of the replay file.
+    ///
+    /// Fails with [`ReplayMetaError::MissingReplayFile`](crate::model::ReplayMetaError::MissingReplayFile)
+    /// if the replay is specified as url.
+    #[cfg(feature = "replay-meta")]
+    FromReplay,
+}
+
+impl<'a> From<&'a str> for Username<'a> {
+    fn from(name: &'a str) -> Self {
+        Self::Name(name)
+    }
+}
+
+impl<'a> From<&'a String> for Username<'a> {
+    fn from(name: &'a String) -> Self {
+        Self::Name(name)
+    }
+}
+
 enum ReplaySource<'a> {
     File(&'a [u8]),
     Url(&'a str),
@@ -21,17 +58,24 @@ enum ReplaySource<'a> {
 pub struct CommissionRender<'a> {
     ordr: &'a OrdrClient,
     replay_source: ReplaySource<'a>,
-    username: &'a str,
-    skin: &'a RenderSkinOption<'a>,
+    username: Username<'a>,
+    skin: Option<&'a RenderSkinOption<'a>>,
     options: Option<&'a RenderOptions>,
+    requirements: Option<RenderRequirements>,
+    beatmap: Option<BeatmapInfo>,
+    validate_replay_url: bool,
+    replay_filename: &'a str,
+    replay_content_type: &'a str,
+    extra_fields: Vec<(&'a str, &'a str)>,
+    ratelimited: bool,
 }
 
 impl<'a> CommissionRender<'a> {
     pub(crate) const fn with_file(
         ordr: &'a OrdrClient,
         replay_file: &'a [u8],
-        username: &'a str,
-        skin: &'a RenderSkinOption<'a>,
+        username: Username<'a>,
+        skin: Option<&'a RenderSkinOption<'a>>,
     ) -> Self {
         Self {
             ordr,
@@ -39,14 +83,21 @@ impl<'a> CommissionRender<'a> {
             username,
             skin,
             options: None,
+            requirements: None,
+            beatmap: None,
+            validate_replay_url: false,
+            replay_filename: "replay.osr",
+            replay_content_type: "application/octet-stream",
+            extra_fields: Vec::new(),
+            ratelimited: true,
         }
     }
 
     pub(crate) const fn with_url(
         ordr: &'a OrdrClient,
         replay_url: &'a str,
-        username: &'a str,
-        skin: &'a RenderSkinOption<'a>,
+        username: Username<'a>,
+        skin: Option<&'a RenderSkinOption<'a>>,
     ) -> Self {
         Self {
             ordr,
@@ -54,48 +105,376 @@ impl<'a> CommissionRender<'a> {
             username,
             skin,
             options: None,
+            requirements: None,
+            beatmap: None,
+            validate_replay_url: false,
+            replay_filename: "replay.osr",
+            replay_content_type: "application/octet-stream",
+            extra_fields: Vec::new(),
+            ratelimited: true,
         }
     }
 
     /// Specify rendering options.
+    ///
+    /// Defaults to [`OrdrClientBuilder::default_render_options`](crate::client::OrdrClientBuilder::default_render_options)
+    /// if specified, otherwise o!rdr's defaults.
     pub fn options(mut self, options: &'a RenderOptions) -> Self {
         self.options = Some(options);
 
         self
     }
-}
 
-impl IntoFuture for &mut CommissionRender<'_> {
-    type Output = Result<RenderAdded, ClientError>;
-    type IntoFuture = OrdrFuture<RenderAdded>;
+    /// Before commissioning the render, check whether any render server can
+    /// currently fulfill the given requirements.
+    ///
+    /// If none can, the request fails with [`ClientError::UnfulfillableRequirements`]
+    /// without being sent.
+    pub fn requirements(mut self, requirements: RenderRequirements) -> Self {
+        self.requirements = Some(requirements);
 
-    fn into_future(self) -> Self::IntoFuture {
-        let mut form = self.options.map_or_else(Form::new, Form::serialize);
+        self
+    }
+
+    /// Before commissioning the render, check whether o!rdr would reject
+    /// the beatmap because it is too long or too difficult.
+    ///
+    /// If it would, the request fails with [`ClientError::BeatmapTooLong`]
+    /// or [`ClientError::StarRatingTooHigh`] without being sent.
+    pub fn beatmap(mut self, beatmap: BeatmapInfo) -> Self {
+        self.beatmap = Some(beatmap);
+
+        self
+    }
+
+    /// Before commissioning the render, send a `HEAD` request to the replay url
+    /// to check whether it is reachable and looks like a replay file.
+    ///
+    /// If it does not, the request fails with [`ClientError::InvalidReplayUrl`]
+    /// without being sent. Servers that don't support `HEAD` requests are
+    /// given the benefit of the doubt.
+    ///
+    /// Has no effect if the replay is specified as file.
+    pub fn validate_replay_url(mut self, validate: bool) -> Self {
+        self.validate_replay_url = validate;
+
+        self
+    }
+
+    /// Specify the filename of the uploaded replay file which o!rdr uses for diagnostics.
+    ///
+    /// Defaults to `replay.osr`. Has no effect if the replay is specified as url.
+    pub fn replay_filename(mut self, filename: &'a str) -> Self {
+        self.replay_filename = filename;
+
+        self
+    }
+
+    /// Specify the content type of the uploaded replay file.
+    ///
+    /// Defaults to `application/octet-stream`. Has no effect if the replay is specified as url.
+    pub fn replay_content_type(mut self, content_type: &'a str) -> Self {
+        self.replay_content_type = content_type;
+
+        self
+    }
+
+    /// Append a text field to the form, e.g. to use a parameter of o!rdr that
+    /// is not (yet) supported by this crate.
+    ///
+    /// Extra fields are added after all other fields except the verification
+    /// key. Adding a field that is already specified does not replace it.
+    pub fn extra_field(mut self, key: &'a str, value: &'a str) -> Self {
+        self.extra_fields.push((key, value));
+
+        self
+    }
+
+    /// Commission the render without acquiring a ratelimit permit
+    /// because the caller already acquired it.
+    pub(crate) fn without_ratelimit(mut self) -> Self {
+        self.ratelimited = false;
+
+        self
+    }
+
+    // Only fails when parsing the username from the replay
+    #[cfg_attr(not(feature = "replay-meta"), allow(clippy::unnecessary_wraps))]
+    fn form(&self) -> Result<Form, ClientError> {
+        let options = self.options.or_else(|| self.ordr.default_render_options());
+
+        let mut form = match options {
+            Some(options) if self.ordr.compact_render_options() => {
+                Form::serialize_changed(options, &RenderOptions::default())
+            }
+            Some(options) => Form::serialize(options),
+            None => Form::new(),
+        };
 
         match self.replay_source {
-            ReplaySource::File(bytes) => form.push_replay("replayFile", bytes),
-            ReplaySource::Url(url) => form.push_text("replayURL", url),
+            ReplaySource::File(bytes) => {
+                let part = Part::bytes(bytes)
+                    .filename(self.replay_filename)
+                    .content_type(self.replay_content_type);
+
+                form.part("replayFile", part)
+            }
+            ReplaySource::Url(url) => form.text("replayURL", url),
         };
 
-        form.push_text("username", self.username);
+        match self.username {
+            Username::Name(name) => {
+                form.text("username", name);
+            }
+            #[cfg(feature = "replay-meta")]
+            Username::FromReplay => {
+                let ReplaySource::File(bytes) = self.replay_source else {
+                    let source = ReplayMetaError::MissingReplayFile;
+
+                    return Err(ClientError::ReplayMeta { source });
+                };
+
+                match ReplayMeta::parse(bytes) {
+                    Ok(meta) => {
+                        form.text("username", meta.player_name.as_ref());
+                    }
+                    Err(source) => return Err(ClientError::ReplayMeta { source }),
+                }
+            }
+        }
 
-        match self.skin {
+        let default_skin = RenderSkinOption::default();
+
+        let skin = self
+            .skin
+            .or_else(|| self.ordr.default_skin())
+            .unwrap_or(&default_skin);
+
+        match skin {
             RenderSkinOption::Official { name } => {
-                form.push_text("skin", name.as_ref())
-                    .push_text("customSkin", "false");
+                form.text("skin", name.as_ref()).text("customSkin", "false");
             }
             RenderSkinOption::Custom { id } => {
-                form.push_text("skin", id.to_string())
-                    .push_text("customSkin", "true");
+                form.text("skin", &id.to_string())
+                    .text("customSkin", "true");
             }
         }
 
+        for (key, value) in &self.extra_fields {
+            form.text(key, value);
+        }
+
         if let Some(verification) = self.ordr.verification() {
-            form.push_text("verificationKey", verification.as_str());
+            form.text("verificationKey", verification.as_str());
+        }
+
+        Ok(form)
+    }
+}
+
+enum JobReplay {
+    File(Bytes),
+    Url(Box<str>),
+}
+
+/// An owned render request.
+///
+/// Unlike [`CommissionRender`], it does not borrow its data so it can be queued
+/// through a [`RequestQueue`](crate::client::RequestQueue) or an `Orchestrator`.
+pub struct RenderJob {
+    replay: JobReplay,
+    username: Box<str>,
+    skin: RenderSkinOption<'static>,
+    options: Option<RenderOptions>,
+    beatmap: Option<BeatmapInfo>,
+}
+
+impl RenderJob {
+    /// Render the given replay file.
+    pub fn with_file(
+        replay_file: impl Into<Bytes>,
+        username: impl Into<Box<str>>,
+        skin: RenderSkinOption<'static>,
+    ) -> Self {
+        Self {
+            replay: JobReplay::File(replay_file.into()),
+            username: username.into(),
+            skin,
+            options: None,
+            beatmap: None,
+        }
+    }
+
+    /// Render the replay behind the given url.
+    pub fn with_url(
+        replay_url: impl Into<Box<str>>,
+        username: impl Into<Box<str>>,
+        skin: RenderSkinOption<'static>,
+    ) -> Self {
+        Self {
+            replay: JobReplay::Url(replay_url.into()),
+            username: username.into(),
+            skin,
+            options: None,
+            beatmap: None,
+        }
+    }
+
+    /// Specify rendering options.
+    #[must_use]
+    pub fn options(self, options: RenderOptions) -> Self {
+        Self {
+            options: Some(options),
+            ..self
         }
+    }
 
-        self.ordr
-            .request(Request::builder(Route::Render).form(form).build())
+    /// Check the beatmap against o!rdr's limits before commissioning.
+    ///
+    /// See [`CommissionRender::beatmap`].
+    #[must_use]
+    pub fn beatmap(self, beatmap: BeatmapInfo) -> Self {
+        Self {
+            beatmap: Some(beatmap),
+            ..self
+        }
+    }
+
+    pub(crate) fn commission<'a>(&'a self, ordr: &'a OrdrClient) -> CommissionRender<'a> {
+        let username = Username::Name(&self.username);
+
+        let commission = match self.replay {
+            JobReplay::File(ref bytes) => {
+                CommissionRender::with_file(ordr, bytes, username, Some(&self.skin))
+            }
+            JobReplay::Url(ref url) => {
+                CommissionRender::with_url(ordr, url, username, Some(&self.skin))
+            }
+        };
+
+        let commission = match self.options {
+            Some(ref options) => commission.options(options),
+            None => commission,
+        };
+
+        match self.beatmap {
+            Some(beatmap) => commission.beatmap(beatmap),
+            None => commission,
+        }
+    }
+}
+
+impl IntoFuture for &mut CommissionRender<'_> {
+    type Output = Result<RenderAdded, ClientError>;
+    type IntoFuture = OrdrFuture<RenderAdded>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        if let Err(err) = self.ordr.error_rate_tracker().check() {
+            return OrdrFuture::error(err);
+        }
+
+        if let Some(Err(err)) = self.beatmap.as_ref().map(BeatmapInfo::check) {
+            return OrdrFuture::error(err);
+        }
+
+        let form = match self.form() {
+            Ok(form) => form,
+            Err(err) => return OrdrFuture::error(err),
+        };
+
+        let dev_mode = self
+            .ordr
+            .verification()
+            .is_some_and(Verification::is_dev_mode);
+
+        let mut req = Request::builder(Route::Render).form(form);
+
+        if !self.ratelimited {
+            req = req.without_ratelimit();
+        }
+
+        let mut fut = self.ordr.request(req.build());
+
+        if dev_mode {
+            fut = fut.map_err(ClientError::into_dev_mode);
+        } else {
+            fut = fut.error_rate(Arc::clone(self.ordr.error_rate_tracker()));
+        }
+
+        let replay_url = match self.replay_source {
+            ReplaySource::Url(url) if self.validate_replay_url => Some(Box::<str>::from(url)),
+            _ => None,
+        };
+
+        let requirements = self.requirements;
+
+        if replay_url.is_none() && requirements.is_none() {
+            return fut;
+        }
+
+        let ordr = self.ordr.clone();
+
+        fut.precheck(Box::pin(async move {
+            if let Some(replay_url) = replay_url {
+                ordr.validate_replay_url(&replay_url).await?;
+            }
+
+            match requirements {
+                Some(requirements) if !ordr.check_capability(requirements).await? => {
+                    Err(ClientError::UnfulfillableRequirements { requirements })
+                }
+                _ => Ok(()),
+            }
+        }))
+    }
+}
+
+/// Replay files are usually well below a megabyte so anything much larger
+/// is unlikely to be one.
+const MAX_REPLAY_SIZE: u64 = 32 * 1024 * 1024;
+
+/// Check the response to a `HEAD` request for a replay url.
+pub(crate) fn check_replay_head(response: &Response<Body>) -> Result<(), ReplayUrlIssue> {
+    let status = response.status();
+
+    if matches!(
+        status,
+        StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+    ) {
+        return Ok(());
+    }
+
+    if !status.is_success() {
+        return Err(ReplayUrlIssue::Status {
+            status_code: status.as_u16(),
+        });
+    }
+
+    let headers = response.headers();
+
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .map(str::trim);
+
+    if let Some(content_type) = content_type {
+        if content_type.starts_with("text/") || content_type.ends_with("json") {
+            return Err(ReplayUrlIssue::ContentType {
+                content_type: content_type.into(),
+            });
+        }
+    }
+
+    let size = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<u64>().ok());
+
+    match size {
+        Some(0) => Err(ReplayUrlIssue::Empty),
+        Some(size) if size > MAX_REPLAY_SIZE => Err(ReplayUrlIssue::TooLarge { size }),
+        _ => Ok(()),
     }
 }
 
@@ -107,3 +486,90 @@ impl IntoFuture for CommissionRender<'_> {
         (&mut self).into_future()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use hyper::{Body, Response, StatusCode};
+
+    use crate::{client::error::ReplayUrlIssue, model::BeatmapInfo, ClientError, OrdrClient};
+
+    use super::check_replay_head;
+
+    fn response(status: StatusCode, headers: &[(&str, &str)]) -> Response<Body> {
+        let mut builder = Response::builder().status(status);
+
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn replay_head() {
+        let ok = response(
+            StatusCode::OK,
+            &[
+                ("content-type", "application/octet-stream"),
+                ("content-length", "123456"),
+            ],
+        );
+        assert!(check_replay_head(&ok).is_ok());
+
+        let unsupported = response(StatusCode::METHOD_NOT_ALLOWED, &[]);
+        assert!(check_replay_head(&unsupported).is_ok());
+
+        let not_found = response(StatusCode::NOT_FOUND, &[]);
+        assert!(matches!(
+            check_replay_head(&not_found),
+            Err(ReplayUrlIssue::Status { status_code: 404 })
+        ));
+
+        let html = response(
+            StatusCode::OK,
+            &[("content-type", "text/html; charset=utf-8")],
+        );
+        assert!(matches!(
+            check_replay_head(&html),
+            Err(ReplayUrlIssue::ContentType { content_type }) if &*content_type == "text/html"
+        ));
+
+        let empty = response(StatusCode::OK, &[("content-length", "0")]);
+        assert!(matches!(
+            check_replay_head(&empty),
+            Err(ReplayUrlIssue::Empty)
+        ));
+    }
+
+    #[tokio::test]
+    // `Duration::from_mins` is only stable since Rust 1.91
+    #[allow(clippy::duration_suboptimal_units)]
+    async fn beatmap_precheck() {
+        let client = OrdrClient::new();
+        let beatmap = BeatmapInfo::new(Duration::from_secs(16 * 60));
+
+        // Fails without sending the request
+        let res = client
+            .render_with_replay_url("https://example.com/replay.osr", "user", None)
+            .beatmap(beatmap)
+            .await;
+
+        assert!(matches!(res, Err(ClientError::BeatmapTooLong { .. })));
+    }
+
+    #[tokio::test]
+    async fn extra_fields() {
+        let client = OrdrClient::new();
+
+        let commission = client
+            .render_with_replay_url("https://example.com/replay.osr", "user", None)
+            .extra_field("experimentalParam", "1");
+
+        let body = String::from_utf8(commission.form().unwrap().build()).unwrap();
+
+        assert!(body.contains("name=\"experimentalParam\"\r\n\r\n1\r\n"));
+        assert!(body.contains("name=\"replayURL\""));
+    }
+}
@@ -1,12 +1,24 @@
-use std::future::IntoFuture;
+use std::{
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
 
+use futures::Stream;
+use pin_project::pin_project;
 use serde::Serialize;
+use tokio::time::{self, Interval, MissedTickBehavior};
 
-use crate::{model::RenderList, routing::Route, ClientError, OrdrClient};
+use crate::{
+    model::{RawRenderList, Render, RenderList},
+    routing::Route,
+    ClientError, OrdrClient,
+};
 
 use super::{OrdrFuture, Request};
 
-#[derive(Serialize)]
+#[derive(Copy, Clone, Serialize)]
 struct GetRenderListFields<'a> {
     #[serde(rename = "pageSize")]
     page_size: Option<u32>,
@@ -24,6 +36,38 @@ struct GetRenderListFields<'a> {
     mapset_id: Option<u32>,
 }
 
+impl GetRenderListFields<'_> {
+    fn validate(&self) -> Result<(), ClientError> {
+        fn check(
+            name: &'static str,
+            value: Option<u32>,
+            min: u32,
+            max: u32,
+        ) -> Result<(), ClientError> {
+            match value {
+                Some(value) if !(min..=max).contains(&value) => {
+                    Err(ClientError::InvalidParameter {
+                        name,
+                        value,
+                        min,
+                        max,
+                    })
+                }
+                _ => Ok(()),
+            }
+        }
+
+        check(
+            "page_size",
+            self.page_size,
+            GetRenderList::MIN_PAGE_SIZE,
+            GetRenderList::MAX_PAGE_SIZE,
+        )?;
+
+        check("page", self.page, GetRenderList::MIN_PAGE, u32::MAX)
+    }
+}
+
 /// Get a [`RenderList`].
 #[must_use]
 pub struct GetRenderList<'a> {
@@ -32,6 +76,15 @@ pub struct GetRenderList<'a> {
 }
 
 impl<'a> GetRenderList<'a> {
+    /// The page size if none is specified.
+    pub const DEFAULT_PAGE_SIZE: u32 = 50;
+    /// The smallest accepted [`page_size`](GetRenderList::page_size).
+    pub const MIN_PAGE_SIZE: u32 = 1;
+    /// The largest accepted [`page_size`](GetRenderList::page_size).
+    pub const MAX_PAGE_SIZE: u32 = 50;
+    /// The first [`page`](GetRenderList::page); pages are 1-indexed.
+    pub const MIN_PAGE: u32 = 1;
+
     pub(crate) const fn new(ordr: &'a OrdrClient) -> Self {
         Self {
             ordr,
@@ -49,6 +102,10 @@ impl<'a> GetRenderList<'a> {
     }
 
     /// The number of renders the query will return you in the page. If not specified, 50 is the default.
+    ///
+    /// Must be within [`MIN_PAGE_SIZE`](GetRenderList::MIN_PAGE_SIZE) and
+    /// [`MAX_PAGE_SIZE`](GetRenderList::MAX_PAGE_SIZE), otherwise the request
+    /// fails with [`ClientError::InvalidParameter`].
     pub fn page_size(&mut self, page_size: u32) -> &mut Self {
         self.fields.page_size = Some(page_size);
         self.fields.page.get_or_insert(1);
@@ -57,6 +114,9 @@ impl<'a> GetRenderList<'a> {
     }
 
     /// The page.
+    ///
+    /// Must be at least [`MIN_PAGE`](GetRenderList::MIN_PAGE), otherwise the
+    /// request fails with [`ClientError::InvalidParameter`].
     pub fn page(&mut self, page: u32) -> &mut Self {
         self.fields.page = Some(page);
 
@@ -103,11 +163,77 @@ impl<'a> GetRenderList<'a> {
     }
 
     /// Get renders with this specific beatmapset ID
+    ///
+    /// To search for a single difficulty, see [`RenderListPages::map_id`].
     pub fn mapset_id(&mut self, mapset_id: u32) -> &mut Self {
         self.fields.mapset_id = Some(mapset_id);
 
         self
     }
+
+    /// Turn the request into a [`Stream`] of consecutive pages, starting at
+    /// the specified [`page`](GetRenderList::page) or the first one.
+    ///
+    /// The stream ends after the first page that holds fewer renders than
+    /// the [`page_size`](GetRenderList::page_size) or after an error.
+    pub fn pages(&self) -> RenderListPages<'a> {
+        RenderListPages {
+            ordr: self.ordr,
+            fields: self.fields,
+            next_page: Some(self.fields.page.unwrap_or(1)),
+            map_id: None,
+            fut: None,
+        }
+    }
+
+    /// Turn the request into a [`RenderListWatcher`] that polls the first
+    /// page every `interval` and only returns renders that are newer than
+    /// the ones seen so far.
+    ///
+    /// Useful to e.g. get notified when anyone renders a certain mapset
+    /// through [`mapset_id`](GetRenderList::mapset_id) without following all
+    /// events of the [`OrdrWebsocket`](crate::OrdrWebsocket).
+    ///
+    /// The specified [`page`](GetRenderList::page) is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn watch(&self, interval: Duration) -> RenderListWatcher<'a> {
+        let mut interval = time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        RenderListWatcher {
+            ordr: self.ordr,
+            fields: GetRenderListFields {
+                page: None,
+                ..self.fields
+            },
+            interval,
+            last_seen: None,
+        }
+    }
+
+    /// Request the [`RenderList`] without fully deserializing its renders.
+    ///
+    /// Useful for large pages when only few renders are of interest; each
+    /// [`RawRender`](crate::model::RawRender) can be deserialized
+    /// individually afterwards.
+    #[must_use]
+    pub fn raw(&self) -> OrdrFuture<RawRenderList> {
+        self.request()
+    }
+
+    fn request<T>(&self) -> OrdrFuture<T> {
+        if let Err(err) = self.fields.validate() {
+            return OrdrFuture::error(err);
+        }
+
+        match Request::builder(Route::RenderList).query(self.fields) {
+            Ok(builder) => self.ordr.request(builder.build()),
+            Err(err) => OrdrFuture::error(err),
+        }
+    }
 }
 
 impl IntoFuture for &mut GetRenderList<'_> {
@@ -115,10 +241,7 @@ impl IntoFuture for &mut GetRenderList<'_> {
     type IntoFuture = OrdrFuture<RenderList>;
 
     fn into_future(self) -> Self::IntoFuture {
-        match Request::builder(Route::RenderList).query(&self.fields) {
-            Ok(builder) => self.ordr.request(builder.build()),
-            Err(err) => OrdrFuture::error(err),
-        }
+        self.request()
     }
 }
 
@@ -130,3 +253,231 @@ impl IntoFuture for GetRenderList<'_> {
         (&mut self).into_future()
     }
 }
+
+/// [`Stream`] of consecutive [`RenderList`] pages.
+///
+/// Created through [`GetRenderList::pages`].
+#[must_use = "streams do nothing unless polled"]
+#[pin_project]
+pub struct RenderListPages<'a> {
+    ordr: &'a OrdrClient,
+    fields: GetRenderListFields<'a>,
+    next_page: Option<u32>,
+    map_id: Option<u32>,
+    #[pin]
+    fut: Option<OrdrFuture<RenderList>>,
+}
+
+impl RenderListPages<'_> {
+    /// Only keep renders of this specific beatmap ID.
+    ///
+    /// The API can only filter by [`mapset_id`](GetRenderList::mapset_id) so
+    /// this filter is applied client-side on each page. Every page still
+    /// has to be requested in full, meaning it may take many requests until
+    /// a matching render shows up and pages may end up empty. Prefer
+    /// combining it with [`mapset_id`](GetRenderList::mapset_id) to keep the
+    /// number of requests down.
+    ///
+    /// The [`max_renders`](RenderList::max_renders) of each page is left as
+    /// returned by the API.
+    pub fn map_id(mut self, map_id: u32) -> Self {
+        self.map_id = Some(map_id);
+
+        self
+    }
+}
+
+impl Stream for RenderListPages<'_> {
+    type Item = Result<RenderList, ClientError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if this.fut.is_none() {
+            let Some(page) = this.next_page.take() else {
+                return Poll::Ready(None);
+            };
+
+            this.fields.page = Some(page);
+
+            let req = GetRenderList {
+                ordr: this.ordr,
+                fields: *this.fields,
+            };
+
+            this.fut.set(Some(req.request()));
+        }
+
+        let Some(fut) = this.fut.as_mut().as_pin_mut() else {
+            return Poll::Ready(None);
+        };
+
+        let mut res = ready!(fut.poll(cx));
+        this.fut.set(None);
+
+        if let Ok(ref mut list) = res {
+            let page_size = this
+                .fields
+                .page_size
+                .unwrap_or(GetRenderList::DEFAULT_PAGE_SIZE);
+
+            if list.renders.len() >= page_size as usize {
+                *this.next_page = this.fields.page.map(|page| page + 1);
+            }
+
+            if let Some(map_id) = *this.map_id {
+                list.renders.retain(|render| render.map_id == map_id);
+            }
+        }
+
+        Poll::Ready(Some(res))
+    }
+}
+
+/// Polls the render list and returns renders that were not seen before.
+///
+/// Created through [`GetRenderList::watch`].
+#[must_use]
+pub struct RenderListWatcher<'a> {
+    ordr: &'a OrdrClient,
+    fields: GetRenderListFields<'a>,
+    interval: Interval,
+    last_seen: Option<u32>,
+}
+
+impl RenderListWatcher<'_> {
+    /// Further pages are requested while all renders of a page are new, up
+    /// to this many pages per poll.
+    pub const MAX_PAGES: u32 = 5;
+
+    /// Only return renders with a higher id than the given one.
+    ///
+    /// By default, the renders of the first poll are considered as already
+    /// seen and are not returned.
+    pub fn since(mut self, render_id: u32) -> Self {
+        self.last_seen = Some(render_id);
+
+        self
+    }
+
+    /// The highest render id seen so far.
+    #[must_use]
+    pub fn last_seen(&self) -> Option<u32> {
+        self.last_seen
+    }
+
+    /// Poll the render list until new renders show up and return them,
+    /// oldest first.
+    ///
+    /// Failed polls are returned as error; the next call continues watching.
+    pub async fn next(&mut self) -> Result<Vec<Render>, ClientError> {
+        loop {
+            self.interval.tick().await;
+
+            let renders = self.poll().await?;
+
+            if !renders.is_empty() {
+                return Ok(renders);
+            }
+        }
+    }
+
+    async fn poll(&mut self) -> Result<Vec<Render>, ClientError> {
+        let page_size = self
+            .fields
+            .page_size
+            .unwrap_or(GetRenderList::DEFAULT_PAGE_SIZE);
+
+        let mut renders = Vec::new();
+
+        for page in 1..=Self::MAX_PAGES {
+            let req = GetRenderList {
+                ordr: self.ordr,
+                fields: GetRenderListFields {
+                    page: Some(page),
+                    ..self.fields
+                },
+            };
+
+            let list: RenderList = req.request().await?;
+            let len = list.renders.len();
+
+            let Some(last_seen) = self.last_seen else {
+                // Nothing to compare against yet so only remember the latest render
+                self.last_seen = Some(
+                    list.renders
+                        .iter()
+                        .map(|render| render.id)
+                        .max()
+                        .unwrap_or(0),
+                );
+
+                return Ok(Vec::new());
+            };
+
+            renders.extend(
+                list.renders
+                    .into_iter()
+                    .filter(|render| render.id > last_seen),
+            );
+
+            if renders.len() < page as usize * page_size as usize || len < page_size as usize {
+                break;
+            }
+        }
+
+        renders.sort_unstable_by_key(|render| render.id);
+        renders.dedup_by_key(|render| render.id);
+
+        if let Some(last) = renders.last() {
+            self.last_seen = Some(last.id);
+        }
+
+        Ok(renders)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ClientError;
+
+    use super::{GetRenderList, GetRenderListFields};
+
+    fn fields(page_size: Option<u32>, page: Option<u32>) -> GetRenderListFields<'static> {
+        GetRenderListFields {
+            page_size,
+            page,
+            ordr_username: None,
+            replay_username: None,
+            render_id: None,
+            no_bots: None,
+            link: None,
+            mapset_id: None,
+        }
+    }
+
+    #[test]
+    fn validate_page_bounds() {
+        assert!(fields(None, None).validate().is_ok());
+        assert!(fields(Some(GetRenderList::MAX_PAGE_SIZE), Some(1))
+            .validate()
+            .is_ok());
+
+        let err = fields(Some(GetRenderList::MAX_PAGE_SIZE + 1), Some(1))
+            .validate()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ClientError::InvalidParameter {
+                name: "page_size",
+                ..
+            }
+        ));
+
+        let err = fields(Some(10), Some(0)).validate().unwrap_err();
+        assert!(matches!(
+            err,
+            ClientError::InvalidParameter { name: "page", .. }
+        ));
+    }
+}
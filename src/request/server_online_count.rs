@@ -1,6 +1,13 @@
-use std::future::IntoFuture;
+use std::{collections::VecDeque, future::IntoFuture, time::Duration};
 
-use crate::{model::ServerOnlineCount, request::Request, routing::Route, ClientError, OrdrClient};
+use tokio::time::{self, Interval, MissedTickBehavior};
+
+use crate::{
+    model::{ServerCountEvent, ServerOnlineCount},
+    request::Request,
+    routing::Route,
+    ClientError, OrdrClient,
+};
 
 use super::OrdrFuture;
 
@@ -14,6 +21,25 @@ impl<'a> GetServerOnlineCount<'a> {
     pub(crate) const fn new(ordr: &'a OrdrClient) -> Self {
         Self { ordr }
     }
+
+    /// Turn the request into a [`ServerCountWatcher`] that requests the
+    /// count every `interval` and returns when it crosses a threshold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn watch(&self, interval: Duration) -> ServerCountWatcher<'a> {
+        let mut interval = time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        ServerCountWatcher {
+            ordr: self.ordr,
+            interval,
+            thresholds: vec![ServerCountWatcher::DEFAULT_THRESHOLD],
+            count: None,
+            pending: VecDeque::new(),
+        }
+    }
 }
 
 impl IntoFuture for &mut GetServerOnlineCount<'_> {
@@ -34,3 +60,134 @@ impl IntoFuture for GetServerOnlineCount<'_> {
         (&mut self).into_future()
     }
 }
+
+/// Polls the amount of online servers and returns when it crosses a threshold.
+///
+/// Created through [`GetServerOnlineCount::watch`] or
+/// [`OrdrClient::watch_server_count`].
+#[must_use]
+pub struct ServerCountWatcher<'a> {
+    ordr: &'a OrdrClient,
+    interval: Interval,
+    /// Sorted in ascending order without duplicates.
+    thresholds: Vec<u32>,
+    count: Option<u32>,
+    pending: VecDeque<ServerCountEvent>,
+}
+
+impl ServerCountWatcher<'_> {
+    /// Only notify when all servers went offline or came back.
+    const DEFAULT_THRESHOLD: u32 = 1;
+
+    /// Notify whenever the count drops below or rises to one of the given
+    /// thresholds.
+    ///
+    /// Defaults to `[1]`, i.e. when the count drops to zero and when it
+    /// recovers from that. Thresholds of zero are ignored.
+    pub fn thresholds(mut self, thresholds: impl IntoIterator<Item = u32>) -> Self {
+        self.thresholds = thresholds
+            .into_iter()
+            .filter(|&threshold| threshold > 0)
+            .collect();
+        self.thresholds.sort_unstable();
+        self.thresholds.dedup();
+
+        self
+    }
+
+    /// The count of the latest poll.
+    #[must_use]
+    pub fn count(&self) -> Option<u32> {
+        self.count
+    }
+
+    /// Poll the count until it crosses a threshold and return how.
+    ///
+    /// Servers are assumed to be online before the first poll so that
+    /// watching during an outage returns [`ServerCountEvent::Dropped`] right
+    /// away. If multiple thresholds are crossed at once, the events are
+    /// returned by consecutive calls. Failed polls are returned as error;
+    /// the next call continues watching.
+    pub async fn next(&mut self) -> Result<ServerCountEvent, ClientError> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+
+            self.interval.tick().await;
+
+            let ServerOnlineCount(count) = GetServerOnlineCount::new(self.ordr).await?;
+            let previous = self.count.replace(count).unwrap_or(u32::MAX);
+
+            self.pending
+                .extend(crossed(&self.thresholds, previous, count));
+        }
+    }
+}
+
+/// The events of thresholds that are crossed by a change from `previous` to
+/// `count`, in the order they were crossed.
+fn crossed(
+    thresholds: &[u32],
+    previous: u32,
+    count: u32,
+) -> impl Iterator<Item = ServerCountEvent> + '_ {
+    let dropped = thresholds
+        .iter()
+        .rev()
+        .filter(move |&&threshold| count < threshold && threshold <= previous)
+        .map(move |&threshold| ServerCountEvent::Dropped { threshold, count });
+
+    let recovered = thresholds
+        .iter()
+        .filter(move |&&threshold| previous < threshold && threshold <= count)
+        .map(move |&threshold| ServerCountEvent::Recovered { threshold, count });
+
+    dropped.chain(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::ServerCountEvent;
+
+    use super::crossed;
+
+    #[test]
+    fn crossed_thresholds() {
+        let thresholds = [1, 3];
+        let events = |previous, count| crossed(&thresholds, previous, count).collect::<Vec<_>>();
+
+        assert!(events(5, 4).is_empty());
+        assert!(events(0, 0).is_empty());
+
+        assert_eq!(
+            events(u32::MAX, 0),
+            [
+                ServerCountEvent::Dropped {
+                    threshold: 3,
+                    count: 0
+                },
+                ServerCountEvent::Dropped {
+                    threshold: 1,
+                    count: 0
+                },
+            ]
+        );
+
+        assert_eq!(
+            events(0, 2),
+            [ServerCountEvent::Recovered {
+                threshold: 1,
+                count: 2
+            }]
+        );
+
+        assert_eq!(
+            events(2, 3),
+            [ServerCountEvent::Recovered {
+                threshold: 3,
+                count: 3
+            }]
+        );
+    }
+}
@@ -1,12 +1,18 @@
-use std::future::IntoFuture;
+use std::{collections::HashMap, future::IntoFuture, time::Duration};
 
 use serde::Serialize;
+use tokio::time::{self, Interval, MissedTickBehavior};
 
-use crate::{model::SkinList, request::Request, routing::Route, ClientError, OrdrClient};
+use crate::{
+    model::{Skin, SkinChange, SkinList},
+    request::Request,
+    routing::Route,
+    ClientError, OrdrClient,
+};
 
 use super::OrdrFuture;
 
-#[derive(Serialize)]
+#[derive(Copy, Clone, Serialize)]
 struct GetSkinListFields<'a> {
     #[serde(rename = "pageSize")]
     page_size: Option<u32>,
@@ -54,6 +60,29 @@ impl<'a> GetSkinList<'a> {
 
         self
     }
+
+    /// Turn the request into a [`SkinListWatcher`] that requests all pages
+    /// every `interval` and returns which skins were added or updated.
+    ///
+    /// Each poll requests every page so it counts towards the skins
+    /// ratelimit multiple times; prefer a generous interval and a large
+    /// [`page_size`](GetSkinList::page_size). The specified
+    /// [`page`](GetSkinList::page) is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn watch(&self, interval: Duration) -> SkinListWatcher<'a> {
+        let mut interval = time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        SkinListWatcher {
+            ordr: self.ordr,
+            fields: self.fields,
+            interval,
+            known: None,
+        }
+    }
 }
 
 impl IntoFuture for &mut GetSkinList<'_> {
@@ -61,7 +90,7 @@ impl IntoFuture for &mut GetSkinList<'_> {
     type IntoFuture = OrdrFuture<SkinList>;
 
     fn into_future(self) -> Self::IntoFuture {
-        match Request::builder(Route::SkinList).query(&self.fields) {
+        match Request::builder(Route::SkinList).query(self.fields) {
             Ok(builder) => self.ordr.request(builder.build()),
             Err(err) => OrdrFuture::error(err),
         }
@@ -76,3 +105,142 @@ impl IntoFuture for GetSkinList<'_> {
         (&mut self).into_future()
     }
 }
+
+/// Polls the skin list and returns the differences to the previous poll.
+///
+/// Created through [`GetSkinList::watch`].
+#[must_use]
+pub struct SkinListWatcher<'a> {
+    ordr: &'a OrdrClient,
+    fields: GetSkinListFields<'a>,
+    interval: Interval,
+    known: Option<HashMap<u32, Skin>>,
+}
+
+impl SkinListWatcher<'_> {
+    /// The page size if none is specified.
+    const DEFAULT_PAGE_SIZE: u32 = 100;
+
+    /// Poll the skin list until skins were added or updated and return
+    /// those changes.
+    ///
+    /// The skins of the first poll are considered as known and are not
+    /// returned. Failed polls are returned as error; the next call continues
+    /// watching.
+    pub async fn next(&mut self) -> Result<Vec<SkinChange>, ClientError> {
+        loop {
+            self.interval.tick().await;
+
+            let skins = self.poll().await?;
+
+            let Some(ref mut known) = self.known else {
+                self.known = Some(skins.into_iter().map(|skin| (skin.id, skin)).collect());
+
+                continue;
+            };
+
+            let changes = diff(known, skins);
+
+            if !changes.is_empty() {
+                return Ok(changes);
+            }
+        }
+    }
+
+    async fn poll(&self) -> Result<Vec<Skin>, ClientError> {
+        let page_size = self.fields.page_size.unwrap_or(Self::DEFAULT_PAGE_SIZE);
+        let mut skins = Vec::new();
+
+        for page in 1.. {
+            let mut req = GetSkinList {
+                ordr: self.ordr,
+                fields: GetSkinListFields {
+                    page_size: Some(page_size),
+                    page: Some(page),
+                    ..self.fields
+                },
+            };
+
+            let list = (&mut req).await?;
+            let len = list.skins.len();
+            skins.extend(list.skins);
+
+            if len < page_size as usize || skins.len() >= list.max_skins as usize {
+                break;
+            }
+        }
+
+        Ok(skins)
+    }
+}
+
+/// Update the known skins and return which ones were added or updated.
+fn diff(known: &mut HashMap<u32, Skin>, skins: Vec<Skin>) -> Vec<SkinChange> {
+    let mut changes = Vec::new();
+
+    for skin in skins {
+        match known.get_mut(&skin.id) {
+            Some(old) if old.same_content(&skin) => *old = skin,
+            Some(old) => {
+                let old = std::mem::replace(old, skin.clone());
+                changes.push(SkinChange::Updated { old, new: skin });
+            }
+            None => {
+                known.insert(skin.id, skin.clone());
+                changes.push(SkinChange::Added(skin));
+            }
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::model::{Skin, SkinChange};
+
+    use super::diff;
+
+    fn skin(id: u32, version: &str, times_used: u32) -> Skin {
+        Skin {
+            skin: format!("skin{id}").into(),
+            presentation_name: format!("Skin {id}").into(),
+            url: Box::from(""),
+            high_res_preview: Box::from(""),
+            low_res_preview: Box::from(""),
+            grid_preview: Box::from(""),
+            id,
+            author: Box::from("author"),
+            modified: false,
+            version: version.into(),
+            alphabetical_id: id,
+            times_used,
+        }
+    }
+
+    #[test]
+    fn diff_skins() {
+        let mut known: HashMap<_, _> = [(1, skin(1, "1.0", 5)), (2, skin(2, "1.0", 5))].into();
+
+        let changes = diff(
+            &mut known,
+            vec![skin(1, "1.0", 6), skin(2, "2.0", 5), skin(3, "1.0", 0)],
+        );
+
+        assert_eq!(
+            changes,
+            [
+                SkinChange::Updated {
+                    old: skin(2, "1.0", 5),
+                    new: skin(2, "2.0", 5),
+                },
+                SkinChange::Added(skin(3, "1.0", 0)),
+            ]
+        );
+
+        assert_eq!(known[&1].times_used, 6);
+        assert!(diff(&mut known, vec![skin(3, "1.0", 1)]).is_empty());
+    }
+}
@@ -2,19 +2,42 @@ use std::fmt::{Display, Formatter, Result as FmtResult};
 
 use hyper::Method;
 
-use crate::client::RatelimiterKind;
+use crate::client::{RatelimitBucket, RatelimiterKind};
 
+/// Endpoints of the o!rdr API.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub(crate) enum Route {
+#[non_exhaustive]
+pub enum Route {
+    /// [`OrdrClient::render_with_replay_file`](crate::OrdrClient::render_with_replay_file)
+    /// and [`OrdrClient::render_with_replay_url`](crate::OrdrClient::render_with_replay_url)
     Render,
+    /// [`OrdrClient::render_list`](crate::OrdrClient::render_list)
     RenderList,
+    /// [`OrdrClient::server_list`](crate::OrdrClient::server_list)
     ServerList,
+    /// [`OrdrClient::server_online_count`](crate::OrdrClient::server_online_count)
     ServerOnlineCount,
+    /// [`OrdrClient::skin_list`](crate::OrdrClient::skin_list)
     SkinList,
+    /// [`OrdrClient::custom_skin_info`](crate::OrdrClient::custom_skin_info)
     SkinCustom,
 }
 
 impl Route {
+    /// The path of the endpoint relative to the base url, e.g. `"skins/custom"`.
+    #[must_use]
+    pub const fn path(self) -> &'static str {
+        match self {
+            Self::Render | Self::RenderList => "renders",
+            Self::ServerList => "servers",
+            Self::ServerOnlineCount => "servers/onlinecount",
+            Self::SkinList => "skins",
+            Self::SkinCustom => "skins/custom",
+        }
+    }
+
+    /// The HTTP method of the endpoint.
+    #[must_use]
     pub fn method(self) -> Method {
         match self {
             Self::Render => Method::POST,
@@ -26,26 +49,33 @@ impl Route {
         }
     }
 
-    pub fn ratelimiter(self) -> RatelimiterKind {
+    /// The [`RatelimitBucket`] that requests to the endpoint count towards.
+    ///
+    /// Returns `None` for [`Route::Render`] which is limited through
+    /// [`OrdrClientBuilder::render_ratelimit`](crate::client::OrdrClientBuilder::render_ratelimit)
+    /// instead.
+    #[must_use]
+    pub const fn bucket(self) -> Option<RatelimitBucket> {
+        match self {
+            Self::Render => None,
+            Self::RenderList => Some(RatelimitBucket::RenderList),
+            Self::ServerList | Self::ServerOnlineCount => Some(RatelimitBucket::Servers),
+            Self::SkinList | Self::SkinCustom => Some(RatelimitBucket::Skins),
+        }
+    }
+
+    pub(crate) fn ratelimiter(self) -> RatelimiterKind {
         match self {
             Route::Render => RatelimiterKind::SendRender,
-            Route::RenderList
-            | Route::ServerList
-            | Route::ServerOnlineCount
-            | Route::SkinList
-            | Route::SkinCustom => RatelimiterKind::General,
+            Route::RenderList => RatelimiterKind::RenderList,
+            Route::ServerList | Route::ServerOnlineCount => RatelimiterKind::Servers,
+            Route::SkinList | Route::SkinCustom => RatelimiterKind::Skins,
         }
     }
 }
 
 impl Display for Route {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        match self {
-            Self::Render | Self::RenderList => f.write_str("renders"),
-            Self::ServerList => f.write_str("servers"),
-            Self::ServerOnlineCount => f.write_str("servers/onlinecount"),
-            Self::SkinList => f.write_str("skins"),
-            Self::SkinCustom => f.write_str("skins/custom"),
-        }
+        f.write_str(self.path())
     }
 }
@@ -0,0 +1,240 @@
+//! All-in-one setup of the client, websocket, and render tracking.
+
+use std::{future::Future, mem, sync::Arc};
+
+use thiserror::Error as ThisError;
+use tokio::sync::broadcast::Sender;
+
+use crate::{
+    client::{error::ConfigError, OrdrClientBuilder, OrdrConfig},
+    tracker::{Orchestrator, RenderTracker},
+    websocket::{
+        event::RawEvent,
+        hub::{EventHub, HubReceiver},
+        ConnectionEvent, ConnectionEvents, HubConfig, HubStats,
+    },
+    OrdrClient, OrdrWebsocket, WebsocketError,
+};
+
+/// Reasons why [`OrdrService::from_config`] failed.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum ServiceConfigError {
+    #[error("Invalid config")]
+    Config(#[from] ConfigError),
+    #[error("Failed to connect the websocket")]
+    Websocket(#[from] WebsocketError),
+}
+
+/// Bundles an [`OrdrClient`] with an [`OrdrWebsocket`] whose events are
+/// handled in a background task.
+///
+/// Events are forwarded to the [`RenderTracker`] and to every [`ServiceEvents`]
+/// receiver so that the websocket does not need to be wired up manually.
+///
+/// Cheap to clone. The background task is aborted once all clones
+/// and all clones of the [`tracker`](OrdrService::tracker) are dropped.
+///
+/// # Example
+/// ```no_run
+/// use rosu_render::{model::RenderSkinOption, OrdrClient, OrdrService};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let service = OrdrService::connect(OrdrClient::builder()).await?;
+///
+/// let mut events = service.events();
+///
+/// tokio::spawn(async move {
+///     while let Some(event) = events.recv().await {
+///         println!("{:?}", event.kind());
+///     }
+/// });
+///
+/// let skin = RenderSkinOption::default();
+/// let render = service
+///     .client()
+///     .render_with_replay_url("https://example.com/replay.osr", "my-bot", &skin)
+///     .await?;
+///
+/// let done = service.tracker().subscribe(render.render_id).wait().await?;
+/// println!("{}", done.video_url);
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct OrdrService {
+    client: OrdrClient,
+    tracker: RenderTracker,
+    events: Arc<EventHub>,
+    connection_events: Sender<ConnectionEvent>,
+}
+
+impl OrdrService {
+    /// Build the client, connect to the websocket, and start handling events.
+    ///
+    /// Must be called within a tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the client fails to build, see [`OrdrClientBuilder::build`].
+    pub async fn connect(builder: OrdrClientBuilder) -> Result<Self, WebsocketError> {
+        Self::connect_with_hub(builder, HubConfig::default()).await
+    }
+
+    /// Same as [`OrdrService::connect`] but events are distributed to
+    /// [`ServiceEvents`] as specified by the given [`HubConfig`].
+    ///
+    /// Must be called within a tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the client fails to build, see [`OrdrClientBuilder::build`].
+    pub async fn connect_with_hub(
+        builder: OrdrClientBuilder,
+        hub: HubConfig,
+    ) -> Result<Self, WebsocketError> {
+        Self::connect_with(builder.build(), OrdrWebsocket::connect(), hub).await
+    }
+
+    /// Build the client and connect to the websocket based on the given
+    /// [`OrdrConfig`], then start handling events.
+    ///
+    /// Must be called within a tokio runtime.
+    pub async fn from_config(mut config: OrdrConfig) -> Result<Self, ServiceConfigError> {
+        let websocket = OrdrWebsocket::builder()
+            .reconnect(mem::take(&mut config.reconnect))
+            .ip_preference(config.ip_preference)
+            .connect();
+
+        let hub = mem::take(&mut config.hub);
+        let client = OrdrClientBuilder::from_config(config)?.try_build()?;
+
+        Self::connect_with(client, websocket, hub)
+            .await
+            .map_err(ServiceConfigError::from)
+    }
+
+    async fn connect_with(
+        client: OrdrClient,
+        websocket: impl Future<Output = Result<OrdrWebsocket, WebsocketError>>,
+        hub: HubConfig,
+    ) -> Result<Self, WebsocketError> {
+        let websocket = websocket.await?;
+        let events = Arc::new(EventHub::new(hub));
+        let connection_events = websocket.connection_events_sender();
+        let tracker = RenderTracker::with_events(websocket, Arc::clone(&events));
+
+        Ok(Self {
+            client,
+            tracker,
+            events,
+            connection_events,
+        })
+    }
+
+    /// The [`OrdrClient`] to send requests with.
+    #[must_use]
+    pub fn client(&self) -> &OrdrClient {
+        &self.client
+    }
+
+    /// The [`RenderTracker`] to subscribe to individual renders with.
+    #[must_use]
+    pub fn tracker(&self) -> &RenderTracker {
+        &self.tracker
+    }
+
+    /// Receive all websocket events from now on.
+    ///
+    /// Each call creates a new independent receiver.
+    #[must_use]
+    pub fn events(&self) -> ServiceEvents {
+        ServiceEvents {
+            rx: self.events.subscribe(),
+        }
+    }
+
+    /// Statistics of the events distributed to [`ServiceEvents`] and of
+    /// each receiver, e.g. to detect slow event handlers.
+    #[must_use]
+    pub fn event_stats(&self) -> HubStats {
+        self.events.stats()
+    }
+
+    /// Receive changes of the websocket's connection state from now on.
+    ///
+    /// Each call creates a new independent receiver.
+    #[must_use]
+    pub fn connection_events(&self) -> ConnectionEvents {
+        ConnectionEvents {
+            rx: self.connection_events.subscribe(),
+        }
+    }
+
+    /// Create an [`Orchestrator`] based on the service's client and tracker.
+    pub fn orchestrator(&self) -> Orchestrator {
+        Orchestrator::new(self.client.clone(), self.tracker.clone())
+    }
+}
+
+/// Receiver of websocket events of an [`OrdrService`].
+///
+/// Created through [`OrdrService::events`].
+pub struct ServiceEvents {
+    rx: HubReceiver,
+}
+
+impl ServiceEvents {
+    /// Await the next event.
+    ///
+    /// If the receiver falls behind by more than the configured capacity,
+    /// the [`OverflowPolicy`](crate::websocket::OverflowPolicy) applies.
+    ///
+    /// Returns `None` once the service's background task stopped or the
+    /// receiver was disconnected.
+    pub async fn recv(&mut self) -> Option<RawEvent> {
+        self.rx.recv().await
+    }
+
+    /// The amount of events that were dropped because this receiver fell behind.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.rx.dropped()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use crate::websocket::{event::RawEvent, hub::EventHub, HubConfig};
+
+    use super::ServiceEvents;
+
+    #[tokio::test]
+    async fn skip_lagged_events() {
+        let hub = EventHub::new(HubConfig {
+            capacity: 1,
+            ..HubConfig::default()
+        });
+
+        let mut events = ServiceEvents {
+            rx: hub.subscribe(),
+        };
+
+        for render_id in [1, 2] {
+            let bytes = format!(r#"["render_done_json",{{"renderID":{render_id}}}]"#);
+            let event = RawEvent::from_bytes(Bytes::from(bytes)).unwrap();
+            hub.publish(&event).await;
+        }
+
+        drop(hub);
+        assert_eq!(events.dropped(), 1);
+
+        let Some(RawEvent::RenderDone(event)) = events.recv().await else {
+            panic!("expected render done");
+        };
+
+        assert_eq!(event.render_id, 2);
+        assert!(events.recv().await.is_none());
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::model::{RenderProgress, RenderResolution, RenderServers, ServerName};
+
+/// Weight of a newly completed render in the learned render speeds.
+const LEARNING_RATE: f64 = 0.2;
+
+/// Percentage from which on the progress of a render is considered
+/// for its estimation.
+const MIN_PERCENTAGE: f32 = 5.0;
+
+/// Features of a render that its duration depends on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderProfile {
+    /// Length of the replay's beatmap.
+    pub map_length: Duration,
+    /// Resolution of the video.
+    pub resolution: RenderResolution,
+}
+
+impl RenderProfile {
+    #[must_use]
+    pub fn new(map_length: Duration, resolution: RenderResolution) -> Self {
+        Self {
+            map_length,
+            resolution,
+        }
+    }
+}
+
+/// Estimates how long renders take until they are done.
+///
+/// Learns from completed renders how long rendering a second of a beatmap
+/// takes for each resolution. Until then, or for renders without
+/// [`RenderProfile`], the average times of the render servers are used if
+/// they were provided through [`EtaEstimator::update_servers`].
+///
+/// Once a render reports its percentage, the estimation additionally
+/// extrapolates its progress so far.
+#[derive(Default)]
+pub struct EtaEstimator {
+    state: Mutex<EtaState>,
+}
+
+#[derive(Default)]
+struct EtaState {
+    /// Learned render seconds per second of beatmap length.
+    speeds: HashMap<RenderResolution, f64>,
+    /// Average total time per render server.
+    servers: HashMap<ServerName, Duration>,
+    /// Average total time across all render servers.
+    server_average: Option<Duration>,
+    renders: HashMap<u32, RenderEstimate>,
+}
+
+struct RenderEstimate {
+    started: Instant,
+    profile: Option<RenderProfile>,
+    renderer: Option<ServerName>,
+    percentage: Option<f32>,
+}
+
+impl RenderEstimate {
+    fn new(started: Instant) -> Self {
+        Self {
+            started,
+            profile: None,
+            renderer: None,
+            percentage: None,
+        }
+    }
+}
+
+impl EtaEstimator {
+    /// Specify the features of a render to improve its estimation and to
+    /// learn from it once it's done.
+    pub fn set_profile(&self, render_id: u32, profile: RenderProfile) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        state
+            .renders
+            .entry(render_id)
+            .or_insert_with(|| RenderEstimate::new(Instant::now()))
+            .profile = Some(profile);
+    }
+
+    /// Use the average times of the given render servers as long as
+    /// nothing better is known.
+    pub fn update_servers(&self, servers: &RenderServers) {
+        let times: HashMap<_, _> = servers
+            .servers
+            .iter()
+            .filter(|server| server.enabled && server.total_avg_time > 0.0)
+            .map(|server| {
+                let time = Duration::from_secs_f32(server.total_avg_time);
+
+                (server.name.clone(), time)
+            })
+            .collect();
+
+        let server_average = u32::try_from(times.len())
+            .ok()
+            .filter(|len| *len > 0)
+            .map(|len| times.values().sum::<Duration>() / len);
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.servers = times;
+        state.server_average = server_average;
+    }
+
+    /// The estimated remaining duration until the render is done.
+    ///
+    /// Returns `None` if the render is not tracked or nothing is known yet
+    /// to base an estimation on.
+    #[must_use]
+    pub fn eta(&self, render_id: u32) -> Option<Duration> {
+        self.eta_at(render_id, Instant::now())
+    }
+
+    /// The learned render duration per second of beatmap length for the
+    /// given resolution.
+    #[must_use]
+    pub fn speed(&self, resolution: &RenderResolution) -> Option<f64> {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        state.speeds.get(resolution).copied()
+    }
+
+    pub(super) fn start(&self, render_id: u32) {
+        self.start_at(render_id, Instant::now());
+    }
+
+    pub(super) fn progress(&self, progress: &RenderProgress) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let Some(render) = state.renders.get_mut(&progress.render_id) else {
+            return;
+        };
+
+        render.renderer = Some(progress.renderer.clone());
+
+        if let Some(percentage) = progress.percentage() {
+            render.percentage = Some(percentage);
+        }
+    }
+
+    pub(super) fn done(&self, render_id: u32) {
+        self.done_at(render_id, Instant::now());
+    }
+
+    pub(super) fn forget(&self, render_id: u32) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.renders.remove(&render_id);
+    }
+
+    fn start_at(&self, render_id: u32, now: Instant) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        state
+            .renders
+            .entry(render_id)
+            .or_insert_with(|| RenderEstimate::new(now));
+    }
+
+    fn done_at(&self, render_id: u32, now: Instant) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let Some(render) = state.renders.remove(&render_id) else {
+            return;
+        };
+
+        let Some(profile) = render.profile else {
+            return;
+        };
+
+        if profile.map_length.is_zero() {
+            return;
+        }
+
+        let elapsed = now.duration_since(render.started);
+        let speed = elapsed.as_secs_f64() / profile.map_length.as_secs_f64();
+
+        state
+            .speeds
+            .entry(profile.resolution)
+            .and_modify(|learned| *learned += LEARNING_RATE * (speed - *learned))
+            .or_insert(speed);
+    }
+
+    fn eta_at(&self, render_id: u32, now: Instant) -> Option<Duration> {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let render = state.renders.get(&render_id)?;
+        let elapsed = now.duration_since(render.started);
+
+        let total = render
+            .profile
+            .as_ref()
+            .and_then(|profile| {
+                let speed = state.speeds.get(&profile.resolution)?;
+
+                Some(profile.map_length.mul_f64(*speed))
+            })
+            .or_else(|| {
+                render
+                    .renderer
+                    .as_ref()
+                    .and_then(|renderer| state.servers.get(renderer))
+                    .copied()
+            })
+            .or(state.server_average);
+
+        let extrapolated = render
+            .percentage
+            .filter(|percentage| *percentage >= MIN_PERCENTAGE)
+            .map(|percentage| {
+                let remaining = (100.0 - percentage.min(100.0)) / percentage;
+
+                (elapsed.mul_f32(remaining), percentage / 100.0)
+            });
+
+        match (total, extrapolated) {
+            (Some(total), Some((extrapolated, weight))) => {
+                let estimated = total.saturating_sub(elapsed);
+
+                Some(extrapolated.mul_f32(weight) + estimated.mul_f32(1.0 - weight.min(1.0)))
+            }
+            (Some(total), None) => Some(total.saturating_sub(elapsed)),
+            (None, Some((extrapolated, _))) => Some(extrapolated),
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+// `Duration::from_mins` is only stable since Rust 1.91
+#[allow(clippy::duration_suboptimal_units)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use crate::model::{RenderProgress, RenderResolution, ServerName};
+
+    use super::{EtaEstimator, RenderProfile};
+
+    fn progress(render_id: u32, progress: &str) -> RenderProgress {
+        RenderProgress {
+            description: Box::from(""),
+            progress: Box::from(progress),
+            render_id,
+            renderer: ServerName::from("server"),
+            username: Box::from("user"),
+        }
+    }
+
+    #[test]
+    fn learn_from_done_renders() {
+        let estimator = EtaEstimator::default();
+        let start = Instant::now();
+        let profile = RenderProfile::new(Duration::from_secs(60), RenderResolution::HD720);
+
+        estimator.start_at(1, start);
+        estimator.set_profile(1, profile.clone());
+        assert_eq!(estimator.eta_at(1, start), None);
+
+        estimator.done_at(1, start + Duration::from_secs(2 * 60));
+        assert_eq!(estimator.speed(&RenderResolution::HD720), Some(2.0));
+        assert_eq!(estimator.eta_at(1, start), None);
+
+        let start = start + Duration::from_secs(200);
+        estimator.start_at(2, start);
+        estimator.set_profile(
+            2,
+            RenderProfile::new(Duration::from_secs(30), profile.resolution),
+        );
+
+        let eta = estimator.eta_at(2, start + Duration::from_secs(10));
+        assert_eq!(eta, Some(Duration::from_secs(50)));
+    }
+
+    #[test]
+    fn extrapolate_progress() {
+        let estimator = EtaEstimator::default();
+        let start = Instant::now();
+
+        estimator.start_at(1, start);
+        estimator.progress(&progress(1, "Rendering... (25%)"));
+
+        let eta = estimator.eta_at(1, start + Duration::from_secs(30));
+        assert_eq!(eta, Some(Duration::from_secs(90)));
+
+        estimator.forget(1);
+        assert_eq!(estimator.eta_at(1, start), None);
+    }
+}
@@ -0,0 +1,841 @@
+//! Tracking of commissioned renders through the [`OrdrWebsocket`].
+
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error as StdError,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use thiserror::Error as ThisError;
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedSender},
+        oneshot,
+    },
+    task::JoinHandle,
+    time,
+};
+
+use crate::{
+    model::{RenderDone, RenderFailed, RenderProgress},
+    request::RenderJob,
+    websocket::{
+        event::{RawEvent, RawRenderAdded},
+        hub::EventHub,
+        DevEvents, EventSource,
+    },
+    ClientError, OrdrClient, OrdrWebsocket, WebsocketError,
+};
+
+pub use self::{
+    eta::{EtaEstimator, RenderProfile},
+    orchestrator::{JobId, Orchestrator},
+    retry::{RenderRetried, RetryPolicy},
+    store::{FileJobStore, JobState, JobStore, MemoryJobStore},
+    subscription::RenderSubscription,
+};
+
+mod eta;
+mod orchestrator;
+mod retry;
+mod store;
+mod subscription;
+
+use self::retry::Resubmit;
+
+/// Keeps an [`EventSource`], usually the [`OrdrWebsocket`], busy in a
+/// background task and forwards events of subscribed renders to their
+/// [`RenderSubscription`].
+///
+/// Cheap to clone. The background task is aborted once all clones are dropped.
+#[derive(Clone)]
+pub struct RenderTracker {
+    inner: Arc<TrackerRef>,
+    retry: Option<RetryPolicy>,
+}
+
+struct TrackerRef {
+    subscribers: Arc<Subscribers>,
+    task: JoinHandle<()>,
+}
+
+impl Drop for TrackerRef {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl RenderTracker {
+    /// Connect to the o!rdr websocket and start tracking.
+    ///
+    /// Must be called within a tokio runtime.
+    pub async fn connect() -> Result<Self, WebsocketError> {
+        OrdrWebsocket::connect().await.map(Self::new)
+    }
+
+    /// Start tracking through the given [`EventSource`], e.g. an [`OrdrWebsocket`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn new(source: impl EventSource) -> Self {
+        Self::with_subscribers(source, Subscribers::default(), None)
+    }
+
+    /// Track renders through the events of a
+    /// [`DevEventSimulator`](crate::websocket::DevEventSimulator) instead of
+    /// the o!rdr websocket.
+    ///
+    /// Equivalent to [`RenderTracker::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    #[must_use]
+    pub fn simulated(events: DevEvents) -> Self {
+        Self::new(events)
+    }
+
+    /// Start tracking through the given [`EventSource`] and persist tracked
+    /// renders in the given [`JobStore`].
+    ///
+    /// Use [`RenderTracker::resume`] to continue tracking stored renders.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn with_store(source: impl EventSource, store: impl JobStore + 'static) -> Self {
+        let subscribers = Subscribers {
+            store: Some(Box::new(store)),
+            ..Subscribers::default()
+        };
+
+        Self::with_subscribers(source, subscribers, None)
+    }
+
+    /// Start tracking through the given websocket and additionally send
+    /// all received events through the given hub.
+    pub(crate) fn with_events(websocket: OrdrWebsocket, events: Arc<EventHub>) -> Self {
+        Self::with_subscribers(websocket, Subscribers::default(), Some(events))
+    }
+
+    fn with_subscribers(
+        source: impl EventSource,
+        subscribers: Subscribers,
+        events: Option<Arc<EventHub>>,
+    ) -> Self {
+        let subscribers = Arc::new(subscribers);
+        let task = tokio::spawn(event_loop(source, Arc::clone(&subscribers), events));
+
+        Self {
+            inner: Arc::new(TrackerRef { subscribers, task }),
+            retry: None,
+        }
+    }
+
+    /// Commission renders through [`RenderTracker::commission`] again if they
+    /// fail for transient reasons.
+    ///
+    /// Only applies to this handle and its clones.
+    #[must_use]
+    pub fn retry_policy(self, policy: RetryPolicy) -> Self {
+        Self {
+            retry: Some(policy),
+            ..self
+        }
+    }
+
+    /// Commission the job and subscribe to the events of its render.
+    ///
+    /// If a [`RetryPolicy`] was specified, the render is commissioned again
+    /// after transient failures and the subscription continues with the new
+    /// render, yielding [`RenderUpdate::Retried`] in between.
+    pub async fn commission(
+        &self,
+        ordr: &OrdrClient,
+        job: RenderJob,
+    ) -> Result<RenderSubscription, ClientError> {
+        let added = job.commission(ordr).await?;
+        let subscription = self.subscribe(added.render_id);
+
+        let Some(policy) = self.retry else {
+            return Ok(subscription);
+        };
+
+        let resubmit = Resubmit::new(ordr.clone(), job, self.clone(), policy);
+
+        Ok(subscription.resubmit(resubmit))
+    }
+
+    /// Subscribe to the events of the given render.
+    ///
+    /// Progress events that were received before subscribing are not replayed.
+    /// A [`RenderDone`] or [`RenderFailed`] event that arrived shortly before
+    /// is, e.g. if the render failed right after being commissioned.
+    #[must_use]
+    pub fn subscribe(&self, render_id: u32) -> RenderSubscription {
+        self.inner.subscribers.subscribe(render_id, true)
+    }
+
+    /// Wait until the websocket confirms that the given render was added.
+    ///
+    /// o!rdr emits a [`RenderAdded`](crate::model::RenderAdded) event for every
+    /// successfully commissioned render. Awaiting it verifies that events are
+    /// delivered before relying on them for the render's completion.
+    ///
+    /// Events that arrived shortly before calling this method are taken into
+    /// account as well.
+    ///
+    /// Returns `false` if no such event was received within the timeout, e.g.
+    /// because the websocket's [`EventMask`](crate::websocket::event::EventMask)
+    /// excludes [`EventKind::RenderAdded`](crate::websocket::event::EventKind::RenderAdded).
+    pub async fn confirm_added(&self, render_id: u32, timeout: Duration) -> bool {
+        let subscribers = &self.inner.subscribers;
+
+        let Some(rx) = subscribers.await_added(render_id) else {
+            return true;
+        };
+
+        let confirmed = matches!(time::timeout(timeout, rx).await, Ok(Ok(())));
+
+        if !confirmed {
+            subscribers.remove_closed_waiters(render_id);
+        }
+
+        confirmed
+    }
+
+    /// Stop tracking the given render.
+    ///
+    /// Its subscriptions receive [`RenderUpdate::Cancelled`] as terminal update and
+    /// it is removed from the [`JobStore`].
+    ///
+    /// The o!rdr API does not provide a way to cancel commissioned renders so the
+    /// render itself keeps going, its events are just no longer forwarded.
+    ///
+    /// Returns `false` if the render was not tracked.
+    #[allow(clippy::must_use_candidate)]
+    pub fn cancel(&self, render_id: u32) -> bool {
+        self.inner.subscribers.cancel(render_id)
+    }
+
+    /// The estimated remaining duration until the given render is done.
+    ///
+    /// Returns `None` if the render is not tracked or nothing is known yet
+    /// to base an estimation on. See [`EtaEstimator`] for how to improve
+    /// estimations.
+    #[must_use]
+    pub fn eta(&self, render_id: u32) -> Option<Duration> {
+        self.inner.subscribers.eta.eta(render_id)
+    }
+
+    /// The [`EtaEstimator`] that learns from tracked renders.
+    #[must_use]
+    pub fn estimator(&self) -> &EtaEstimator {
+        &self.inner.subscribers.eta
+    }
+
+    /// Subscribe to all renders of the [`JobStore`], e.g. after a restart.
+    ///
+    /// Returns an empty list if the tracker was created without store.
+    pub fn resume(&self) -> Result<Vec<RenderSubscription>, Box<dyn StdError + Send + Sync>> {
+        let subscribers = &self.inner.subscribers;
+
+        let Some(ref store) = subscribers.store else {
+            return Ok(Vec::new());
+        };
+
+        let subscriptions = store
+            .load()?
+            .into_iter()
+            .map(|(render_id, _)| subscribers.subscribe(render_id, false))
+            .collect();
+
+        Ok(subscriptions)
+    }
+}
+
+async fn event_loop(
+    mut source: impl EventSource,
+    subscribers: Arc<Subscribers>,
+    events: Option<Arc<EventHub>>,
+) {
+    while let Some(res) = source.next_event().await {
+        match res {
+            Ok(event) => {
+                subscribers.dispatch(&event);
+
+                if let Some(ref events) = events {
+                    events.publish(&event).await;
+                }
+            }
+            Err(err @ WebsocketError::ReconnectExhausted { .. }) => {
+                warn!(?err, "Stopped tracking renders");
+
+                return;
+            }
+            Err(err) => warn!(?err, "Websocket error while tracking renders"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Subscribers {
+    senders: Mutex<HashMap<u32, TrackedRender>>,
+    /// Terminal events of renders without subscriber which are replayed to
+    /// subscriptions that are created shortly after.
+    ///
+    /// Only locked while holding the lock of `senders` so that an event is
+    /// either dispatched or remembered for the next subscription.
+    recent_terminal: Mutex<VecDeque<(u32, RawEvent)>>,
+    added: Mutex<AddedRenders>,
+    store: Option<Box<dyn JobStore>>,
+    eta: EtaEstimator,
+}
+
+#[derive(Default)]
+struct TrackedRender {
+    senders: Vec<UnboundedSender<RenderUpdate>>,
+    /// Whether the render was saved as [`JobState::Rendering`] already.
+    rendering: bool,
+}
+
+/// Renders whose [`RenderAdded`](crate::model::RenderAdded) event was
+/// received recently or is being awaited.
+#[derive(Default)]
+struct AddedRenders {
+    recent: VecDeque<u32>,
+    waiters: HashMap<u32, Vec<oneshot::Sender<()>>>,
+}
+
+impl AddedRenders {
+    /// How many added renders are remembered for confirmations that are
+    /// requested after the event was received.
+    const HISTORY: usize = 128;
+}
+
+impl Subscribers {
+    /// How many terminal events of renders without subscriber are remembered.
+    const TERMINAL_HISTORY: usize = 128;
+
+    fn subscribe(self: &Arc<Self>, render_id: u32, persist: bool) -> RenderSubscription {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        if persist {
+            self.save(render_id, &JobState::Commissioned);
+        }
+
+        self.eta.start(render_id);
+
+        let replay = {
+            let mut senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+            senders.entry(render_id).or_default().senders.push(tx);
+
+            let mut recent = self
+                .recent_terminal
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+
+            recent
+                .iter()
+                .position(|(id, _)| *id == render_id)
+                .and_then(|idx| recent.remove(idx))
+        };
+
+        let subscription = RenderSubscription::new(render_id, rx, Arc::downgrade(self));
+
+        if let Some((_, event)) = replay {
+            self.dispatch(&event);
+        }
+
+        subscription
+    }
+
+    fn dispatch(&self, event: &RawEvent) {
+        let (render_id, terminal) = match event {
+            RawEvent::RenderProgress(event) => (event.render_id, false),
+            RawEvent::RenderDone(event) => (event.render_id, true),
+            RawEvent::RenderFailed(event) => (event.render_id, true),
+            RawEvent::RenderAdded(event) => return self.confirm_added(event),
+            _ => return,
+        };
+
+        let mut senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let Some(tracked) = senders.get_mut(&render_id) else {
+            // The render may be subscribed to right after commissioning it
+            if terminal {
+                let mut recent = self
+                    .recent_terminal
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+
+                if recent.len() == Self::TERMINAL_HISTORY {
+                    recent.pop_front();
+                }
+
+                recent.push_back((render_id, event.clone()));
+            }
+
+            return;
+        };
+
+        let update_res = match event {
+            RawEvent::RenderProgress(event) => event.deserialize().map(RenderUpdate::Progress),
+            RawEvent::RenderDone(event) => event.deserialize().map(RenderUpdate::Done),
+            RawEvent::RenderFailed(event) => event.deserialize().map(RenderUpdate::Failed),
+            _ => unreachable!(),
+        };
+
+        let update = match update_res {
+            Ok(update) => update,
+            Err(err) => {
+                warn!(render_id, ?err, "Failed to deserialize render event");
+
+                // No further events will arrive for the render anyway
+                if terminal {
+                    senders.remove(&render_id);
+                    drop(senders);
+                    self.forget(render_id);
+                }
+
+                return;
+            }
+        };
+
+        tracked.senders.retain(|tx| tx.send(update.clone()).is_ok());
+
+        match update {
+            RenderUpdate::Progress(ref progress) => self.eta.progress(progress),
+            RenderUpdate::Done(_) => self.eta.done(render_id),
+            _ => {}
+        }
+
+        // The store is only called after releasing the lock and only for
+        // state changes, not for every progress update
+        if terminal || tracked.senders.is_empty() {
+            senders.remove(&render_id);
+            drop(senders);
+            self.forget(render_id);
+        } else if let RenderUpdate::Progress(ref progress) = update {
+            if tracked.rendering {
+                return;
+            }
+
+            tracked.rendering = true;
+            drop(senders);
+
+            let state = JobState::Rendering {
+                progress: progress.progress.clone(),
+            };
+
+            self.save(render_id, &state);
+        }
+    }
+
+    fn confirm_added(&self, event: &RawRenderAdded) {
+        let render_id = match event.deserialize() {
+            Ok(added) => added.render_id,
+            Err(err) => {
+                warn!(?err, "Failed to deserialize render added event");
+
+                return;
+            }
+        };
+
+        let mut added = self.added.lock().unwrap_or_else(PoisonError::into_inner);
+
+        for tx in added.waiters.remove(&render_id).into_iter().flatten() {
+            let _ = tx.send(());
+        }
+
+        if added.recent.len() == AddedRenders::HISTORY {
+            added.recent.pop_front();
+        }
+
+        added.recent.push_back(render_id);
+    }
+
+    /// Returns `None` if the render was added recently.
+    fn await_added(&self, render_id: u32) -> Option<oneshot::Receiver<()>> {
+        let mut added = self.added.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if added.recent.contains(&render_id) {
+            return None;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        added.waiters.entry(render_id).or_default().push(tx);
+
+        Some(rx)
+    }
+
+    fn remove_closed_waiters(&self, render_id: u32) {
+        let mut added = self.added.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(waiters) = added.waiters.get_mut(&render_id) {
+            waiters.retain(|tx| !tx.is_closed());
+
+            if waiters.is_empty() {
+                added.waiters.remove(&render_id);
+            }
+        }
+    }
+
+    fn cancel(&self, render_id: u32) -> bool {
+        let removed = self
+            .senders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&render_id);
+
+        let Some(tracked) = removed else {
+            return false;
+        };
+
+        for tx in tracked.senders {
+            let _ = tx.send(RenderUpdate::Cancelled);
+        }
+
+        self.forget(render_id);
+
+        true
+    }
+
+    /// Remove senders whose subscription no longer receives updates and stop
+    /// tracking the render if none are left.
+    fn release(&self, render_id: u32) {
+        let mut senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let Some(tracked) = senders.get_mut(&render_id) else {
+            return;
+        };
+
+        tracked.senders.retain(|tx| !tx.is_closed());
+
+        if tracked.senders.is_empty() {
+            senders.remove(&render_id);
+            drop(senders);
+            self.forget(render_id);
+        }
+    }
+
+    /// Remove the render from the [`JobStore`] and the [`EtaEstimator`].
+    fn forget(&self, render_id: u32) {
+        self.remove(render_id);
+        self.eta.forget(render_id);
+    }
+
+    fn save(&self, render_id: u32, state: &JobState) {
+        if let Some(ref store) = self.store {
+            if let Err(err) = store.save(render_id, state) {
+                warn!(render_id, ?err, "Failed to save render in job store");
+            }
+        }
+    }
+
+    fn remove(&self, render_id: u32) {
+        if let Some(ref store) = self.store {
+            if let Err(err) = store.remove(render_id) {
+                warn!(render_id, ?err, "Failed to remove render from job store");
+            }
+        }
+    }
+}
+
+/// An update of a tracked render.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RenderUpdate {
+    Progress(RenderProgress),
+    Done(RenderDone),
+    Failed(RenderFailed),
+    /// The render failed and was commissioned again as specified through
+    /// [`RenderTracker::retry_policy`]. Further updates belong to the new render.
+    Retried(RenderRetried),
+    /// Tracking was stopped through [`RenderTracker::cancel`].
+    Cancelled,
+    /// No update was received within the duration specified through
+    /// [`RenderSubscription::stall_timeout`].
+    Stalled,
+    /// The render did not complete within the duration specified through
+    /// [`RenderSubscription::with_timeout`].
+    TimedOut,
+    /// The render was removed from o!rdr before completing.
+    ///
+    /// Only detected through [`RenderSubscription::poll_on_stall`].
+    Removed,
+}
+
+impl RenderUpdate {
+    /// Whether no further updates will follow.
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Done(_) | Self::Failed(_) | Self::Cancelled | Self::TimedOut | Self::Removed
+        )
+    }
+}
+
+/// Reasons why a render did not complete.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum RenderFailure {
+    #[error("Tracking the render was cancelled")]
+    Cancelled,
+    #[error("Failed to commission the render")]
+    Commission {
+        #[source]
+        source: ClientError,
+    },
+    #[error("The render failed: {}", .0.error_message)]
+    Failed(RenderFailed),
+    #[error("The render did not complete in time")]
+    TimedOut,
+    #[error("The render was removed before completing")]
+    Removed,
+    #[error("The tracker stopped before the render completed")]
+    TrackerClosed,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use bytes::Bytes;
+    use tokio::time;
+
+    use crate::{
+        websocket::{event::RawEvent, DevEventSimulator},
+        OrdrWebsocket,
+    };
+
+    use super::{JobState, MemoryJobStore, RenderTracker, RenderUpdate, Subscribers};
+
+    fn event(bytes: &'static [u8]) -> RawEvent {
+        RawEvent::from_bytes(Bytes::from_static(bytes)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn dispatch_to_subscriber() {
+        let subscribers = Arc::new(Subscribers::default());
+        let mut subscription = subscribers.subscribe(42, true);
+
+        subscribers.dispatch(&event(
+            br#"["render_done_json",{"renderID":41,"videoUrl":"https://link.issou.best/a"}]"#,
+        ));
+        subscribers.dispatch(&event(
+            br#"["render_done_json",{"renderID":42,"videoUrl":"https://link.issou.best/b"}]"#,
+        ));
+
+        let Some(RenderUpdate::Done(done)) = subscription.next().await else {
+            panic!("expected render done");
+        };
+
+        assert_eq!(done.render_id, 42);
+        assert!(subscription.next().await.is_none());
+        assert!(subscribers.senders.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn track_simulated_render() {
+        let (simulator, events) = DevEventSimulator::new();
+        let tracker = RenderTracker::simulated(events);
+
+        let added = simulator.progress_steps(2).simulate_success();
+        let done = tracker.subscribe(added.render_id).wait().await.unwrap();
+
+        assert_eq!(done.render_id, added.render_id);
+    }
+
+    #[tokio::test]
+    async fn track_local_websocket() {
+        let websocket = OrdrWebsocket::from_events([event(
+            br#"["render_done_json",{"renderID":42,"videoUrl":"https://link.issou.best/a"}]"#,
+        )]);
+
+        // The current-thread runtime only starts the event loop once we await
+        let tracker = RenderTracker::new(websocket);
+        let done = tracker.subscribe(42).wait().await.unwrap();
+
+        assert_eq!(done.render_id, 42);
+    }
+
+    #[tokio::test]
+    async fn confirm_added_render() {
+        let (simulator, events) = DevEventSimulator::new();
+        let tracker = RenderTracker::simulated(events);
+
+        let added = simulator.simulate_success();
+        let timeout = Duration::from_secs(5);
+
+        assert!(tracker.confirm_added(added.render_id, timeout).await);
+
+        // Confirmations requested after the event was received
+        assert!(tracker.confirm_added(added.render_id, timeout).await);
+
+        let missing = added.render_id + 1;
+        assert!(
+            !tracker
+                .confirm_added(missing, Duration::from_millis(10))
+                .await
+        );
+        assert!(tracker
+            .inner
+            .subscribers
+            .added
+            .lock()
+            .unwrap()
+            .waiters
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn estimate_subscribed_render() {
+        let subscribers = Arc::new(Subscribers::default());
+        let _subscription = subscribers.subscribe(42, true);
+
+        assert_eq!(subscribers.eta.eta(42), None);
+
+        subscribers.dispatch(&event(
+            br#"["render_progress_json",{"renderID":42,"progress":"Rendering... (50%)","description":"","renderer":"server","username":"user"}]"#,
+        ));
+
+        assert!(subscribers.eta.eta(42).is_some());
+
+        subscribers.dispatch(&event(
+            br#"["render_done_json",{"renderID":42,"videoUrl":"https://link.issou.best/a"}]"#,
+        ));
+
+        assert_eq!(subscribers.eta.eta(42), None);
+    }
+
+    #[tokio::test]
+    async fn persist_state_changes() {
+        let subscribers = Arc::new(Subscribers {
+            store: Some(Box::new(MemoryJobStore::new())),
+            ..Default::default()
+        });
+        let _subscription = subscribers.subscribe(42, true);
+
+        let load = || subscribers.store.as_ref().unwrap().load().unwrap();
+        assert_eq!(load(), [(42, JobState::Commissioned)]);
+
+        subscribers.dispatch(&event(
+            br#"["render_progress_json",{"renderID":42,"progress":"Rendering... (25%)","description":"","renderer":"server","username":"user"}]"#,
+        ));
+        subscribers.dispatch(&event(
+            br#"["render_progress_json",{"renderID":42,"progress":"Rendering... (50%)","description":"","renderer":"server","username":"user"}]"#,
+        ));
+
+        // Only the transition into rendering is persisted
+        let rendering = JobState::Rendering {
+            progress: Box::from("Rendering... (25%)"),
+        };
+        assert_eq!(load(), [(42, rendering)]);
+
+        subscribers.dispatch(&event(
+            br#"["render_done_json",{"renderID":42,"videoUrl":"https://link.issou.best/a"}]"#,
+        ));
+
+        assert!(load().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_failure_before_subscribing() {
+        let subscribers = Arc::new(Subscribers {
+            store: Some(Box::new(MemoryJobStore::new())),
+            ..Default::default()
+        });
+
+        // The render failed between commissioning it and subscribing to it
+        subscribers.dispatch(&event(
+            br#"["render_failed_json",{"renderID":42,"errorCode":2,"errorMessage":"failed"}]"#,
+        ));
+
+        let mut subscription = subscribers.subscribe(42, true);
+
+        let next = time::timeout(Duration::from_secs(5), subscription.next()).await;
+
+        let Ok(Some(RenderUpdate::Failed(failed))) = next else {
+            panic!("expected render failed");
+        };
+
+        assert_eq!(failed.render_id, 42);
+        assert!(subscription.next().await.is_none());
+        assert!(subscribers.senders.lock().unwrap().is_empty());
+        assert!(subscribers.recent_terminal.lock().unwrap().is_empty());
+        assert!(subscribers
+            .store
+            .as_ref()
+            .unwrap()
+            .load()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_subscription() {
+        let subscribers = Arc::new(Subscribers::default());
+        let mut subscription = subscribers.subscribe(42, true);
+
+        assert!(subscribers.cancel(42));
+        assert!(!subscribers.cancel(42));
+        assert_eq!(subscription.next().await, Some(RenderUpdate::Cancelled));
+        assert!(subscription.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn release_locally_finished_render() {
+        let subscribers = Arc::new(Subscribers {
+            store: Some(Box::new(MemoryJobStore::new())),
+            ..Default::default()
+        });
+
+        let load = || subscribers.store.as_ref().unwrap().load().unwrap();
+
+        let mut subscription = subscribers
+            .subscribe(42, true)
+            .with_timeout(Duration::from_millis(10));
+
+        assert_eq!(subscription.next().await, Some(RenderUpdate::TimedOut));
+        assert!(load().is_empty());
+        assert!(subscribers.senders.lock().unwrap().is_empty());
+
+        let subscription = subscribers.subscribe(43, true);
+        assert_eq!(load(), [(43, JobState::Commissioned)]);
+
+        drop(subscription);
+        assert!(load().is_empty());
+        assert!(subscribers.senders.lock().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "mock-server")]
+    #[tokio::test]
+    async fn release_removed_render() {
+        let server = crate::mock::MockServer::start().await.unwrap();
+
+        let subscribers = Arc::new(Subscribers {
+            store: Some(Box::new(MemoryJobStore::new())),
+            ..Default::default()
+        });
+
+        // The mock server responds to the render list with 404
+        let mut subscription = subscribers
+            .subscribe(42, true)
+            .stall_timeout(Duration::from_millis(10))
+            .poll_on_stall(server.client().build());
+
+        assert_eq!(subscription.next().await, Some(RenderUpdate::Removed));
+        assert!(subscribers
+            .store
+            .as_ref()
+            .unwrap()
+            .load()
+            .unwrap()
+            .is_empty());
+        assert!(subscribers.senders.lock().unwrap().is_empty());
+    }
+}
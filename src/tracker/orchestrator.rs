@@ -0,0 +1,178 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter, Result as FmtResult},
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{stream::FuturesUnordered, Stream};
+
+use crate::{model::RenderDone, request::RenderJob, OrdrClient};
+
+use super::{RenderFailure, RenderTracker};
+
+type JobFuture = Pin<Box<dyn Future<Output = (JobId, Result<RenderDone, RenderFailure>)> + Send>>;
+
+/// Identifies a [`RenderJob`] pushed onto an [`Orchestrator`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobId(u64);
+
+impl JobId {
+    /// The numeric value of the id.
+    #[must_use]
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for JobId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl RenderJob {
+    async fn run(
+        self,
+        ordr: OrdrClient,
+        tracker: RenderTracker,
+    ) -> Result<RenderDone, RenderFailure> {
+        tracker
+            .commission(&ordr, self)
+            .await
+            .map_err(|source| RenderFailure::Commission { source })?
+            .wait()
+            .await
+    }
+}
+
+/// Commissions many [`RenderJob`]s and awaits their completion.
+///
+/// At most [`concurrency`](Orchestrator::concurrency) renders are in flight at
+/// once; further jobs are queued. Commissioning still respects the ratelimits
+/// of the [`OrdrClient`]. Results are yielded in order of completion.
+///
+/// # Example
+/// ```no_run
+/// use rosu_render::{
+///     model::RenderSkinOption,
+///     request::RenderJob,
+///     tracker::{Orchestrator, RenderTracker},
+///     OrdrClient,
+/// };
+///
+/// # async fn example(replays: Vec<Vec<u8>>) -> Result<(), Box<dyn std::error::Error>> {
+/// let tracker = RenderTracker::connect().await?;
+/// let mut orchestrator = Orchestrator::new(OrdrClient::new(), tracker).concurrency(2);
+///
+/// for replay in replays {
+///     orchestrator.push(RenderJob::with_file(replay, "my-bot", RenderSkinOption::default()));
+/// }
+///
+/// while let Some((job_id, res)) = orchestrator.next().await {
+///     match res {
+///         Ok(done) => println!("Job {job_id}: {}", done.video_url),
+///         Err(err) => println!("Job {job_id} failed: {err}"),
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+#[must_use]
+pub struct Orchestrator {
+    ordr: OrdrClient,
+    tracker: RenderTracker,
+    concurrency: usize,
+    queued: VecDeque<(JobId, RenderJob)>,
+    running: FuturesUnordered<JobFuture>,
+    next_id: u64,
+}
+
+impl Orchestrator {
+    const DEFAULT_CONCURRENCY: usize = 4;
+
+    /// Create a new [`Orchestrator`] that runs up to 4 renders concurrently.
+    pub fn new(ordr: OrdrClient, tracker: RenderTracker) -> Self {
+        Self {
+            ordr,
+            tracker,
+            concurrency: Self::DEFAULT_CONCURRENCY,
+            queued: VecDeque::new(),
+            running: FuturesUnordered::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Specify how many renders may be in flight at once. Values below 1 are treated as 1.
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            ..self
+        }
+    }
+
+    /// Queue a job. It is commissioned once a slot frees up and the returned
+    /// stream is polled.
+    pub fn push(&mut self, job: RenderJob) -> JobId {
+        let id = JobId(self.next_id);
+        self.next_id += 1;
+        self.queued.push_back((id, job));
+
+        id
+    }
+
+    /// The amount of jobs that have not been commissioned yet.
+    #[must_use]
+    pub fn queued(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// The amount of jobs that are currently being commissioned or rendered.
+    #[must_use]
+    pub fn running(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Await the next completed job.
+    ///
+    /// Returns `None` once all jobs are done.
+    pub async fn next(&mut self) -> Option<(JobId, Result<RenderDone, RenderFailure>)> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    fn fill_slots(&mut self) {
+        while self.running.len() < self.concurrency {
+            let Some((id, job)) = self.queued.pop_front() else {
+                break;
+            };
+
+            let fut = job.run(self.ordr.clone(), self.tracker.clone());
+            self.running.push(Box::pin(async move { (id, fut.await) }));
+        }
+    }
+}
+
+impl Stream for Orchestrator {
+    type Item = (JobId, Result<RenderDone, RenderFailure>);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.fill_slots();
+
+        match Pin::new(&mut this.running).poll_next(cx) {
+            Poll::Ready(Some(item)) => {
+                this.fill_slots();
+
+                Poll::Ready(Some(item))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.queued.len() + self.running.len();
+
+        (len, Some(len))
+    }
+}
@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use tokio::time::{self, Instant};
+
+use crate::{model::RenderFailed, request::RenderJob, OrdrClient};
+
+use super::{RenderSubscription, RenderTracker};
+
+/// Specifies whether renders that failed for transient reasons, e.g. because
+/// all beatmap mirrors were unavailable, are commissioned again.
+///
+/// Only failures for which [`RenderFailed::is_retryable`] holds are retried.
+/// Note that o!rdr may reject renders of replays that failed recently so the
+/// delay should not be too short.
+///
+/// Set through [`RenderTracker::retry_policy`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    delay: Duration,
+}
+
+impl RetryPolicy {
+    const DEFAULT_DELAY: Duration = Duration::from_secs(30);
+
+    /// Commission failed renders again up to `max_attempts` times.
+    ///
+    /// The delay before commissioning again defaults to 30 seconds.
+    #[must_use]
+    pub const fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            delay: Self::DEFAULT_DELAY,
+        }
+    }
+
+    /// Specify the delay between a failure and commissioning the render again.
+    #[must_use]
+    pub const fn delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    /// The maximum amount of times a render is commissioned again.
+    #[must_use]
+    pub const fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    fn should_retry(&self, attempts: u32, failed: &RenderFailed) -> bool {
+        attempts < self.max_attempts && failed.is_retryable()
+    }
+}
+
+/// A render failed for a transient reason and was commissioned again.
+///
+/// Yielded as [`RenderUpdate::Retried`](super::RenderUpdate::Retried).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderRetried {
+    /// The failure of the previous render.
+    pub failed: RenderFailed,
+    /// The id of the newly commissioned render.
+    pub render_id: u32,
+    /// How often the render has been commissioned again, starting at 1.
+    pub attempt: u32,
+}
+
+/// The outcome of [`Resubmit::retry`].
+pub(super) enum Retry {
+    /// The render was commissioned again.
+    Retried(RenderRetried, RenderSubscription),
+    /// The render is not commissioned again.
+    Skipped,
+    /// The deadline passed while waiting to commission the render again.
+    TimedOut,
+}
+
+/// Everything needed to commission a render again.
+pub(super) struct Resubmit {
+    ordr: OrdrClient,
+    job: RenderJob,
+    tracker: RenderTracker,
+    policy: RetryPolicy,
+    attempts: u32,
+}
+
+impl Resubmit {
+    pub(super) fn new(
+        ordr: OrdrClient,
+        job: RenderJob,
+        tracker: RenderTracker,
+        policy: RetryPolicy,
+    ) -> Self {
+        Self {
+            ordr,
+            job,
+            tracker,
+            policy,
+            attempts: 0,
+        }
+    }
+
+    /// Commission the render again if the policy allows it.
+    ///
+    /// Gives up if the deadline passes before the policy's delay elapsed.
+    pub(super) async fn retry(
+        &mut self,
+        failed: &RenderFailed,
+        deadline: Option<Instant>,
+    ) -> Retry {
+        if !self.policy.should_retry(self.attempts, failed) {
+            return Retry::Skipped;
+        }
+
+        let delay = time::sleep(self.policy.delay);
+
+        if let Some(deadline) = deadline {
+            if time::timeout_at(deadline, delay).await.is_err() {
+                return Retry::TimedOut;
+            }
+        } else {
+            delay.await;
+        }
+
+        let added = match self.job.commission(&self.ordr).await {
+            Ok(added) => added,
+            Err(err) => {
+                warn!(
+                    render_id = failed.render_id,
+                    ?err,
+                    "Failed to commission render again"
+                );
+
+                return Retry::Skipped;
+            }
+        };
+
+        self.attempts += 1;
+
+        let retried = RenderRetried {
+            failed: failed.clone(),
+            render_id: added.render_id,
+            attempt: self.attempts,
+        };
+
+        Retry::Retried(retried, self.tracker.subscribe(added.render_id))
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    fs,
+    io::{Error as IoError, ErrorKind},
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+
+type BoxedError = Box<dyn StdError + Send + Sync>;
+
+/// The persisted state of a tracked render.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum JobState {
+    /// The render was commissioned but no progress has been received yet.
+    Commissioned,
+    /// The render is in progress.
+    Rendering {
+        /// The progress description when rendering started.
+        progress: Box<str>,
+    },
+}
+
+/// Persists the renders that a [`RenderTracker`](super::RenderTracker) is waiting on.
+///
+/// Renders are saved when subscribed to, updated once they start rendering,
+/// and removed once they complete. Further progress is not saved. After a restart, [`RenderTracker::resume`](super::RenderTracker::resume)
+/// subscribes to all renders of the store again.
+///
+/// Errors while saving or removing are logged but do not interrupt tracking.
+pub trait JobStore: Send + Sync {
+    /// Save or overwrite the state of a render.
+    fn save(&self, render_id: u32, state: &JobState) -> Result<(), BoxedError>;
+
+    /// Remove a render.
+    fn remove(&self, render_id: u32) -> Result<(), BoxedError>;
+
+    /// Load all stored renders.
+    fn load(&self) -> Result<Vec<(u32, JobState)>, BoxedError>;
+}
+
+/// A [`JobStore`] that only keeps renders in memory.
+#[derive(Debug, Default)]
+pub struct MemoryJobStore {
+    jobs: Mutex<HashMap<u32, JobState>>,
+}
+
+impl MemoryJobStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl JobStore for MemoryJobStore {
+    fn save(&self, render_id: u32, state: &JobState) -> Result<(), BoxedError> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(render_id, state.clone());
+
+        Ok(())
+    }
+
+    fn remove(&self, render_id: u32) -> Result<(), BoxedError> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&render_id);
+
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<(u32, JobState)>, BoxedError> {
+        let jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+
+        Ok(jobs
+            .iter()
+            .map(|(id, state)| (*id, state.clone()))
+            .collect())
+    }
+}
+
+/// A [`JobStore`] that persists renders as JSON file.
+///
+/// The whole file is rewritten on every change by writing to a temporary
+/// file first and then renaming it so that a crash never leaves a partial file.
+#[derive(Debug)]
+pub struct FileJobStore {
+    path: PathBuf,
+    jobs: Mutex<HashMap<u32, JobState>>,
+}
+
+impl FileJobStore {
+    /// Open the store at the given path. If the file does not exist yet, the store is empty.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, IoError> {
+        let path = path.into();
+
+        let jobs = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Self {
+            path,
+            jobs: Mutex::new(jobs),
+        })
+    }
+
+    fn persist(&self, jobs: &HashMap<u32, JobState>) -> Result<(), IoError> {
+        let bytes = serde_json::to_vec(jobs)?;
+        let tmp_path = self.path.with_extension("tmp");
+
+        fs::write(&tmp_path, bytes)?;
+
+        fs::rename(tmp_path, &self.path)
+    }
+}
+
+impl JobStore for FileJobStore {
+    fn save(&self, render_id: u32, state: &JobState) -> Result<(), BoxedError> {
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+        jobs.insert(render_id, state.clone());
+
+        self.persist(&jobs).map_err(BoxedError::from)
+    }
+
+    fn remove(&self, render_id: u32) -> Result<(), BoxedError> {
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if jobs.remove(&render_id).is_none() {
+            return Ok(());
+        }
+
+        self.persist(&jobs).map_err(BoxedError::from)
+    }
+
+    fn load(&self) -> Result<Vec<(u32, JobState)>, BoxedError> {
+        let jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+
+        Ok(jobs
+            .iter()
+            .map(|(id, state)| (*id, state.clone()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileJobStore, JobState, JobStore};
+
+    #[test]
+    fn file_store_roundtrip() {
+        let path = std::env::temp_dir().join("rosu-render-file-store-roundtrip.json");
+        let _ = std::fs::remove_file(&path);
+
+        let store = FileJobStore::open(&path).unwrap();
+        store.save(1, &JobState::Commissioned).unwrap();
+        store.save(2, &JobState::Commissioned).unwrap();
+        let rendering = JobState::Rendering {
+            progress: "Rendering: 42%".into(),
+        };
+        store.save(2, &rendering).unwrap();
+        store.remove(1).unwrap();
+        drop(store);
+
+        let jobs = FileJobStore::open(&path).unwrap().load().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(jobs, [(2, rendering)]);
+    }
+}
@@ -0,0 +1,413 @@
+use std::{sync::Weak, time::Duration};
+
+use tokio::{
+    sync::mpsc::UnboundedReceiver,
+    time::{self, Instant},
+};
+
+use crate::{
+    model::{Render, RenderDone, RenderFailed, RenderStatus},
+    ClientError, OrdrClient,
+};
+
+use super::{
+    retry::{Resubmit, Retry},
+    RenderFailure, RenderUpdate, Subscribers,
+};
+
+/// Receives the [`RenderUpdate`]s of a single render.
+///
+/// Created through [`RenderTracker::subscribe`](super::RenderTracker::subscribe).
+///
+/// The render is no longer tracked once all of its subscriptions are dropped.
+pub struct RenderSubscription {
+    receiver: Receiver,
+    progress_delta: f32,
+    last_percentage: Option<f32>,
+    deadline: Option<Instant>,
+    stall: Option<Stall>,
+    poll_client: Option<OrdrClient>,
+    resubmit: Option<Box<Resubmit>>,
+    finished: bool,
+}
+
+struct Stall {
+    timeout: Duration,
+    last_activity: Instant,
+}
+
+/// Receives the updates of a render and stops tracking it once dropped.
+struct Receiver {
+    render_id: u32,
+    rx: UnboundedReceiver<RenderUpdate>,
+    subscribers: Weak<Subscribers>,
+}
+
+impl Receiver {
+    /// Stop receiving updates. The render is no longer tracked if no other
+    /// subscription receives its updates.
+    fn release(&mut self) {
+        self.rx.close();
+
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers.release(self.render_id);
+        }
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl RenderSubscription {
+    pub(super) fn new(
+        render_id: u32,
+        rx: UnboundedReceiver<RenderUpdate>,
+        subscribers: Weak<Subscribers>,
+    ) -> Self {
+        Self {
+            receiver: Receiver {
+                render_id,
+                rx,
+                subscribers,
+            },
+            progress_delta: 0.0,
+            last_percentage: None,
+            deadline: None,
+            stall: None,
+            poll_client: None,
+            resubmit: None,
+            finished: false,
+        }
+    }
+
+    pub(super) fn resubmit(self, resubmit: Resubmit) -> Self {
+        Self {
+            resubmit: Some(Box::new(resubmit)),
+            ..self
+        }
+    }
+
+    /// The id of the tracked render.
+    ///
+    /// Changes when the render is commissioned again, see
+    /// [`RenderUpdate::Retried`].
+    #[must_use]
+    pub fn render_id(&self) -> u32 {
+        self.receiver.render_id
+    }
+
+    /// Only yield progress updates whose [percentage](crate::model::RenderProgress::percentage)
+    /// differs by at least `delta` from the previously yielded one.
+    ///
+    /// Progress updates without percentage, e.g. when the render enters a new
+    /// phase, are always yielded.
+    #[must_use]
+    pub fn progress_delta(self, delta: f32) -> Self {
+        Self {
+            progress_delta: delta,
+            ..self
+        }
+    }
+
+    /// Give up on the render if it did not complete within the given duration.
+    ///
+    /// Once the duration passed, [`RenderUpdate::TimedOut`] is yielded as terminal update,
+    /// also while waiting to commission a failed render again.
+    #[must_use]
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            deadline: Some(Instant::now() + timeout),
+            ..self
+        }
+    }
+
+    /// Yield [`RenderUpdate::Stalled`] whenever no update was received for the given duration.
+    #[must_use]
+    pub fn stall_timeout(self, timeout: Duration) -> Self {
+        Self {
+            stall: Some(Stall {
+                timeout,
+                last_activity: Instant::now(),
+            }),
+            ..self
+        }
+    }
+
+    /// When the render stalls, look it up in the render list in case its
+    /// terminal event got lost. If it turns out to be done,
+    /// [`RenderUpdate::Done`] is yielded instead of [`RenderUpdate::Stalled`].
+    /// If it failed, it's handled like a failure event, i.e. the
+    /// [`RetryPolicy`](super::RetryPolicy) decides whether it's commissioned
+    /// again and otherwise [`RenderUpdate::Failed`] is yielded. Since the
+    /// render list provides no error code, the failure's message is the
+    /// render's progress description and its error code is `None`, so it is
+    /// not considered [retryable](RenderFailed::is_retryable).
+    /// If it was removed or can no longer be found, [`RenderUpdate::Removed`]
+    /// is yielded.
+    ///
+    /// Only takes effect alongside [`RenderSubscription::stall_timeout`],
+    /// regardless of which is specified first.
+    /// Each lookup requests the render list so it counts towards its ratelimit.
+    #[must_use]
+    pub fn poll_on_stall(self, ordr: OrdrClient) -> Self {
+        Self {
+            poll_client: Some(ordr),
+            ..self
+        }
+    }
+
+    /// Await the next update.
+    ///
+    /// Returns `None` after a terminal update or if the tracker stopped.
+    pub async fn next(&mut self) -> Option<RenderUpdate> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            let recv_res = match self.next_timeout() {
+                Some(timeout) => time::timeout_at(timeout, self.receiver.rx.recv()).await,
+                None => Ok(self.receiver.rx.recv().await),
+            };
+
+            let update = match recv_res {
+                Ok(Some(update)) => update,
+                Ok(None) => return None,
+                // Failures found through polling may be commissioned again as well
+                Err(_) => self.on_timeout().await,
+            };
+
+            if let Some(ref mut stall) = self.stall {
+                stall.last_activity = Instant::now();
+            }
+
+            if let RenderUpdate::Progress(ref progress) = update {
+                if !self.accept_progress(progress.percentage()) {
+                    continue;
+                }
+            }
+
+            if let RenderUpdate::Failed(ref failed) = update {
+                if let Some(update) = self.retry(failed).await {
+                    return Some(update);
+                }
+            }
+
+            self.finished = update.is_terminal();
+
+            return Some(update);
+        }
+    }
+
+    /// Await the completion of the render, skipping progress updates.
+    pub async fn wait(mut self) -> Result<RenderDone, RenderFailure> {
+        loop {
+            match self.next().await {
+                Some(
+                    RenderUpdate::Progress(_) | RenderUpdate::Retried(_) | RenderUpdate::Stalled,
+                ) => {}
+                Some(RenderUpdate::Done(done)) => return Ok(done),
+                Some(RenderUpdate::Failed(failed)) => return Err(RenderFailure::Failed(failed)),
+                Some(RenderUpdate::Cancelled) => return Err(RenderFailure::Cancelled),
+                Some(RenderUpdate::TimedOut) => return Err(RenderFailure::TimedOut),
+                Some(RenderUpdate::Removed) => return Err(RenderFailure::Removed),
+                None => return Err(RenderFailure::TrackerClosed),
+            }
+        }
+    }
+
+    /// Commission the render again and continue with the new render.
+    ///
+    /// Returns `None` if the render is not commissioned again.
+    async fn retry(&mut self, failed: &RenderFailed) -> Option<RenderUpdate> {
+        let resubmit = self.resubmit.as_mut()?;
+
+        let (retried, next) = match resubmit.retry(failed, self.deadline).await {
+            Retry::Retried(retried, next) => (retried, next),
+            Retry::Skipped => return None,
+            Retry::TimedOut => {
+                self.finished = true;
+                self.receiver.release();
+
+                return Some(RenderUpdate::TimedOut);
+            }
+        };
+
+        // Replacing the receiver stops tracking the failed render
+        self.receiver = next.receiver;
+        self.last_percentage = None;
+
+        if let Some(ref mut stall) = self.stall {
+            stall.last_activity = Instant::now();
+        }
+
+        Some(RenderUpdate::Retried(retried))
+    }
+
+    fn next_timeout(&self) -> Option<Instant> {
+        let stall = self
+            .stall
+            .as_ref()
+            .map(|stall| stall.last_activity + stall.timeout);
+
+        match (self.deadline, stall) {
+            (Some(deadline), Some(stall)) => Some(deadline.min(stall)),
+            (deadline, stall) => deadline.or(stall),
+        }
+    }
+
+    async fn on_timeout(&mut self) -> RenderUpdate {
+        let now = Instant::now();
+
+        if self.deadline.is_some_and(|deadline| deadline <= now) {
+            self.receiver.release();
+
+            return RenderUpdate::TimedOut;
+        }
+
+        let Some(ref mut stall) = self.stall else {
+            return RenderUpdate::Stalled;
+        };
+
+        stall.last_activity = now;
+
+        let Some(ref ordr) = self.poll_client else {
+            return RenderUpdate::Stalled;
+        };
+
+        let update = poll_render(ordr, self.receiver.render_id).await;
+
+        // No further events will arrive for the render
+        if update.is_terminal() {
+            self.receiver.release();
+        }
+
+        update
+    }
+
+    fn accept_progress(&mut self, percentage: Option<f32>) -> bool {
+        let Some(percentage) = percentage else {
+            self.last_percentage = None;
+
+            return true;
+        };
+
+        match self.last_percentage {
+            Some(last) if (percentage - last).abs() < self.progress_delta => false,
+            _ => {
+                self.last_percentage = Some(percentage);
+
+                true
+            }
+        }
+    }
+}
+
+/// Look up the render in the render list to check whether it's done or removed.
+async fn poll_render(ordr: &OrdrClient, render_id: u32) -> RenderUpdate {
+    match ordr.fetch_render(render_id).await {
+        Ok(render) => polled_update(render_id, render),
+        Err(ClientError::Response {
+            status_code: 404, ..
+        }) => RenderUpdate::Removed,
+        Err(err) => {
+            warn!(render_id, ?err, "Failed to poll stalled render");
+
+            RenderUpdate::Stalled
+        }
+    }
+}
+
+fn polled_update(render_id: u32, render: Option<Render>) -> RenderUpdate {
+    match RenderStatus::from(render) {
+        RenderStatus::Done { video_url } => RenderUpdate::Done(RenderDone {
+            render_id,
+            video_url,
+        }),
+        RenderStatus::Failed { message } => RenderUpdate::Failed(RenderFailed {
+            render_id,
+            error_code: None,
+            error_message: message,
+        }),
+        RenderStatus::Removed | RenderStatus::NotFound => RenderUpdate::Removed,
+        _ => RenderUpdate::Stalled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Weak, time::Duration};
+
+    use tokio::sync::mpsc;
+
+    use crate::{model::RenderList, tracker::RenderUpdate, OrdrClient};
+
+    use super::{polled_update, RenderSubscription};
+
+    #[test]
+    fn smoothed_progress() {
+        let (_tx, rx) = mpsc::unbounded_channel();
+        let mut subscription = RenderSubscription::new(42, rx, Weak::new()).progress_delta(10.0);
+
+        assert!(subscription.accept_progress(Some(0.0)));
+        assert!(!subscription.accept_progress(Some(5.0)));
+        assert!(subscription.accept_progress(Some(12.5)));
+        assert!(subscription.accept_progress(None));
+        assert!(subscription.accept_progress(Some(15.0)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stall_then_timeout() {
+        let (_tx, rx) = mpsc::unbounded_channel();
+        let mut subscription = RenderSubscription::new(42, rx, Weak::new())
+            .stall_timeout(Duration::from_millis(10))
+            .with_timeout(Duration::from_millis(25));
+
+        assert_eq!(subscription.next().await, Some(RenderUpdate::Stalled));
+        assert_eq!(subscription.next().await, Some(RenderUpdate::Stalled));
+        assert_eq!(subscription.next().await, Some(RenderUpdate::TimedOut));
+        assert_eq!(subscription.next().await, None);
+    }
+
+    #[test]
+    fn poll_on_stall_before_stall_timeout() {
+        let (_tx, rx) = mpsc::unbounded_channel();
+        let subscription = RenderSubscription::new(42, rx, Weak::new())
+            .poll_on_stall(OrdrClient::new())
+            .stall_timeout(Duration::from_secs(30));
+
+        assert!(subscription.poll_client.is_some());
+        assert!(subscription.stall.is_some());
+    }
+
+    #[test]
+    fn missing_render_is_removed() {
+        assert_eq!(polled_update(42, None), RenderUpdate::Removed);
+        assert!(RenderUpdate::Removed.is_terminal());
+    }
+
+    #[test]
+    fn failed_render_is_failed() {
+        let json = include_str!("../../tests/fixtures/http/render_list.json");
+        let mut list: RenderList = serde_json::from_str(json).unwrap();
+        let mut render = list.renders.swap_remove(0);
+        render.progress = Box::from("Error: all beatmap mirrors are unavailable");
+
+        let update = polled_update(42, Some(render));
+
+        let RenderUpdate::Failed(ref failed) = update else {
+            panic!("expected render failed");
+        };
+
+        assert_eq!(failed.render_id, 42);
+        assert_eq!(failed.error_code, None);
+        assert_eq!(
+            &*failed.error_message,
+            "Error: all beatmap mirrors are unavailable"
+        );
+        assert!(update.is_terminal());
+    }
+}
@@ -0,0 +1,160 @@
+use std::io::{Error as IoError, ErrorKind, Read};
+
+use brotli_decompressor::Decompressor as BrotliDecoder;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use hyper::{body::Bytes, http::HeaderValue};
+
+/// Value of the `Accept-Encoding` header.
+pub(crate) const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+/// Responses that decompress to more bytes than this are rejected.
+const MAX_DECOMPRESSED_LEN: u64 = 32 * 1024 * 1024;
+
+/// Decompress the bytes based on the response's `Content-Encoding` header.
+///
+/// Multiple encodings are listed in the order they were applied so they are
+/// decoded in reverse order.
+///
+/// Fails if the decompressed bytes of any stage exceed [`MAX_DECOMPRESSED_LEN`].
+pub(crate) fn decompress(bytes: Bytes, encoding: Option<&HeaderValue>) -> Result<Bytes, IoError> {
+    let Some(encoding) = encoding else {
+        return Ok(bytes);
+    };
+
+    let encoding = String::from_utf8_lossy(encoding.as_bytes());
+
+    let encodings = encoding
+        .split(',')
+        .map(str::trim)
+        .filter(|encoding| !encoding.is_empty())
+        .map(|encoding| {
+            Encoding::parse(encoding).ok_or_else(|| {
+                let msg = format!("unsupported content encoding `{encoding}`");
+
+                IoError::new(ErrorKind::InvalidData, msg)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    encodings
+        .into_iter()
+        .rev()
+        .try_fold(bytes, |bytes, encoding| encoding.decode(bytes))
+}
+
+#[derive(Copy, Clone)]
+enum Encoding {
+    Gzip,
+    Deflate,
+    Brotli,
+    Identity,
+}
+
+impl Encoding {
+    fn parse(encoding: &str) -> Option<Self> {
+        let is = |name: &str| encoding.eq_ignore_ascii_case(name);
+
+        if is("gzip") || is("x-gzip") {
+            Some(Self::Gzip)
+        } else if is("deflate") {
+            Some(Self::Deflate)
+        } else if is("br") {
+            Some(Self::Brotli)
+        } else if is("identity") {
+            Some(Self::Identity)
+        } else {
+            None
+        }
+    }
+
+    fn decode(self, bytes: Bytes) -> Result<Bytes, IoError> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Self::Gzip => Box::new(GzDecoder::new(&*bytes)),
+            Self::Deflate => Box::new(ZlibDecoder::new(&*bytes)),
+            Self::Brotli => Box::new(BrotliDecoder::new(&*bytes, 4096)),
+            Self::Identity => return Ok(bytes),
+        };
+
+        // Reading one more byte than allowed reveals whether the limit is exceeded
+        let capacity = (bytes.len() * 4).min(MAX_DECOMPRESSED_LEN as usize);
+        let mut decompressed = Vec::with_capacity(capacity);
+        decoder
+            .take(MAX_DECOMPRESSED_LEN + 1)
+            .read_to_end(&mut decompressed)?;
+
+        if decompressed.len() as u64 > MAX_DECOMPRESSED_LEN {
+            let msg = format!("decompressed response exceeds {MAX_DECOMPRESSED_LEN} bytes");
+
+            return Err(IoError::new(ErrorKind::InvalidData, msg));
+        }
+
+        Ok(Bytes::from(decompressed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Write};
+
+    use flate2::{
+        write::{GzEncoder, ZlibEncoder},
+        Compression,
+    };
+    use hyper::{body::Bytes, http::HeaderValue};
+
+    use super::{decompress, MAX_DECOMPRESSED_LEN};
+
+    #[test]
+    fn gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(br#"{"renders":[]}"#).unwrap();
+        let compressed = Bytes::from(encoder.finish().unwrap());
+
+        let encoding = HeaderValue::from_static("gzip");
+        let decompressed = decompress(compressed.clone(), Some(&encoding)).unwrap();
+
+        assert_eq!(&*decompressed, br#"{"renders":[]}"#);
+
+        let encoding = HeaderValue::from_static(" GZIP ");
+        let decompressed = decompress(compressed, Some(&encoding)).unwrap();
+
+        assert_eq!(&*decompressed, br#"{"renders":[]}"#);
+    }
+
+    #[test]
+    fn stacked_encodings() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(br#"{"renders":[]}"#).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&gzipped).unwrap();
+        let compressed = Bytes::from(encoder.finish().unwrap());
+
+        let encoding = HeaderValue::from_static("gzip, deflate");
+        let decompressed = decompress(compressed.clone(), Some(&encoding)).unwrap();
+
+        assert_eq!(&*decompressed, br#"{"renders":[]}"#);
+
+        let encoding = HeaderValue::from_static("gzip, zstd");
+        let err = decompress(compressed, Some(&encoding)).unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn exceeds_limit() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+
+        for _ in 0..=MAX_DECOMPRESSED_LEN / 1024 {
+            encoder.write_all(&[0; 1024]).unwrap();
+        }
+
+        let compressed = Bytes::from(encoder.finish().unwrap());
+
+        let encoding = HeaderValue::from_static("gzip");
+        let err = decompress(compressed, Some(&encoding)).unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -2,7 +2,8 @@ use std::fmt::{Formatter, Result as FmtResult};
 
 use serde::{
     de::{Error as DeError, Unexpected, Visitor},
-    Deserializer,
+    ser::Error as SerError,
+    Deserializer, Serializer,
 };
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
@@ -41,3 +42,40 @@ pub(crate) fn deserialize_datetime<'de, D: Deserializer<'de>>(
 ) -> Result<OffsetDateTime, D::Error> {
     d.deserialize_any(OffsetDateTimeVisitor)
 }
+
+pub(crate) fn serialize_datetime<S: Serializer>(
+    datetime: &OffsetDateTime,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    let formatted = datetime.format(&Rfc3339).map_err(SerError::custom)?;
+
+    s.serialize_str(&formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use time::OffsetDateTime;
+
+    use super::{deserialize_datetime, serialize_datetime};
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Wrapper {
+        #[serde(
+            deserialize_with = "deserialize_datetime",
+            serialize_with = "serialize_datetime"
+        )]
+        datetime: OffsetDateTime,
+    }
+
+    #[test]
+    fn round_trip() {
+        let from_millis: Wrapper = serde_json::from_str(r#"{"datetime":1700000000123}"#).unwrap();
+
+        let json = serde_json::to_string(&from_millis).unwrap();
+        assert_eq!(json, r#"{"datetime":"2023-11-14T22:13:20.123Z"}"#);
+
+        let from_rfc3339: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_rfc3339, from_millis);
+    }
+}
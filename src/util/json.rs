@@ -0,0 +1,61 @@
+use hyper::body::Bytes;
+use serde::de::DeserializeOwned;
+
+use crate::ClientError;
+
+/// Deserialize a response body, including the path of the failing field
+/// in the error if the `serde_path_to_error` feature is enabled.
+pub(crate) fn from_bytes<T: DeserializeOwned>(bytes: &Bytes) -> Result<T, ClientError> {
+    #[cfg(not(feature = "serde_path_to_error"))]
+    let res = serde_json::from_slice(bytes).map_err(|source| (source, None));
+
+    #[cfg(feature = "serde_path_to_error")]
+    let res = {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+
+        serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+            let path = err.path();
+
+            let path = (path.iter().next().is_some()).then(|| path.to_string().into_boxed_str());
+
+            (err.into_inner(), path)
+        })
+    };
+
+    res.map_err(|(source, path)| ClientError::Parsing {
+        body: bytes.clone().into(),
+        path,
+        source,
+    })
+}
+
+#[cfg(all(test, feature = "serde_path_to_error"))]
+mod tests {
+    use hyper::body::Bytes;
+    use serde::Deserialize;
+
+    use crate::ClientError;
+
+    #[derive(Debug, Deserialize)]
+    struct Outer {
+        #[allow(dead_code)]
+        inner: Vec<Inner>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Inner {
+        #[allow(dead_code)]
+        id: u32,
+    }
+
+    #[test]
+    fn parsing_error_path() {
+        let bytes = Bytes::from_static(br#"{"inner":[{"id":1},{"id":"2"}]}"#);
+
+        let Err(ClientError::Parsing { path, .. }) = super::from_bytes::<Outer>(&bytes) else {
+            panic!("expected parsing error");
+        };
+
+        assert_eq!(path.as_deref(), Some("inner[1].id"));
+    }
+}
@@ -1,2 +1,4 @@
+#[cfg(feature = "compression")]
+pub(crate) mod compression;
 pub(crate) mod datetime;
-pub(crate) mod multipart;
+pub(crate) mod json;
@@ -1,4 +0,0 @@
-mod form;
-mod serializer;
-
-pub(crate) use self::{form::Form, serializer::FormSerializer};
@@ -0,0 +1,282 @@
+//! Forward websocket events to an HTTP callback.
+
+use std::fmt::Write;
+
+use hmac::{Hmac, Mac};
+use hyper::{
+    body::HttpBody,
+    header::{CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT},
+    http::uri::InvalidUri,
+    Body, Client as HyperClient, Error as HyperError, Method, Request as HyperRequest, StatusCode,
+    Uri,
+};
+use sha2::Sha256;
+use thiserror::Error as ThisError;
+
+use crate::{
+    client::{connector, ROSU_RENDER_USER_AGENT},
+    dns::DnsResolver,
+    websocket::event::{EventKind, RawEvent},
+};
+
+/// Header containing the event kind of a webhook request.
+pub const EVENT_HEADER: &str = "X-Ordr-Event";
+
+/// Header containing the signature of a webhook request.
+///
+/// Its value is `sha256=` followed by the hex encoded HMAC-SHA256 of the
+/// request body using the webhook's secret as key.
+pub const SIGNATURE_HEADER: &str = "X-Ordr-Signature";
+
+/// Response bodies are read up to this many bytes so that their connection
+/// can be reused. Connections of larger bodies are closed instead.
+const MAX_RESPONSE_BODY: usize = 64 * 1024;
+
+/// Bodies of error responses are truncated to this many bytes.
+const MAX_BODY_SNIPPET: usize = 512;
+
+/// POSTs websocket events as JSON to a URL.
+///
+/// The body of each request has the form `{"event":"<kind>","data":<payload>}`
+/// where `<kind>` is [`EventKind::as_str`] and `<payload>` the event's JSON as
+/// received from o!rdr.
+///
+/// # Example
+/// ```no_run
+/// use rosu_render::{webhook::Webhook, websocket::event::EventKind, OrdrWebsocket};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let webhook = Webhook::new("https://example.com/ordr")?
+///     .secret("my-secret")
+///     .events(&[EventKind::RenderDone, EventKind::RenderFailed]);
+///
+/// let mut websocket = OrdrWebsocket::connect().await?;
+///
+/// loop {
+///     let event = websocket.next_event().await?;
+///
+///     if let Err(err) = webhook.forward(&event).await {
+///         println!("Failed to forward event: {err}");
+///     }
+/// }
+/// # }
+/// ```
+pub struct Webhook {
+    http: HyperClient<connector::Connector>,
+    url: Uri,
+    secret: Option<Box<[u8]>>,
+    events: Option<Box<[EventKind]>>,
+}
+
+impl Webhook {
+    /// Create a new [`Webhook`] that forwards all events to the given URL.
+    pub fn new(url: &str) -> Result<Self, WebhookError> {
+        let url = url
+            .parse()
+            .map_err(|source| WebhookError::InvalidUrl { source })?;
+
+        Ok(Self {
+            http: HyperClient::builder().build(connector::create(
+                None,
+                DnsResolver::default(),
+                &connector::TlsConfig::new(),
+            )),
+            url,
+            secret: None,
+            events: None,
+        })
+    }
+
+    /// Sign each request with the given secret.
+    ///
+    /// The signature is sent in the [`SIGNATURE_HEADER`].
+    #[must_use]
+    pub fn secret(self, secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            secret: Some(Box::from(secret.as_ref())),
+            ..self
+        }
+    }
+
+    /// Only forward events of the given kinds.
+    #[must_use]
+    pub fn events(self, events: &[EventKind]) -> Self {
+        Self {
+            events: Some(Box::from(events)),
+            ..self
+        }
+    }
+
+    /// Whether the event would be forwarded.
+    #[must_use]
+    pub fn is_selected(&self, kind: EventKind) -> bool {
+        self.events
+            .as_ref()
+            .is_none_or(|events| events.contains(&kind))
+    }
+
+    /// POST the event to the webhook URL.
+    ///
+    /// Returns `false` if the event was not selected and thus not sent.
+    pub async fn forward(&self, event: &RawEvent) -> Result<bool, WebhookError> {
+        let kind = event.kind();
+
+        if !self.is_selected(kind) {
+            return Ok(false);
+        }
+
+        let payload = event.payload();
+
+        let mut body = Vec::with_capacity(payload.len() + 32);
+        body.extend_from_slice(b"{\"event\":\"");
+        body.extend_from_slice(kind.as_str().as_bytes());
+        body.extend_from_slice(b"\",\"data\":");
+        body.extend_from_slice(payload);
+        body.push(b'}');
+
+        let mut req = HyperRequest::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_LENGTH, body.len())
+            .header(USER_AGENT, ROSU_RENDER_USER_AGENT)
+            .header(EVENT_HEADER, kind.as_str());
+
+        if let Some(ref secret) = self.secret {
+            req = req.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+
+        let req = req
+            .body(Body::from(body))
+            .map_err(|source| WebhookError::BuildingRequest { source })?;
+
+        let response = self
+            .http
+            .request(req)
+            .await
+            .map_err(|source| WebhookError::RequestError { source })?;
+
+        let status = response.status();
+        let body = drain(response.into_body()).await;
+
+        if status.is_success() {
+            Ok(true)
+        } else {
+            let snippet = &body[..body.len().min(MAX_BODY_SNIPPET)];
+            let body = String::from_utf8_lossy(snippet).into();
+
+            Err(WebhookError::Status { status, body })
+        }
+    }
+}
+
+/// Read the body up to [`MAX_RESPONSE_BODY`] bytes.
+///
+/// Fully read bodies let hyper return the connection to the pool.
+async fn drain(mut body: Body) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    while let Some(Ok(chunk)) = body.data().await {
+        if bytes.len() + chunk.len() > MAX_RESPONSE_BODY {
+            break;
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    bytes
+}
+
+fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(body);
+
+    let mut signature = String::with_capacity(7 + 64);
+    signature.push_str("sha256=");
+
+    for byte in mac.finalize().into_bytes() {
+        let _ = write!(signature, "{byte:02x}");
+    }
+
+    signature
+}
+
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum WebhookError {
+    #[error("Failed to build the request")]
+    BuildingRequest {
+        #[source]
+        source: hyper::http::Error,
+    },
+    #[error("Invalid webhook url")]
+    InvalidUrl {
+        #[source]
+        source: InvalidUri,
+    },
+    #[error("Failed to send the request")]
+    RequestError {
+        #[source]
+        source: HyperError,
+    },
+    #[error("The webhook responded with status code {status}")]
+    Status {
+        status: StatusCode,
+        /// The beginning of the response body.
+        body: Box<str>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use hyper::StatusCode;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use crate::websocket::event::RawEvent;
+
+    use super::{sign, Webhook, WebhookError};
+
+    #[test]
+    fn hmac_sha256_signature() {
+        // RFC 4231 test case 2
+        let signature = sign(b"Jefe", b"what do ya want for nothing?");
+
+        assert_eq!(
+            signature,
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn error_status_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut buf = [0; 1024];
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut buf).await.unwrap();
+
+            let response = b"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 4\r\n\r\noops";
+            stream.write_all(response).await.unwrap();
+        });
+
+        let webhook = Webhook::new(&format!("http://{addr}")).unwrap();
+        let event = RawEvent::from_bytes(Bytes::from_static(
+            br#"["render_done_json",{"renderID":42,"videoUrl":"https://link.issou.best/a"}]"#,
+        ))
+        .unwrap();
+
+        let err = webhook.forward(&event).await.unwrap_err();
+
+        assert!(matches!(
+            err,
+            WebhookError::Status { status, ref body }
+                if status == StatusCode::INTERNAL_SERVER_ERROR && &**body == "oops"
+        ));
+    }
+}
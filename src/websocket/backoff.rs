@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::client::ReconnectConfig;
+
+/// Determines the delay between reconnect attempts of the [`OrdrWebsocket`](super::OrdrWebsocket).
+///
+/// Configured through [`OrdrWebsocketBuilder::backoff`](super::OrdrWebsocketBuilder::backoff).
+pub trait BackoffPolicy: Send + Sync + 'static {
+    /// The delay before the next reconnect attempt after `failures`
+    /// consecutive failed attempts. `failures` is at least 1.
+    fn delay(&self, failures: u32) -> Duration;
+}
+
+/// Doubles the delay after each failed attempt up to a maximum.
+///
+/// By default, the delay is randomly reduced by up to half so that many
+/// clients do not reconnect in lockstep after o!rdr restarted.
+#[derive(Copy, Clone, Debug)]
+pub struct ExponentialBackoff {
+    initial: Duration,
+    max: Duration,
+    jitter: bool,
+}
+
+impl ExponentialBackoff {
+    /// Start at `initial` and double after each failed attempt up to `max`.
+    #[must_use]
+    pub const fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            jitter: true,
+        }
+    }
+
+    /// Whether the delay should be randomized. Defaults to `true`.
+    #[must_use]
+    pub const fn jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self::from(ReconnectConfig::default())
+    }
+}
+
+impl From<ReconnectConfig> for ExponentialBackoff {
+    fn from(config: ReconnectConfig) -> Self {
+        Self::new(
+            Duration::from_millis(config.initial_backoff_ms),
+            Duration::from_millis(config.max_backoff_ms),
+        )
+        .jitter(config.jitter)
+    }
+}
+
+impl BackoffPolicy for ExponentialBackoff {
+    fn delay(&self, failures: u32) -> Duration {
+        let factor = 1_u32.checked_shl(failures - 1).unwrap_or(u32::MAX);
+
+        let delay = self
+            .initial
+            .checked_mul(factor)
+            .map_or(self.max, |delay| delay.min(self.max));
+
+        if self.jitter {
+            equal_jitter(delay)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Waits the same delay before each attempt.
+#[derive(Copy, Clone, Debug)]
+pub struct ConstantBackoff(pub Duration);
+
+impl BackoffPolicy for ConstantBackoff {
+    fn delay(&self, _: u32) -> Duration {
+        self.0
+    }
+}
+
+/// Increases the delay along the fibonacci sequence up to a maximum,
+/// i.e. `initial`, `initial`, `2 * initial`, `3 * initial`, `5 * initial`, ...
+#[derive(Copy, Clone, Debug)]
+pub struct FibonacciBackoff {
+    initial: Duration,
+    max: Duration,
+}
+
+impl FibonacciBackoff {
+    #[must_use]
+    pub const fn new(initial: Duration, max: Duration) -> Self {
+        Self { initial, max }
+    }
+}
+
+impl BackoffPolicy for FibonacciBackoff {
+    fn delay(&self, failures: u32) -> Duration {
+        let (mut prev, mut curr) = (0_u32, 1_u32);
+
+        for _ in 1..failures {
+            let Some(next) = prev.checked_add(curr) else {
+                return self.max;
+            };
+
+            (prev, curr) = (curr, next);
+        }
+
+        self.initial
+            .checked_mul(curr)
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+/// Keep half of the delay and randomize the other half.
+fn equal_jitter(delay: Duration) -> Duration {
+    let half = delay / 2;
+
+    half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{BackoffPolicy, ExponentialBackoff, FibonacciBackoff};
+
+    #[test]
+    fn exponential_backoff() {
+        let backoff = ExponentialBackoff::new(Duration::from_millis(100), Duration::from_secs(1));
+
+        for (failures, expected) in [(1, 100), (2, 200), (4, 800), (5, 1000), (40, 1000)] {
+            let max = Duration::from_millis(expected);
+            let delay = backoff.delay(failures);
+
+            assert!(delay >= max / 2 && delay <= max, "{failures}: {delay:?}");
+            assert_eq!(backoff.jitter(false).delay(failures), max);
+        }
+    }
+
+    #[test]
+    fn fibonacci_backoff() {
+        let backoff = FibonacciBackoff::new(Duration::from_millis(100), Duration::from_secs(1));
+
+        let delays: Vec<_> = (1..=7)
+            .map(|failures| backoff.delay(failures).as_millis())
+            .collect();
+
+        assert_eq!(delays, [100, 100, 200, 300, 500, 800, 1000]);
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use crate::{
+    client::ReconnectConfig,
+    dns::{DnsResolver, IpPreference, Resolve, SystemResolver},
+    OrdrClient, WebsocketError,
+};
+
+use super::{
+    backoff::BackoffPolicy,
+    engineio::{
+        socket::{self, WS_URL},
+        tls::{TlsContainer, TlsOptions},
+    },
+    event::EventMask,
+    reconnect::Reconnect,
+    runtime::{Runtime, TokioRuntime},
+    OrdrWebsocket,
+};
+
+/// A builder for [`OrdrWebsocket`].
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use rosu_render::websocket::{FibonacciBackoff, OrdrWebsocket};
+///
+/// # #[tokio::main] async fn main() -> Result<(), rosu_render::WebsocketError> {
+/// let websocket = OrdrWebsocket::builder()
+///     .backoff(FibonacciBackoff::new(
+///         Duration::from_millis(500),
+///         Duration::from_secs(30),
+///     ))
+///     .connect()
+///     .await?;
+/// # Ok(()) }
+/// ```
+#[must_use]
+#[derive(Default)]
+pub struct OrdrWebsocketBuilder {
+    reconnect: ReconnectConfig,
+    backoff: Option<Box<dyn BackoffPolicy>>,
+    resolver: Option<Arc<dyn Resolve>>,
+    ip_preference: IpPreference,
+    dns: Option<DnsResolver>,
+    tls: TlsOptions,
+    url: Option<Box<str>>,
+    event_mask: EventMask,
+    runtime: Option<Arc<dyn Runtime>>,
+}
+
+impl OrdrWebsocketBuilder {
+    /// Create a new builder to create an [`OrdrWebsocket`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connect to the o!rdr websocket.
+    pub async fn connect(self) -> Result<OrdrWebsocket, WebsocketError> {
+        let reconnect = match self.backoff {
+            Some(backoff) => Reconnect::new(self.reconnect, backoff),
+            None => Reconnect::from(self.reconnect),
+        };
+
+        let resolver = self.dns.unwrap_or_else(|| {
+            let resolver = self
+                .resolver
+                .unwrap_or_else(|| Arc::new(SystemResolver) as Arc<dyn Resolve>);
+
+            DnsResolver::new(resolver, self.ip_preference)
+        });
+        let tls = TlsContainer::new(self.tls)?;
+        let url = socket::endpoint(self.url.as_deref().unwrap_or(WS_URL))?;
+        let runtime = self
+            .runtime
+            .unwrap_or_else(|| Arc::new(TokioRuntime) as Arc<dyn Runtime>);
+
+        let websocket_fut = OrdrWebsocket::connect_with(url, reconnect, resolver, tls, runtime);
+        let mut websocket = Box::pin(websocket_fut).await?;
+
+        websocket.set_event_mask(self.event_mask);
+
+        Ok(websocket)
+    }
+
+    /// Resolve hostnames the same way as the given client and, if both use
+    /// `rustls`, reuse its TLS config instead of loading certificates again.
+    ///
+    /// Overrides [`OrdrWebsocketBuilder::resolver`] and
+    /// [`OrdrWebsocketBuilder::ip_preference`]. A config specified through
+    /// [`OrdrWebsocketBuilder::rustls_config`] takes precedence.
+    pub fn client(mut self, client: &OrdrClient) -> Self {
+        self.dns = Some(client.resolver().clone());
+
+        #[cfg(all(
+            any(
+                feature = "http-rustls-native-roots",
+                feature = "http-rustls-webpki-roots"
+            ),
+            any(feature = "ws-rustls-native-roots", feature = "ws-rustls-webpki-roots")
+        ))]
+        if self.tls.rustls_config.is_none() {
+            self.tls.rustls_config = Some(Arc::clone(&client.tls_config().rustls));
+        }
+
+        self
+    }
+
+    /// Specify the url of the websocket server, e.g. the one of a
+    /// [`MockServer`](crate::mock::MockServer).
+    ///
+    /// Defaults to `wss://apis.issou.best`. The engine.io path is appended.
+    pub fn url(self, url: impl Into<Box<str>>) -> Self {
+        Self {
+            url: Some(url.into()),
+            ..self
+        }
+    }
+
+    /// Only yield events of the given kinds.
+    ///
+    /// See [`OrdrWebsocket::set_event_mask`].
+    pub fn event_mask(self, mask: impl Into<EventMask>) -> Self {
+        Self {
+            event_mask: mask.into(),
+            ..self
+        }
+    }
+
+    /// Specify how reconnecting is handled.
+    ///
+    /// Unless a policy is specified through [`OrdrWebsocketBuilder::backoff`],
+    /// the delay between attempts is based on the config's exponential backoff.
+    pub fn reconnect(self, reconnect: ReconnectConfig) -> Self {
+        Self { reconnect, ..self }
+    }
+
+    /// Specify the delay between reconnect attempts.
+    ///
+    /// Defaults to an [`ExponentialBackoff`](super::ExponentialBackoff) with jitter.
+    pub fn backoff(self, backoff: impl BackoffPolicy) -> Self {
+        Self {
+            backoff: Some(Box::new(backoff)),
+            ..self
+        }
+    }
+
+    /// Specify the resolver for the websocket's hostname, e.g. one based on `hickory-dns`.
+    ///
+    /// Defaults to the [`SystemResolver`].
+    pub fn resolver(self, resolver: impl Resolve) -> Self {
+        Self {
+            resolver: Some(Arc::new(resolver)),
+            ..self
+        }
+    }
+
+    /// Specify the runtime that provides timers, e.g. for heartbeats and
+    /// reconnect delays, so that the websocket can be used without tokio.
+    ///
+    /// Defaults to the [`TokioRuntime`]. See [`Runtime`] for which parts of
+    /// the connection depend on the enabled features.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(feature = "runtime-smol")]
+    /// # fn main() -> Result<(), rosu_render::WebsocketError> {
+    /// use rosu_render::websocket::{OrdrWebsocket, SmolRuntime};
+    ///
+    /// smol::block_on(async {
+    ///     let mut websocket = OrdrWebsocket::builder()
+    ///         .runtime(SmolRuntime)
+    ///         .connect()
+    ///         .await?;
+    ///
+    ///     let event = websocket.next_event().await?;
+    ///     println!("{event:?}");
+    ///
+    ///     Ok(())
+    /// })
+    /// # }
+    /// # #[cfg(not(feature = "runtime-smol"))]
+    /// # fn main() {}
+    /// ```
+    pub fn runtime(self, runtime: impl Runtime) -> Self {
+        Self {
+            runtime: Some(Arc::new(runtime)),
+            ..self
+        }
+    }
+
+    /// Specify which IP versions are used to connect.
+    ///
+    /// Defaults to [`IpPreference::Any`].
+    pub fn ip_preference(self, ip_preference: IpPreference) -> Self {
+        Self {
+            ip_preference,
+            ..self
+        }
+    }
+
+    /// Trust the given DER-encoded certificate in addition to the default
+    /// root certificates, e.g. the one of a proxy that intercepts TLS.
+    ///
+    /// Invalid certificates cause [`OrdrWebsocketBuilder::connect`] to fail.
+    pub fn add_root_certificate(mut self, der: impl Into<Vec<u8>>) -> Self {
+        self.tls.root_certificates.push(der.into());
+
+        self
+    }
+
+    /// Use the given `rustls` config for the websocket connection instead
+    /// of the default one.
+    ///
+    /// Certificates added through [`OrdrWebsocketBuilder::add_root_certificate`]
+    /// are ignored in that case.
+    #[cfg(any(feature = "ws-rustls-native-roots", feature = "ws-rustls-webpki-roots"))]
+    pub fn rustls_config(mut self, config: Arc<rustls_tls::ClientConfig>) -> Self {
+        self.tls.rustls_config = Some(config);
+
+        self
+    }
+}
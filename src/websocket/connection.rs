@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+/// Changes of the websocket's connection state.
+///
+/// Received through [`ConnectionEvents`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnectionEvent {
+    /// The connection was (re-)established.
+    Connected,
+    /// The connection was lost. Reconnecting starts with the next call of
+    /// [`OrdrWebsocket::next_event`](super::OrdrWebsocket::next_event).
+    Disconnected { reason: DisconnectReason },
+    /// A reconnect is about to be attempted after waiting for `delay`.
+    Reconnecting {
+        /// Number of consecutive attempts, starting at 1.
+        attempt: u32,
+        delay: Duration,
+    },
+}
+
+/// Why the websocket lost its connection.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DisconnectReason {
+    /// The underlying connection was closed.
+    Closed,
+    /// The server sent a socket.io disconnect.
+    ServerDisconnect,
+    /// The server rejected the socket.io connection.
+    ConnectError,
+}
+
+/// Receiver of [`ConnectionEvent`]s.
+///
+/// Created through [`OrdrWebsocket::connection_events`](super::OrdrWebsocket::connection_events).
+pub struct ConnectionEvents {
+    pub(crate) rx: Receiver<ConnectionEvent>,
+}
+
+impl ConnectionEvents {
+    /// The amount of events that are buffered before the oldest ones are dropped.
+    pub(super) const CAPACITY: usize = 16;
+
+    /// Await the next connection event.
+    ///
+    /// If the receiver fell behind, the oldest events are skipped.
+    ///
+    /// Returns `None` once the websocket was dropped.
+    pub async fn recv(&mut self) -> Option<ConnectionEvent> {
+        loop {
+            match self.rx.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "Connection events receiver lagged behind");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::broadcast;
+
+    use super::{ConnectionEvent, ConnectionEvents, DisconnectReason};
+
+    #[tokio::test]
+    async fn skip_lagged_events() {
+        let (tx, rx) = broadcast::channel(2);
+        let mut events = ConnectionEvents { rx };
+
+        let reason = DisconnectReason::Closed;
+        tx.send(ConnectionEvent::Disconnected { reason }).unwrap();
+
+        for attempt in [1, 2] {
+            let delay = Duration::from_millis(100);
+            tx.send(ConnectionEvent::Reconnecting { attempt, delay })
+                .unwrap();
+        }
+
+        drop(tx);
+
+        assert!(matches!(
+            events.recv().await,
+            Some(ConnectionEvent::Reconnecting { attempt: 1, .. })
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Some(ConnectionEvent::Reconnecting { attempt: 2, .. })
+        ));
+        assert!(events.recv().await.is_none());
+    }
+}
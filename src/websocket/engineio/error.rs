@@ -1,7 +1,7 @@
-use std::{error::Error as StdError, str::Utf8Error};
+use std::{error::Error as StdError, io::Error as IoError, str::Utf8Error};
 
 use bytes::Bytes;
-use hyper::Error as HyperError;
+use hyper::{header::SERVER, Error as HyperError, HeaderMap, StatusCode};
 use serde_json::Error as SerdeError;
 use thiserror::Error as ThisError;
 use tokio_tungstenite::tungstenite::{Error as TungsteniteError, Message};
@@ -10,12 +10,18 @@ use tokio_tungstenite::tungstenite::{Error as TungsteniteError, Message};
 pub enum EngineIoError {
     #[error("Failed to chunk response")]
     ChunkingResponse(#[source] HyperError),
+    #[error("Failed to connect to the websocket server")]
+    Connect(#[source] IoError),
     #[error("Failed to deserialize data={data:?}")]
     Deserialize {
         #[source]
         source: SerdeError,
         data: Bytes,
     },
+    #[error("Server closed the connection before the handshake")]
+    HandshakeClosed,
+    #[error("Server rejected the websocket upgrade with status code {}", .response.status)]
+    HandshakeRejected { response: Box<HandshakeResponse> },
     #[error("Server timed out while attempting to handshake")]
     HandshakeTimeout,
     #[error("Server did not fulfill its heartbeat obligation")]
@@ -28,6 +34,8 @@ pub enum EngineIoError {
     InvalidUtf8(#[from] Utf8Error),
     #[error("Invalid packet id {0}")]
     InvalidPacketId(u8),
+    #[error("Invalid websocket url `{url}`")]
+    InvalidUrl { url: Box<str> },
     #[error("Failed to load the TLS connector or its certificates")]
     LoadingTls(#[source] Box<dyn StdError + Send + Sync>),
     #[error("Failed to reconnect websocket")]
@@ -41,3 +49,65 @@ pub enum EngineIoError {
     #[error("Failed to send message through websocket")]
     WebsocketSend(#[source] TungsteniteError),
 }
+
+/// The HTTP response of a rejected websocket upgrade.
+#[derive(Debug)]
+pub struct HandshakeResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// The beginning of the response body, if any.
+    pub body: Option<Box<str>>,
+}
+
+impl HandshakeResponse {
+    /// Maximum amount of bytes of the body that are kept.
+    const BODY_LIMIT: usize = 512;
+
+    pub(crate) fn new(status: StatusCode, headers: HeaderMap, body: Option<&[u8]>) -> Self {
+        let body = body.filter(|body| !body.is_empty()).map(|body| {
+            let snippet = &body[..body.len().min(Self::BODY_LIMIT)];
+
+            Box::from(String::from_utf8_lossy(snippet))
+        });
+
+        Self {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    /// Whether the response was sent by Cloudflare instead of o!rdr itself,
+    /// e.g. because the connection was blocked.
+    #[must_use]
+    pub fn is_cloudflare(&self) -> bool {
+        self.headers.contains_key("cf-ray")
+            || self
+                .headers
+                .get(SERVER)
+                .and_then(|server| server.to_str().ok())
+                .is_some_and(|server| server.eq_ignore_ascii_case("cloudflare"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::{header::SERVER, HeaderMap, StatusCode};
+
+    use super::HandshakeResponse;
+
+    #[test]
+    fn handshake_response() {
+        let body = [b'a'; 1000];
+        let response =
+            HandshakeResponse::new(StatusCode::BAD_GATEWAY, HeaderMap::new(), Some(&body));
+        assert_eq!(response.body.as_deref().map(str::len), Some(512));
+        assert!(!response.is_cloudflare());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(SERVER, "cloudflare".parse().unwrap());
+        let response = HandshakeResponse::new(StatusCode::FORBIDDEN, headers, Some(b""));
+        assert_eq!(response.body, None);
+        assert!(response.is_cloudflare());
+    }
+}
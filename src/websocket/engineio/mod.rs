@@ -4,19 +4,42 @@ pub(crate) mod packet;
 pub(crate) mod socket;
 pub(crate) mod tls;
 
+use std::sync::Arc;
+
 use bytes::Bytes;
+use url::Url;
 
-use crate::websocket::engineio::packet::Packet;
+use crate::{
+    dns::DnsResolver,
+    websocket::{engineio::packet::Packet, Runtime, ServerTiming},
+};
 
-use self::{error::EngineIoError, packet::PacketId, socket::Socket};
+use self::{error::EngineIoError, packet::PacketId, socket::Socket, tls::TlsContainer};
 
 pub(crate) struct EngineIo {
     socket: Socket,
+    url: Url,
+    resolver: DnsResolver,
+    tls: TlsContainer,
+    runtime: Arc<dyn Runtime>,
 }
 
 impl EngineIo {
-    pub(crate) async fn connect() -> Result<Self, EngineIoError> {
-        Socket::new().await.map(|socket| Self { socket })
+    pub(crate) async fn connect(
+        url: Url,
+        resolver: DnsResolver,
+        tls: TlsContainer,
+        runtime: Arc<dyn Runtime>,
+    ) -> Result<Self, EngineIoError> {
+        let socket = Socket::new(url.clone(), &resolver, &tls, Arc::clone(&runtime)).await?;
+
+        Ok(Self {
+            socket,
+            url,
+            resolver,
+            tls,
+            runtime,
+        })
     }
 
     pub(crate) async fn next_message(&mut self) -> Result<Option<Bytes>, EngineIoError> {
@@ -33,6 +56,10 @@ impl EngineIo {
         }
     }
 
+    pub(crate) const fn timing(&self) -> ServerTiming {
+        self.socket.timing()
+    }
+
     pub(crate) async fn emit(&mut self, packet: Packet) -> Result<(), EngineIoError> {
         self.socket.emit(packet).await
     }
@@ -43,7 +70,8 @@ impl EngineIo {
 
     pub(crate) async fn reconnect(&mut self) -> Result<(), EngineIoError> {
         trace!("Reconnecting engine.io");
-        self.socket = Socket::new().await?;
+        let runtime = Arc::clone(&self.runtime);
+        self.socket = Socket::new(self.url.clone(), &self.resolver, &self.tls, runtime).await?;
 
         Ok(())
     }
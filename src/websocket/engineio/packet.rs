@@ -3,6 +3,8 @@ use std::time::Duration;
 use bytes::Bytes;
 use serde::Deserialize;
 
+use crate::websocket::ServerTiming;
+
 use super::error::EngineIoError;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -85,7 +87,10 @@ pub(super) struct HandshakePacket {
 }
 
 impl HandshakePacket {
-    pub(super) fn heartbeat_interval(&self) -> Duration {
-        Duration::from_millis(self.ping_interval + self.ping_timeout)
+    pub(super) fn timing(&self) -> ServerTiming {
+        ServerTiming {
+            ping_interval: Duration::from_millis(self.ping_interval),
+            ping_timeout: Duration::from_millis(self.ping_timeout),
+        }
     }
 }
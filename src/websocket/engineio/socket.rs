@@ -1,12 +1,20 @@
-use std::time::Duration;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use bytes::Bytes;
 use futures::{SinkExt, StreamExt};
-use tokio::time::Instant;
 use tokio_tungstenite::tungstenite::Message;
 use url::Url;
 
-use crate::websocket::engineio::packet::{Packet, PacketId};
+use crate::{
+    dns::DnsResolver,
+    websocket::{
+        engineio::packet::{Packet, PacketId},
+        runtime, Runtime, ServerTiming,
+    },
+};
 
 use super::{
     error::EngineIoError,
@@ -14,46 +22,64 @@ use super::{
     tls::{Connection, TlsContainer},
 };
 
-const WS_URL: &str = "https://apis.issou.best";
+pub(crate) const WS_URL: &str = "wss://apis.issou.best";
 const WS_PATH: &str = "/ordr/ws/";
 const ENGINE_IO_VERSION: &str = "4";
 
+/// The engine.io endpoint of the given websocket url.
+pub(crate) fn endpoint(base_url: &str) -> Result<Url, EngineIoError> {
+    let mut url = Url::parse(base_url).map_err(|_| EngineIoError::InvalidUrl {
+        url: Box::from(base_url),
+    })?;
+
+    url.set_path(WS_PATH);
+    url.query_pairs_mut()
+        .append_pair("EIO", ENGINE_IO_VERSION)
+        .append_pair("transport", "websocket");
+
+    Ok(url)
+}
+
 pub(super) struct Socket {
     connection: Connection,
-    heartbeat_interval: Duration,
+    timing: ServerTiming,
     last_heartbeat: Instant,
+    runtime: Arc<dyn Runtime>,
 }
 
 impl Socket {
-    pub(super) async fn new() -> Result<Self, EngineIoError> {
-        let mut url = Url::parse(WS_URL).expect("WS_URL is valid url");
-        url.set_path(WS_PATH);
-        url.query_pairs_mut().append_pair("EIO", ENGINE_IO_VERSION);
-
+    pub(super) async fn new(
+        url: Url,
+        resolver: &DnsResolver,
+        tls: &TlsContainer,
+        runtime: Arc<dyn Runtime>,
+    ) -> Result<Self, EngineIoError> {
         let timeout = Duration::from_secs(30);
-        let handshake_fut = Self::handshake(url);
+        let handshake_fut = Self::handshake(url, resolver, tls);
 
-        let (connection, handshake) = tokio::time::timeout(timeout, handshake_fut)
+        let (connection, handshake) = runtime::timeout(&*runtime, timeout, handshake_fut)
             .await
-            .map_err(|_| EngineIoError::HandshakeTimeout)??;
+            .ok_or(EngineIoError::HandshakeTimeout)??;
 
         Ok(Self {
             connection,
-            heartbeat_interval: handshake.heartbeat_interval(),
+            timing: handshake.timing(),
             last_heartbeat: Instant::now(),
+            runtime,
         })
     }
 
-    async fn handshake(mut url: Url) -> Result<(Connection, HandshakePacket), EngineIoError> {
-        url.query_pairs_mut().append_pair("transport", "websocket");
-        url.set_scheme("wss").expect("wss is valid scheme");
-
-        let mut connection = TlsContainer::new()?.connect(&url).await?;
+    async fn handshake(
+        url: Url,
+        resolver: &DnsResolver,
+        tls: &TlsContainer,
+    ) -> Result<(Connection, HandshakePacket), EngineIoError> {
+        let mut connection = tls.connect(&url, resolver).await?;
 
         let msg = connection
             .next()
             .await
-            .expect("websocket is open at this point")
+            .ok_or(EngineIoError::HandshakeClosed)?
             .map_err(EngineIoError::WebsocketReceive)?;
 
         let Message::Text(text) = msg else {
@@ -72,14 +98,18 @@ impl Socket {
 
     pub(super) async fn next_packet(&mut self) -> Result<Option<Packet>, EngineIoError> {
         loop {
-            let timeout = self.heartbeat_deadline();
+            let timeout = self
+                .heartbeat_deadline()
+                .saturating_duration_since(Instant::now());
+
+            let next_message = self.connection.next();
 
-            let message = match tokio::time::timeout_at(timeout, self.connection.next()).await {
-                Ok(Some(message)) => message,
-                Ok(None) => return Ok(None),
-                Err(_) => {
+            let message = match runtime::timeout(&*self.runtime, timeout, next_message).await {
+                Some(Some(message)) => message,
+                Some(None) => return Ok(None),
+                None => {
                     trace!(
-                        interval = ?self.heartbeat_interval,
+                        deadline = ?self.timing.heartbeat_deadline(),
                         since_last_heartbeat = ?self.last_heartbeat.elapsed(),
                         "Heartbeat timed out",
                     );
@@ -104,7 +134,18 @@ impl Socket {
     }
 
     pub(super) async fn pong(&mut self) -> Result<(), EngineIoError> {
-        self.last_heartbeat = Instant::now();
+        let now = Instant::now();
+        let elapsed = now - self.last_heartbeat;
+        self.last_heartbeat = now;
+
+        if self.timing.is_ping_late(elapsed) {
+            warn!(
+                ?elapsed,
+                ping_interval = ?self.timing.ping_interval,
+                ping_timeout = ?self.timing.ping_timeout,
+                "Server ping arrived close to the timeout"
+            );
+        }
 
         self.emit(Packet::new(PacketId::Pong, Bytes::new())).await
     }
@@ -113,8 +154,12 @@ impl Socket {
         self.emit(Packet::new(PacketId::Close, Bytes::new())).await
     }
 
+    pub(super) const fn timing(&self) -> ServerTiming {
+        self.timing
+    }
+
     fn heartbeat_deadline(&self) -> Instant {
-        self.last_heartbeat + self.heartbeat_interval
+        self.last_heartbeat + self.timing.heartbeat_deadline()
     }
 
     async fn emit_static(connection: &mut Connection, packet: Packet) -> Result<(), EngineIoError> {
@@ -1,10 +1,21 @@
-use tokio::net::TcpStream;
-use tokio_tungstenite::{
-    tungstenite::protocol::WebSocketConfig, Connector, MaybeTlsStream, WebSocketStream,
+use std::{
+    io::{Error as IoError, ErrorKind},
+    net::SocketAddr,
 };
+
+use tokio_tungstenite::tungstenite::{protocol::WebSocketConfig, Error as TungsteniteError};
 use url::Url;
 
-use super::error::EngineIoError;
+#[cfg(feature = "runtime-async-std")]
+use async_std::net::TcpStream;
+#[cfg(all(feature = "runtime-smol", not(feature = "runtime-async-std")))]
+use smol::net::TcpStream;
+#[cfg(not(any(feature = "runtime-async-std", feature = "runtime-smol")))]
+use tokio::net::TcpStream;
+
+use crate::dns::DnsResolver;
+
+use super::error::{EngineIoError, HandshakeResponse};
 
 // `max_send_queue` is currently marked deprecated and does nothing anymore
 #[allow(deprecated)]
@@ -17,50 +28,160 @@ const WEBSOCKET_CONFIG: WebSocketConfig = WebSocketConfig {
     max_write_buffer_size: 64 * 1024,
 };
 
-pub(super) type Connection = WebSocketStream<MaybeTlsStream<TcpStream>>;
+#[cfg(not(any(feature = "runtime-async-std", feature = "runtime-smol")))]
+pub(super) type Connection =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<TcpStream>>;
+
+/// With a non-tokio runtime, the connection is established through
+/// `async-tungstenite` on top of that runtime's TCP stream.
+#[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+pub(super) type Connection = async_tungstenite::WebSocketStream<Box<dyn Stream>>;
+
+/// A plain or TLS stream of a non-tokio runtime.
+#[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+pub(super) trait Stream: AsyncRead + AsyncWrite + Send + Sync + Unpin {}
+
+#[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+impl<T: AsyncRead + AsyncWrite + Send + Sync + Unpin> Stream for T {}
+
+/// TLS options that were specified through the
+/// [`OrdrWebsocketBuilder`](crate::websocket::OrdrWebsocketBuilder).
+#[derive(Clone, Default)]
+pub(crate) struct TlsOptions {
+    /// DER-encoded certificates to trust in addition to the default roots.
+    pub(crate) root_certificates: Vec<Vec<u8>>,
+    /// Prebuilt config to use instead of the default one.
+    #[cfg(any(feature = "ws-rustls-native-roots", feature = "ws-rustls-webpki-roots"))]
+    pub(crate) rustls_config: Option<std::sync::Arc<rustls_tls::ClientConfig>>,
+}
 
 #[derive(Clone)]
-pub(super) struct TlsContainer {
-    #[allow(unused)]
+pub(crate) struct TlsContainer {
     tls: Option<r#impl::TlsConnector>,
 }
 
 impl TlsContainer {
-    pub(super) fn new() -> Result<Self, EngineIoError> {
-        r#impl::new()
+    pub(crate) fn new(options: TlsOptions) -> Result<Self, EngineIoError> {
+        r#impl::new(options)
     }
 
-    pub(super) async fn connect(&self, url: &Url) -> Result<Connection, EngineIoError> {
-        r#impl::connect(url, WEBSOCKET_CONFIG, self).await
+    pub(super) async fn connect(
+        &self,
+        url: &Url,
+        resolver: &DnsResolver,
+    ) -> Result<Connection, EngineIoError> {
+        let stream = connect_tcp(url, resolver).await?;
+
+        self.handshake(url, stream).await.map_err(|err| match err {
+            TungsteniteError::Http(response) => {
+                let (parts, body) = response.into_parts();
+                let response = HandshakeResponse::new(parts.status, parts.headers, body.as_deref());
+
+                EngineIoError::HandshakeRejected {
+                    response: Box::new(response),
+                }
+            }
+            err => EngineIoError::Reconnect(err),
+        })
     }
 
-    #[allow(unused)]
-    pub(super) fn connector(&self) -> Option<Connector> {
-        r#impl::connector(self)
+    #[cfg(not(any(feature = "runtime-async-std", feature = "runtime-smol")))]
+    async fn handshake(
+        &self,
+        url: &Url,
+        stream: TcpStream,
+    ) -> Result<Connection, TungsteniteError> {
+        let connector = r#impl::connector(self);
+
+        tokio_tungstenite::client_async_tls_with_config(
+            url,
+            stream,
+            Some(WEBSOCKET_CONFIG),
+            connector,
+        )
+        .await
+        .map(|(stream, _)| stream)
     }
+
+    #[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+    async fn handshake(
+        &self,
+        url: &Url,
+        stream: TcpStream,
+    ) -> Result<Connection, TungsteniteError> {
+        let stream = match (url.scheme(), self.tls.as_ref()) {
+            ("wss", Some(tls)) => {
+                let domain = url
+                    .host_str()
+                    .ok_or(TungsteniteError::Url(UrlError::NoHostName))?;
+
+                r#impl::wrap_stream(tls, domain, stream).await?
+            }
+            _ => Box::new(stream) as Box<dyn Stream>,
+        };
+
+        async_tungstenite::client_async_with_config(url, stream, Some(WEBSOCKET_CONFIG))
+            .await
+            .map(|(stream, _)| stream)
+    }
+}
+
+/// Connect to the first reachable address of the url's host.
+async fn connect_tcp(url: &Url, resolver: &DnsResolver) -> Result<TcpStream, EngineIoError> {
+    let invalid_url = || EngineIoError::InvalidUrl {
+        url: Box::from(url.as_str()),
+    };
+
+    let host = url.host_str().ok_or_else(invalid_url)?;
+    let port = url.port_or_known_default().ok_or_else(invalid_url)?;
+    let addrs = resolver
+        .lookup(host)
+        .await
+        .map_err(EngineIoError::Connect)?;
+    let mut last_err = None;
+
+    for ip in addrs {
+        match TcpStream::connect(SocketAddr::new(ip, port)).await {
+            Ok(stream) => return Ok(stream),
+            Err(err) => {
+                trace!(%ip, ?err, "Failed to connect websocket address");
+                last_err = Some(err);
+            }
+        }
+    }
+
+    // `lookup` ensures at least one address so there should be an error
+    Err(EngineIoError::Connect(last_err.unwrap_or_else(|| {
+        IoError::new(ErrorKind::AddrNotAvailable, "no address to connect to")
+    })))
 }
 
-#[cfg(any(feature = "rustls-native-roots", feature = "rustls-webpki-roots"))]
+#[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+use futures::{AsyncRead, AsyncWrite};
+#[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+use tokio_tungstenite::tungstenite::error::UrlError;
+
+#[cfg(any(feature = "ws-rustls-native-roots", feature = "ws-rustls-webpki-roots"))]
 mod r#impl {
     //! Rustls
 
     use rustls_tls::ClientConfig;
     use std::sync::Arc;
-    use tokio_tungstenite::{tungstenite::protocol::WebSocketConfig, Connector};
-    use url::Url;
 
     use crate::websocket::engineio::error::EngineIoError;
 
-    use super::{Connection, TlsContainer};
+    use super::{TlsContainer, TlsOptions};
 
     pub(super) type TlsConnector = Arc<ClientConfig>;
 
-    #[cfg(any(feature = "rustls-native-roots", feature = "rustls-webpki-roots"))]
-    #[allow(clippy::unnecessary_wraps)]
-    pub(super) fn new() -> Result<TlsContainer, EngineIoError> {
+    pub(super) fn new(options: TlsOptions) -> Result<TlsContainer, EngineIoError> {
+        if let Some(config) = options.rustls_config {
+            return Ok(TlsContainer { tls: Some(config) });
+        }
+
         let mut roots = rustls_tls::RootCertStore::empty();
 
-        #[cfg(feature = "rustls-native-roots")]
+        #[cfg(feature = "ws-rustls-native-roots")]
         {
             let certs = rustls_native_certs::load_native_certs()
                 .map_err(|err| EngineIoError::LoadingTls(Box::new(err)))?;
@@ -72,7 +193,7 @@ mod r#impl {
             }
         }
 
-        #[cfg(feature = "rustls-webpki-roots")]
+        #[cfg(feature = "ws-rustls-webpki-roots")]
         {
             roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
                 rustls_tls::OwnedTrustAnchor::from_subject_spki_name_constraints(
@@ -83,6 +204,12 @@ mod r#impl {
             }));
         };
 
+        for cert in options.root_certificates {
+            roots
+                .add(&rustls_tls::Certificate(cert))
+                .map_err(|err| EngineIoError::LoadingTls(Box::new(err)))?;
+        }
+
         let config = ClientConfig::builder()
             .with_safe_defaults()
             .with_root_certificates(roots)
@@ -93,113 +220,110 @@ mod r#impl {
         })
     }
 
-    pub(super) async fn connect(
-        url: &Url,
-        config: WebSocketConfig,
-        tls: &TlsContainer,
-    ) -> Result<Connection, EngineIoError> {
-        let (stream, _) = tokio_tungstenite::connect_async_tls_with_config(
-            url,
-            Some(config),
-            false,
-            tls.connector(),
-        )
-        .await
-        .map_err(EngineIoError::Reconnect)?;
-
-        Ok(stream)
-    }
-
-    pub(super) fn connector(container: &TlsContainer) -> Option<Connector> {
+    #[cfg(not(any(feature = "runtime-async-std", feature = "runtime-smol")))]
+    pub(super) fn connector(container: &TlsContainer) -> Option<tokio_tungstenite::Connector> {
         container
             .tls
             .as_ref()
-            .map(|tls| Connector::Rustls(Arc::clone(tls)))
+            .map(|tls| tokio_tungstenite::Connector::Rustls(Arc::clone(tls)))
+    }
+
+    #[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+    pub(super) async fn wrap_stream(
+        tls: &TlsConnector,
+        domain: &str,
+        stream: super::TcpStream,
+    ) -> Result<Box<dyn super::Stream>, tokio_tungstenite::tungstenite::Error> {
+        use tokio_tungstenite::tungstenite::{error::TlsError, Error as TungsteniteError};
+
+        let domain = rustls_tls::ServerName::try_from(domain)
+            .map_err(|_| TungsteniteError::Tls(TlsError::InvalidDnsName))?;
+
+        let stream = futures_rustls::TlsConnector::from(Arc::clone(tls))
+            .connect(domain, stream)
+            .await
+            .map_err(TungsteniteError::Io)?;
+
+        Ok(Box::new(stream))
     }
 }
 
 #[cfg(all(
-    feature = "native",
-    not(any(feature = "rustls-native-roots", feature = "rustls-webpki-roots"))
+    feature = "ws-native",
+    not(any(feature = "ws-rustls-native-roots", feature = "ws-rustls-webpki-roots"))
 ))]
 mod r#impl {
     //! Native TLS
 
-    pub(super) use native_tls::TlsConnector;
-    use tokio_tungstenite::{tungstenite::protocol::WebSocketConfig, Connector};
-    use url::Url;
+    use native_tls::Certificate;
 
-    use super::{Connection, TlsContainer};
+    use super::{TlsContainer, TlsOptions};
 
     use crate::websocket::engineio::error::EngineIoError;
 
-    pub(super) fn new() -> Result<TlsContainer, EngineIoError> {
-        let native_connector =
-            TlsConnector::new().map_err(|err| EngineIoError::LoadingTls(Box::new(err)))?;
+    #[cfg(not(any(feature = "runtime-async-std", feature = "runtime-smol")))]
+    pub(super) use native_tls::TlsConnector;
+
+    #[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+    pub(super) type TlsConnector = std::sync::Arc<async_native_tls::TlsConnector>;
+
+    #[cfg(not(any(feature = "runtime-async-std", feature = "runtime-smol")))]
+    pub(super) fn new(options: TlsOptions) -> Result<TlsContainer, EngineIoError> {
+        let mut builder = TlsConnector::builder();
+
+        for cert in options.root_certificates {
+            let cert = Certificate::from_der(&cert)
+                .map_err(|err| EngineIoError::LoadingTls(Box::new(err)))?;
+
+            builder.add_root_certificate(cert);
+        }
+
+        let native_connector = builder
+            .build()
+            .map_err(|err| EngineIoError::LoadingTls(Box::new(err)))?;
 
         Ok(TlsContainer {
             tls: Some(native_connector),
         })
     }
 
-    pub(super) async fn connect(
-        url: &Url,
-        config: WebSocketConfig,
-        tls: &TlsContainer,
-    ) -> Result<Connection, EngineIoError> {
-        let (stream, _) = tokio_tungstenite::connect_async_tls_with_config(
-            url,
-            Some(config),
-            false,
-            tls.connector(),
-        )
-        .await
-        .map_err(EngineIoError::Reconnect)?;
-
-        Ok(stream)
-    }
-
-    pub(super) fn connector(container: &TlsContainer) -> Option<Connector> {
+    #[cfg(not(any(feature = "runtime-async-std", feature = "runtime-smol")))]
+    pub(super) fn connector(container: &TlsContainer) -> Option<tokio_tungstenite::Connector> {
         container
             .tls
             .as_ref()
-            .map(|tls| Connector::NativeTls(tls.clone()))
+            .map(|tls| tokio_tungstenite::Connector::NativeTls(tls.clone()))
     }
-}
-
-#[cfg(not(any(
-    feature = "native",
-    feature = "rustls-native-roots",
-    feature = "rustls-webpki-roots"
-)))]
-mod r#impl {
-    //! Plain connections with no TLS.
 
-    pub(super) type TlsConnector = ();
-    use tokio_tungstenite::{tungstenite::protocol::WebSocketConfig, Connector};
-    use url::Url;
+    #[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+    pub(super) fn new(options: TlsOptions) -> Result<TlsContainer, EngineIoError> {
+        let mut connector = async_native_tls::TlsConnector::new();
 
-    use crate::websocket::engineio::EngineIoError;
+        for cert in options.root_certificates {
+            let cert = Certificate::from_der(&cert)
+                .map_err(|err| EngineIoError::LoadingTls(Box::new(err)))?;
 
-    use super::{Connection, TlsContainer};
+            connector = connector.add_root_certificate(cert);
+        }
 
-    pub(super) fn new() -> Result<TlsContainer, EngineIoError> {
-        Ok(TlsContainer { tls: None })
+        Ok(TlsContainer {
+            tls: Some(std::sync::Arc::new(connector)),
+        })
     }
 
-    pub(super) async fn connect(
-        url: &Url,
-        config: WebSocketConfig,
-        _tls: &TlsContainer,
-    ) -> Result<Connection, EngineIoError> {
-        let (stream, _) = tokio_tungstenite::connect_async_with_config(url, Some(config), false)
-            .await
-            .map_err(EngineIoError::Reconnect)?;
+    #[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+    pub(super) async fn wrap_stream(
+        tls: &TlsConnector,
+        domain: &str,
+        stream: super::TcpStream,
+    ) -> Result<Box<dyn super::Stream>, tokio_tungstenite::tungstenite::Error> {
+        use tokio_tungstenite::tungstenite::{error::TlsError, Error as TungsteniteError};
 
-        Ok(stream)
-    }
+        let stream = tls
+            .connect(domain, stream)
+            .await
+            .map_err(|err| TungsteniteError::Tls(TlsError::Native(err)))?;
 
-    pub(super) fn connector(_: &TlsContainer) -> Option<Connector> {
-        None
+        Ok(Box::new(stream))
     }
 }
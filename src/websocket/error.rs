@@ -1,12 +1,13 @@
-use std::str::Utf8Error;
+use std::{str::Utf8Error, time::Duration};
 
 use bytes::Bytes;
 use serde_json::Error as SerdeError;
 use thiserror::Error as ThisError;
 
-use crate::websocket::engineio::error::EngineIoError;
+pub use crate::websocket::engineio::error::{EngineIoError, HandshakeResponse};
 
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum WebsocketError {
     #[error("Failed to deserialize data={data:?}")]
     Deserialize {
@@ -24,4 +25,22 @@ pub enum WebsocketError {
     InvalidPacket,
     #[error("Failed to decode binary as UTF-8")]
     InvalidUtf8(#[from] Utf8Error),
+    /// Reconnecting was given up as configured through
+    /// [`ReconnectConfig`](crate::client::ReconnectConfig).
+    ///
+    /// The websocket will keep returning this error; a new one must be connected.
+    #[error("Gave up reconnecting after {attempts} attempts and {downtime:?} downtime")]
+    ReconnectExhausted { attempts: u32, downtime: Duration },
+}
+
+impl WebsocketError {
+    /// The HTTP response if the server rejected the websocket upgrade,
+    /// e.g. to tell apart Cloudflare blocks from o!rdr maintenance.
+    #[must_use]
+    pub fn handshake_response(&self) -> Option<&HandshakeResponse> {
+        match self {
+            Self::EngineIo(EngineIoError::HandshakeRejected { response }) => Some(response),
+            _ => None,
+        }
+    }
 }
@@ -1,10 +1,15 @@
+use std::ops::{BitOr, BitOrAssign};
+
 use bytes::Bytes;
+use serde::Deserialize;
 use serde_json::Error as SerdeError;
 
 use crate::model::{
     CustomSkinProcessUpdate, Event, RenderAdded, RenderDone, RenderFailed, RenderProgress,
 };
 
+use super::frame::EventFrame;
+
 /// Websocket [`Event`](crate::model::Event) that has not been fully deserialized yet.
 /// This lets you check if you're interested in the event and only then deserialize it.
 ///
@@ -35,83 +40,236 @@ pub enum RawEvent {
     CustomSkinProcessUpdate(RawCustomSkinProcessUpdate),
 }
 
-impl RawEvent {
-    pub(crate) fn from_bytes(bytes: Bytes) -> Result<Self, crate::WebsocketError> {
-        fn split_bytes(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
-            let comma_idx = bytes.iter().position(|&byte| byte == b',')?;
+/// The kind of a [`RawEvent`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EventKind {
+    RenderAdded,
+    RenderDone,
+    RenderFailed,
+    RenderProgress,
+    CustomSkinProcessUpdate,
+}
 
-            let ([b'[', b'"', prefix @ .., b'"'], [_, suffix @ .., b']']) =
-                bytes.split_at(comma_idx)
-            else {
-                return None;
-            };
+impl EventKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RenderAdded => "render_added",
+            Self::RenderDone => "render_done",
+            Self::RenderFailed => "render_failed",
+            Self::RenderProgress => "render_progress",
+            Self::CustomSkinProcessUpdate => "custom_skin_process_update",
+        }
+    }
 
-            Some((prefix, suffix))
+    /// The kind of the socket.io event with the given name.
+    pub(super) fn from_event_name(name: &str) -> Option<Self> {
+        match name {
+            "render_added_json" => Some(Self::RenderAdded),
+            "render_done_json" => Some(Self::RenderDone),
+            "render_failed_json" => Some(Self::RenderFailed),
+            "render_progress_json" => Some(Self::RenderProgress),
+            "custom_skin_process_update" => Some(Self::CustomSkinProcessUpdate),
+            _ => None,
         }
+    }
+
+    const fn bit(self) -> u8 {
+        match self {
+            Self::RenderAdded => 1 << 0,
+            Self::RenderDone => 1 << 1,
+            Self::RenderFailed => 1 << 2,
+            Self::RenderProgress => 1 << 3,
+            Self::CustomSkinProcessUpdate => 1 << 4,
+        }
+    }
+}
+
+impl BitOr for EventKind {
+    type Output = EventMask;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        EventMask::from(self) | rhs
+    }
+}
+
+/// A set of [`EventKind`]s.
+///
+/// Events whose kind is not contained in the mask of an
+/// [`OrdrWebsocket`](super::OrdrWebsocket) are dropped before they are
+/// deserialized.
+///
+/// # Example
+/// ```
+/// use rosu_render::websocket::event::{EventKind, EventMask};
+///
+/// let mask = EventKind::RenderDone | EventKind::RenderFailed;
+///
+/// assert_eq!(mask, EventMask::RENDER_DONE | EventMask::RENDER_FAILED);
+/// assert!(mask.contains(EventKind::RenderDone));
+/// assert!(!mask.contains(EventKind::RenderProgress));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EventMask(u8);
+
+impl EventMask {
+    pub const RENDER_ADDED: Self = Self(EventKind::RenderAdded.bit());
+    pub const RENDER_DONE: Self = Self(EventKind::RenderDone.bit());
+    pub const RENDER_FAILED: Self = Self(EventKind::RenderFailed.bit());
+    pub const RENDER_PROGRESS: Self = Self(EventKind::RenderProgress.bit());
+    pub const CUSTOM_SKIN_PROCESS_UPDATE: Self = Self(EventKind::CustomSkinProcessUpdate.bit());
 
-        fn find_render_id(mut bytes: &[u8]) -> Option<u32> {
-            loop {
-                let idx = bytes.iter().position(|&byte| byte == b'r')?;
+    /// A mask that contains every kind.
+    pub const ALL: Self = Self(
+        Self::RENDER_ADDED.0
+            | Self::RENDER_DONE.0
+            | Self::RENDER_FAILED.0
+            | Self::RENDER_PROGRESS.0
+            | Self::CUSTOM_SKIN_PROCESS_UPDATE.0,
+    );
 
-                let Some(rest) = bytes[idx..].strip_prefix(b"renderID\":") else {
-                    bytes = &bytes[idx + 1..];
+    /// A mask that contains no kind.
+    pub const NONE: Self = Self(0);
 
-                    continue;
-                };
+    /// Whether the mask contains the given kind.
+    #[must_use]
+    pub const fn contains(self, kind: EventKind) -> bool {
+        self.0 & kind.bit() != 0
+    }
+
+    /// Whether the mask contains every kind.
+    #[must_use]
+    pub const fn is_all(self) -> bool {
+        self.0 == Self::ALL.0
+    }
+}
 
-                rest.first().copied().filter(u8::is_ascii_digit)?;
+impl Default for EventMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
 
-                let render_id = rest
-                    .iter()
-                    .copied()
-                    .take_while(u8::is_ascii_digit)
-                    .fold(0, |num, byte| num * 10 + u32::from(byte & 0xF));
+impl From<EventKind> for EventMask {
+    fn from(kind: EventKind) -> Self {
+        Self(kind.bit())
+    }
+}
+
+impl BitOr for EventMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOr<EventKind> for EventMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: EventKind) -> Self::Output {
+        self | Self::from(rhs)
+    }
+}
+
+impl BitOrAssign for EventMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitOrAssign<EventKind> for EventMask {
+    fn bitor_assign(&mut self, rhs: EventKind) {
+        self.0 |= rhs.bit();
+    }
+}
+
+impl RawEvent {
+    /// The kind of the event.
+    #[must_use]
+    pub fn kind(&self) -> EventKind {
+        match self {
+            RawEvent::RenderAdded(_) => EventKind::RenderAdded,
+            RawEvent::RenderDone(_) => EventKind::RenderDone,
+            RawEvent::RenderFailed(_) => EventKind::RenderFailed,
+            RawEvent::RenderProgress(_) => EventKind::RenderProgress,
+            RawEvent::CustomSkinProcessUpdate(_) => EventKind::CustomSkinProcessUpdate,
+        }
+    }
+
+    /// The JSON payload of the event.
+    #[must_use]
+    pub fn payload(&self) -> &Bytes {
+        match self {
+            RawEvent::RenderAdded(event) => &event.bytes,
+            RawEvent::RenderDone(event) => &event.bytes,
+            RawEvent::RenderFailed(event) => &event.bytes,
+            RawEvent::RenderProgress(event) => &event.bytes,
+            RawEvent::CustomSkinProcessUpdate(event) => &event.bytes,
+        }
+    }
 
-                return Some(render_id);
+    /// Parse the data of a socket.io event packet, e.g.
+    /// `["render_done_json",{"renderID":1,"videoUrl":"..."}]`.
+    ///
+    /// The event's payload is its first argument; further arguments are ignored.
+    pub(crate) fn from_bytes(bytes: Bytes) -> Result<Self, crate::WebsocketError> {
+        /// Only deserializes the `renderID` field and skips all others
+        /// regardless of their order or surrounding whitespace.
+        fn find_render_id(bytes: &[u8]) -> Option<u32> {
+            #[derive(Deserialize)]
+            struct RenderId {
+                #[serde(rename = "renderID")]
+                render_id: u32,
             }
+
+            serde_json::from_slice::<RenderId>(bytes)
+                .ok()
+                .map(|id| id.render_id)
         }
 
-        let Some((event, payload)) = split_bytes(&bytes) else {
+        let Ok(EventFrame { name, args }) = EventFrame::parse(&bytes) else {
             return Err(crate::WebsocketError::InvalidEvent(bytes));
         };
 
-        let payload_bytes = bytes.slice_ref(payload);
+        let Some(payload) = args.into_iter().next() else {
+            return Err(crate::WebsocketError::InvalidEvent(bytes));
+        };
 
-        match event {
-            b"render_progress_json" => find_render_id(payload)
-                .map(|render_id| {
-                    Self::RenderProgress(RawRenderProgress {
-                        render_id,
-                        bytes: payload_bytes,
-                    })
+        let render_id = || find_render_id(&payload);
+
+        let event = match name.as_ref() {
+            "render_progress_json" => render_id().map(|render_id| {
+                Self::RenderProgress(RawRenderProgress {
+                    render_id,
+                    bytes: payload.clone(),
                 })
-                .ok_or(crate::WebsocketError::InvalidEvent(bytes)),
-            b"render_added_json" => Ok(Self::RenderAdded(RawRenderAdded {
-                bytes: payload_bytes,
+            }),
+            "render_added_json" => Some(Self::RenderAdded(RawRenderAdded {
+                bytes: payload.clone(),
             })),
-            b"render_done_json" => find_render_id(payload)
-                .map(|render_id| {
-                    Self::RenderDone(RawRenderDone {
-                        render_id,
-                        bytes: payload_bytes,
-                    })
+            "render_done_json" => render_id().map(|render_id| {
+                Self::RenderDone(RawRenderDone {
+                    render_id,
+                    bytes: payload.clone(),
                 })
-                .ok_or(crate::WebsocketError::InvalidEvent(bytes)),
-            b"render_failed_json" => find_render_id(payload)
-                .map(|render_id| {
-                    Self::RenderFailed(RawRenderFailed {
-                        render_id,
-                        bytes: payload_bytes,
-                    })
+            }),
+            "render_failed_json" => render_id().map(|render_id| {
+                Self::RenderFailed(RawRenderFailed {
+                    render_id,
+                    bytes: payload.clone(),
                 })
-                .ok_or(crate::WebsocketError::InvalidEvent(bytes)),
-            b"custom_skin_process_update" => {
-                Ok(Self::CustomSkinProcessUpdate(RawCustomSkinProcessUpdate {
-                    bytes: payload_bytes,
+            }),
+            "custom_skin_process_update" => {
+                Some(Self::CustomSkinProcessUpdate(RawCustomSkinProcessUpdate {
+                    bytes: payload.clone(),
                 }))
             }
-            _ => Err(crate::WebsocketError::InvalidEvent(bytes)),
-        }
+            _ => None,
+        };
+
+        event.ok_or(crate::WebsocketError::InvalidEvent(bytes))
     }
 
     /// Deserialize into an [`Event`].
@@ -195,3 +353,170 @@ impl RawCustomSkinProcessUpdate {
         serde_json::from_slice(&self.bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use bytes::Bytes;
+    use proptest::{
+        array::{uniform4, uniform6},
+        collection::vec,
+        prelude::*,
+        sample::select,
+    };
+
+    use crate::model::Event;
+
+    use super::{EventKind, RawEvent};
+
+    /// Random JSON whitespace.
+    fn ws() -> impl Strategy<Value = &'static str> {
+        select(&["", " ", "  ", "\n", "\t", "\r\n "][..])
+    }
+
+    /// A JSON string containing characters that need escaping.
+    fn string() -> impl Strategy<Value = String> {
+        "[aZ0 \"\\\\é%]{0,11}".prop_map(|chars| serde_json::to_string(&chars).unwrap())
+    }
+
+    /// The fields as object in random order with random whitespace.
+    fn object(fields: Vec<(&'static str, String)>) -> impl Strategy<Value = String> {
+        let len = fields.len();
+
+        (Just(fields).prop_shuffle(), vec(uniform4(ws()), len)).prop_map(|(fields, padding)| {
+            let mut json = String::from("{");
+
+            for (i, ((key, value), [a, b, c, d])) in fields.iter().zip(padding).enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+
+                let _ = write!(json, r#"{a}"{key}"{b}:{c}{value}{d}"#);
+            }
+
+            json.push('}');
+
+            json
+        })
+    }
+
+    /// A frame with its event kind and id.
+    fn event() -> impl Strategy<Value = (EventKind, u32, String)> {
+        let added = any::<u32>().prop_flat_map(|id| {
+            let fields = vec![("renderID", id.to_string())];
+
+            (
+                Just((EventKind::RenderAdded, id, "render_added_json")),
+                object(fields),
+            )
+        });
+
+        let done = (any::<u32>(), string()).prop_flat_map(|(id, video_url)| {
+            let fields = vec![("renderID", id.to_string()), ("videoUrl", video_url)];
+
+            (
+                Just((EventKind::RenderDone, id, "render_done_json")),
+                object(fields),
+            )
+        });
+
+        let failed = (any::<u32>(), 1..=40_u8, string()).prop_flat_map(|(id, code, msg)| {
+            let fields = vec![
+                ("renderID", id.to_string()),
+                ("errorCode", code.to_string()),
+                ("errorMessage", msg),
+            ];
+
+            (
+                Just((EventKind::RenderFailed, id, "render_failed_json")),
+                object(fields),
+            )
+        });
+
+        let progress = (any::<u32>(), string(), string(), string(), string()).prop_flat_map(
+            |(id, progress, description, renderer, username)| {
+                let fields = vec![
+                    ("renderID", id.to_string()),
+                    ("progress", progress),
+                    ("description", description),
+                    ("renderer", renderer),
+                    ("username", username),
+                ];
+
+                let kind = (EventKind::RenderProgress, id, "render_progress_json");
+
+                (Just(kind), object(fields))
+            },
+        );
+
+        let skin = any::<u32>().prop_flat_map(|id| {
+            let fields = vec![("skinId", id.to_string())];
+            let kind = (
+                EventKind::CustomSkinProcessUpdate,
+                id,
+                "custom_skin_process_update",
+            );
+
+            (Just(kind), object(fields))
+        });
+
+        let payload = prop_oneof![added, done, failed, progress, skin];
+
+        (payload, uniform6(ws())).prop_map(|(((kind, id, name), payload), [a, b, c, d, e, f])| {
+            let frame = format!(r#"{a}[{b}"{name}"{c},{d}{payload}{e}]{f}"#);
+
+            (kind, id, frame)
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(512))]
+
+        #[test]
+        fn parse_random_events((kind, id, frame) in event()) {
+            let event = RawEvent::from_bytes(Bytes::from(frame.clone()))
+                .map_err(|err| TestCaseError::fail(format!("failed to parse: {err:?}")))?;
+
+            prop_assert_eq!(event.kind(), kind);
+
+            let render_id = match event {
+                RawEvent::RenderDone(ref event) => Some(event.render_id),
+                RawEvent::RenderFailed(ref event) => Some(event.render_id),
+                RawEvent::RenderProgress(ref event) => Some(event.render_id),
+                RawEvent::RenderAdded(_) | RawEvent::CustomSkinProcessUpdate(_) => None,
+            };
+
+            if let Some(render_id) = render_id {
+                prop_assert_eq!(render_id, id);
+            }
+
+            let deserialized_id = match event.deserialize() {
+                Ok(Event::RenderAdded(event)) => event.render_id,
+                Ok(Event::RenderDone(event)) => event.render_id,
+                Ok(Event::RenderFailed(event)) => event.render_id,
+                Ok(Event::RenderProgress(event)) => event.render_id,
+                Ok(Event::CustomSkinProcessUpdate(event)) => event.skin_id,
+                Err(err) => {
+                    return Err(TestCaseError::fail(format!("failed to deserialize: {err:?}")));
+                }
+            };
+
+            prop_assert_eq!(deserialized_id, id);
+        }
+    }
+
+    #[test]
+    fn reject_missing_render_id() {
+        let frames = [
+            r#"["render_done_json",{"videoUrl":"a"}]"#,
+            r#"["render_done_json",{"renderID":"12"}]"#,
+            r#"["render_progress_json",{"data":{"renderID":12}}]"#,
+            r#"["render_failed_json",{"renderID":-1}]"#,
+        ];
+
+        for frame in frames {
+            assert!(RawEvent::from_bytes(Bytes::from_static(frame.as_bytes())).is_err());
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt::{Formatter, Result as FmtResult};
+
+use bytes::Bytes;
+use serde::de::{Deserializer, Error as _, IgnoredAny, SeqAccess, Visitor};
+use serde_json::{value::RawValue, Error as JsonError};
+
+/// Data of a socket.io event packet, i.e. a JSON array whose first
+/// element is the event name followed by the event's arguments.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct EventFrame {
+    pub name: Box<str>,
+    /// The JSON of each argument. String arguments are unescaped
+    /// so that stringified JSON payloads are handled transparently.
+    pub args: Vec<Bytes>,
+}
+
+impl EventFrame {
+    pub(super) fn parse(bytes: &Bytes) -> Result<Self, JsonError> {
+        let values: Vec<&RawValue> = serde_json::from_slice(bytes)?;
+
+        let Some((name, args)) = values.split_first() else {
+            return Err(JsonError::custom("missing event name"));
+        };
+
+        let name = serde_json::from_str(name.get())?;
+
+        let args = args
+            .iter()
+            .map(|arg| {
+                let json = arg.get();
+
+                if json.starts_with('"') {
+                    serde_json::from_str::<String>(json).map(Bytes::from)
+                } else {
+                    Ok(bytes.slice_ref(json.as_bytes()))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { name, args })
+    }
+
+    /// Only deserialize the event name and skip over the arguments without
+    /// allocating.
+    ///
+    /// Fails for names that contain escape sequences.
+    pub(super) fn parse_name(bytes: &[u8]) -> Result<&str, JsonError> {
+        struct NameVisitor;
+
+        impl<'de> Visitor<'de> for NameVisitor {
+            type Value = &'de str;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+                f.write_str("an event frame")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let name = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::custom("missing event name"))?;
+
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+
+                Ok(name)
+            }
+        }
+
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        let name = deserializer.deserialize_seq(NameVisitor)?;
+        deserializer.end()?;
+
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::EventFrame;
+
+    fn parse(frame: &'static str) -> Option<EventFrame> {
+        EventFrame::parse(&Bytes::from_static(frame.as_bytes())).ok()
+    }
+
+    #[test]
+    fn parse_frames() {
+        let frame =
+            parse(r#" [ "render_done_json" ,  {"renderID": 1} , 2, "{\"a\":1}" ] "#).unwrap();
+
+        assert_eq!(&*frame.name, "render_done_json");
+        assert_eq!(
+            frame.args,
+            [
+                Bytes::from_static(br#"{"renderID": 1}"#),
+                Bytes::from_static(b"2"),
+                Bytes::from_static(br#"{"a":1}"#),
+            ]
+        );
+
+        let frame = parse(r#"["esc\"aped"]"#).unwrap();
+        assert_eq!(&*frame.name, "esc\"aped");
+        assert!(frame.args.is_empty());
+    }
+
+    #[test]
+    fn parse_names() {
+        let name = EventFrame::parse_name(br#" [ "render_done_json" , {"renderID": 1}, "{}" ] "#);
+        assert_eq!(name.unwrap(), "render_done_json");
+
+        assert!(EventFrame::parse_name(br#"["esc\"aped"]"#).is_err());
+        assert!(EventFrame::parse_name(b"[]").is_err());
+        assert!(EventFrame::parse_name(br#"["render_done_json", {]"#).is_err());
+    }
+
+    #[test]
+    fn reject_invalid_frames() {
+        assert!(parse("[]").is_none());
+        assert!(parse("[1, {}]").is_none());
+        assert!(parse(r#"{"name": "render_done_json"}"#).is_none());
+        assert!(parse(r#"["render_done_json", {]"#).is_none());
+    }
+}
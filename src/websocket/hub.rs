@@ -0,0 +1,635 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tokio::{sync::Notify, time::Instant};
+
+use super::event::{EventKind, RawEvent};
+
+/// What happens to a new event if a receiver's buffer is full.
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum OverflowPolicy {
+    /// Drop the oldest buffered event of the receiver.
+    #[default]
+    DropOldest,
+    /// Wait until the receiver made space.
+    ///
+    /// A slow receiver thus delays all other receivers as well as the
+    /// tracking of renders.
+    Block,
+    /// Disconnect the receiver. It still receives its buffered events
+    /// and then returns `None`.
+    DisconnectSubscriber,
+    /// Drop progress events, either the new one or the oldest buffered one.
+    ///
+    /// Other events are always buffered, even beyond the capacity, so that
+    /// e.g. no `render_done` or `render_failed` is lost.
+    DropProgress,
+    /// Like [`OverflowPolicy::DropProgress`] but additionally, regardless of the
+    /// capacity, a progress event replaces a still buffered progress event
+    /// of the same render so that only the latest progress is received.
+    CoalesceProgress,
+}
+
+/// How websocket events are distributed to multiple receivers, e.g. to
+/// those of [`OrdrService::events`](crate::OrdrService::events).
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct HubConfig {
+    /// The amount of events that are buffered for each receiver.
+    ///
+    /// Defaults to 128.
+    pub capacity: usize,
+    /// What happens if a receiver's buffer is full.
+    pub overflow: OverflowPolicy,
+    /// If specified, a receiver gets at most one progress event per render
+    /// within this many milliseconds. Progress events in between are
+    /// coalesced so that only the latest one is received.
+    pub progress_window_ms: Option<u64>,
+}
+
+impl Default for HubConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 128,
+            overflow: OverflowPolicy::DropOldest,
+            progress_window_ms: None,
+        }
+    }
+}
+
+/// Statistics of the distributed events and their receivers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HubStats {
+    /// Amount of distributed events per kind.
+    pub events: HashMap<EventKind, u64>,
+    /// Statistics of each receiver that is still alive.
+    pub subscribers: Vec<SubscriberStats>,
+}
+
+/// Statistics of a single receiver.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SubscriberStats {
+    /// Identifies the receiver across multiple [`HubStats`].
+    pub id: u64,
+    /// Amount of events that were delivered into the receiver's buffer.
+    pub received: u64,
+    /// Amount of events that were dropped because the receiver's buffer was full.
+    pub dropped: u64,
+    /// Amount of progress events that were replaced by a later one due to
+    /// [`HubConfig::progress_window_ms`].
+    pub coalesced: u64,
+    /// Amount of events that are currently buffered.
+    pub queued: usize,
+    /// Whether the receiver was disconnected due to
+    /// [`OverflowPolicy::DisconnectSubscriber`].
+    pub disconnected: bool,
+}
+
+/// Distributes events to any amount of receivers, each with their own buffer.
+pub(crate) struct EventHub {
+    config: HubConfig,
+    state: Mutex<HubState>,
+}
+
+#[derive(Default)]
+struct HubState {
+    subscribers: Vec<Arc<Queue>>,
+    next_id: u64,
+    events: HashMap<EventKind, u64>,
+}
+
+impl EventHub {
+    pub(crate) fn new(config: HubConfig) -> Self {
+        Self {
+            config: HubConfig {
+                capacity: config.capacity.max(1),
+                ..config
+            },
+            state: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> HubReceiver {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let progress_window = self.config.progress_window_ms.map(Duration::from_millis);
+        let queue = Arc::new(Queue::new(
+            state.next_id,
+            self.config.capacity,
+            progress_window.is_some(),
+        ));
+        state.next_id += 1;
+        state.subscribers.push(Arc::clone(&queue));
+
+        HubReceiver {
+            queue,
+            last_dropped: 0,
+            progress_window: progress_window.map(|window| ProgressWindow {
+                window,
+                last_progress: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Distribute the event to all receivers.
+    pub(crate) async fn publish(&self, event: &RawEvent) {
+        let subscribers = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            *state.events.entry(event.kind()).or_default() += 1;
+            state.subscribers.retain(|queue| queue.is_active());
+
+            state.subscribers.clone()
+        };
+
+        for queue in subscribers {
+            queue.push(event.clone(), self.config.overflow).await;
+        }
+    }
+
+    pub(crate) fn stats(&self) -> HubStats {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state
+            .subscribers
+            .retain(|queue| !queue.receiver_dropped.load(Ordering::Relaxed));
+
+        HubStats {
+            events: state.events.clone(),
+            subscribers: state
+                .subscribers
+                .iter()
+                .map(|queue| queue.stats())
+                .collect(),
+        }
+    }
+}
+
+impl Drop for EventHub {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+
+        for queue in state.subscribers.drain(..) {
+            queue.disconnect();
+        }
+    }
+}
+
+struct Queue {
+    id: u64,
+    capacity: usize,
+    coalesce_progress: bool,
+    events: Mutex<VecDeque<RawEvent>>,
+    received: AtomicU64,
+    dropped: AtomicU64,
+    coalesced: AtomicU64,
+    disconnected: AtomicBool,
+    receiver_dropped: AtomicBool,
+    readable: Notify,
+    writable: Notify,
+}
+
+impl Queue {
+    fn new(id: u64, capacity: usize, coalesce_progress: bool) -> Self {
+        Self {
+            id,
+            capacity,
+            coalesce_progress,
+            events: Mutex::new(VecDeque::with_capacity(capacity.min(1024))),
+            received: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+            disconnected: AtomicBool::new(false),
+            receiver_dropped: AtomicBool::new(false),
+            readable: Notify::new(),
+            writable: Notify::new(),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        !(self.disconnected.load(Ordering::Relaxed)
+            || self.receiver_dropped.load(Ordering::Relaxed))
+    }
+
+    async fn push(&self, event: RawEvent, overflow: OverflowPolicy) {
+        loop {
+            let writable = {
+                let mut events = self.events.lock().unwrap_or_else(PoisonError::into_inner);
+
+                if !self.is_active() {
+                    return;
+                }
+
+                if self.coalesce_progress || overflow == OverflowPolicy::CoalesceProgress {
+                    if let RawEvent::RenderProgress(ref progress) = event {
+                        let queued = events.iter_mut().find(|queued| {
+                            matches!(queued, RawEvent::RenderProgress(queued) if queued.render_id == progress.render_id)
+                        });
+
+                        if let Some(queued) = queued {
+                            *queued = event;
+                            self.received.fetch_add(1, Ordering::Relaxed);
+
+                            if self.coalesce_progress {
+                                self.coalesced.fetch_add(1, Ordering::Relaxed);
+                            } else {
+                                self.dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+
+                            return;
+                        }
+                    }
+                }
+
+                if events.len() < self.capacity {
+                    events.push_back(event);
+                    self.received.fetch_add(1, Ordering::Relaxed);
+                    self.readable.notify_one();
+
+                    return;
+                }
+
+                match overflow {
+                    OverflowPolicy::DropOldest => {
+                        events.pop_front();
+                        events.push_back(event);
+                        self.received.fetch_add(1, Ordering::Relaxed);
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.readable.notify_one();
+
+                        return;
+                    }
+                    OverflowPolicy::DisconnectSubscriber => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        warn!(
+                            subscriber = self.id,
+                            "Disconnecting event receiver that fell behind"
+                        );
+                        self.disconnect();
+
+                        return;
+                    }
+                    OverflowPolicy::DropProgress | OverflowPolicy::CoalesceProgress => {
+                        if !matches!(event, RawEvent::RenderProgress(_)) {
+                            let progress = events
+                                .iter()
+                                .position(|queued| matches!(queued, RawEvent::RenderProgress(_)));
+
+                            if let Some(idx) = progress {
+                                events.remove(idx);
+                            }
+
+                            events.push_back(event);
+                            self.received.fetch_add(1, Ordering::Relaxed);
+                            self.readable.notify_one();
+
+                            if progress.is_none() {
+                                return;
+                            }
+                        }
+
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+
+                        return;
+                    }
+                    OverflowPolicy::Block => self.writable.notified(),
+                }
+            };
+
+            writable.await;
+        }
+    }
+
+    fn disconnect(&self) {
+        self.disconnected.store(true, Ordering::Relaxed);
+        self.readable.notify_one();
+    }
+
+    fn stats(&self) -> SubscriberStats {
+        SubscriberStats {
+            id: self.id,
+            received: self.received.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            queued: self
+                .events
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .len(),
+            disconnected: self.disconnected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Receiving end of an [`EventHub`].
+pub(crate) struct HubReceiver {
+    queue: Arc<Queue>,
+    last_dropped: u64,
+    progress_window: Option<ProgressWindow>,
+}
+
+/// Holds back progress events of a [`HubReceiver`].
+struct ProgressWindow {
+    window: Duration,
+    /// When the last progress event of each render was received.
+    last_progress: HashMap<u32, Instant>,
+}
+
+impl HubReceiver {
+    /// Returns `None` once the hub was dropped or the receiver was
+    /// disconnected and all buffered events were received.
+    pub(crate) async fn recv(&mut self) -> Option<RawEvent> {
+        loop {
+            let (popped, held_until) = {
+                let mut events = self
+                    .queue
+                    .events
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+
+                match self.progress_window {
+                    Some(ref mut progress_window) => progress_window.pop(&mut events),
+                    None => (events.pop_front(), None),
+                }
+            };
+
+            if let Some(event) = popped {
+                self.queue.writable.notify_one();
+
+                let dropped = self.dropped();
+
+                if dropped > self.last_dropped {
+                    let skipped = dropped - self.last_dropped;
+                    self.last_dropped = dropped;
+                    warn!(skipped, "Event receiver lagged behind");
+                }
+
+                return Some(event);
+            }
+
+            if let Some(deadline) = held_until {
+                let _ = tokio::time::timeout_at(deadline, self.queue.readable.notified()).await;
+
+                continue;
+            }
+
+            if self.queue.disconnected.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            self.queue.readable.notified().await;
+        }
+    }
+
+    pub(crate) fn dropped(&self) -> u64 {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl ProgressWindow {
+    /// Pop the next event that may be received.
+    ///
+    /// If only held back progress events are buffered, returns when the
+    /// first of them may be received.
+    fn pop(&mut self, events: &mut VecDeque<RawEvent>) -> (Option<RawEvent>, Option<Instant>) {
+        let now = Instant::now();
+        let mut held_until: Option<Instant> = None;
+        let mut idx = None;
+
+        for (i, event) in events.iter().enumerate() {
+            let render_id = match event {
+                RawEvent::RenderProgress(progress) => {
+                    let deadline = self
+                        .last_progress
+                        .get(&progress.render_id)
+                        .map(|last| *last + self.window)
+                        .filter(|deadline| *deadline > now);
+
+                    if let Some(deadline) = deadline {
+                        held_until = Some(held_until.map_or(deadline, |held| held.min(deadline)));
+
+                        continue;
+                    }
+
+                    idx = Some(i);
+
+                    break;
+                }
+                RawEvent::RenderDone(done) => done.render_id,
+                RawEvent::RenderFailed(failed) => failed.render_id,
+                RawEvent::RenderAdded(_) | RawEvent::CustomSkinProcessUpdate(_) => {
+                    idx = Some(i);
+
+                    break;
+                }
+            };
+
+            // The render is finished so its latest progress must not be
+            // received after this event.
+            let held = events.iter().take(i).position(|held| {
+                matches!(held, RawEvent::RenderProgress(held) if held.render_id == render_id)
+            });
+
+            idx = Some(held.unwrap_or(i));
+
+            break;
+        }
+
+        let Some(event) = idx.and_then(|idx| events.remove(idx)) else {
+            return (None, held_until);
+        };
+
+        match event {
+            RawEvent::RenderProgress(ref progress) => {
+                self.last_progress.insert(progress.render_id, now);
+            }
+            RawEvent::RenderDone(ref done) => {
+                self.last_progress.remove(&done.render_id);
+            }
+            RawEvent::RenderFailed(ref failed) => {
+                self.last_progress.remove(&failed.render_id);
+            }
+            RawEvent::RenderAdded(_) | RawEvent::CustomSkinProcessUpdate(_) => {}
+        }
+
+        (Some(event), None)
+    }
+}
+
+impl Drop for HubReceiver {
+    fn drop(&mut self) {
+        self.queue.receiver_dropped.store(true, Ordering::Relaxed);
+        self.queue.writable.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+    use tokio::time::Instant;
+
+    use crate::websocket::event::{EventKind, RawEvent};
+
+    use super::{EventHub, HubConfig, OverflowPolicy};
+
+    fn event(render_id: u32) -> RawEvent {
+        let bytes = format!(r#"["render_done_json",{{"renderID":{render_id}}}]"#);
+
+        RawEvent::from_bytes(Bytes::from(bytes)).unwrap()
+    }
+
+    fn progress(render_id: u32, progress: &str) -> RawEvent {
+        let bytes = format!(
+            r#"["render_progress_json",{{"renderID":{render_id},"progress":"{progress}"}}]"#
+        );
+
+        RawEvent::from_bytes(Bytes::from(bytes)).unwrap()
+    }
+
+    fn hub(overflow: OverflowPolicy) -> EventHub {
+        EventHub::new(HubConfig {
+            capacity: 1,
+            overflow,
+            progress_window_ms: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn drop_progress() {
+        let hub = hub(OverflowPolicy::DropProgress);
+        let mut rx = hub.subscribe();
+
+        hub.publish(&progress(1, "a")).await;
+        hub.publish(&progress(1, "b")).await;
+        hub.publish(&event(1)).await;
+        hub.publish(&event(2)).await;
+
+        assert_eq!(rx.dropped(), 2);
+        drop(hub);
+
+        assert_eq!(rx.recv().await, Some(event(1)));
+        assert_eq!(rx.recv().await, Some(event(2)));
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn progress_window() {
+        let hub = EventHub::new(HubConfig {
+            capacity: 8,
+            progress_window_ms: Some(200),
+            ..HubConfig::default()
+        });
+
+        let mut rx = hub.subscribe();
+
+        hub.publish(&progress(1, "a")).await;
+        hub.publish(&progress(2, "a")).await;
+        hub.publish(&progress(1, "b")).await;
+
+        assert_eq!(rx.recv().await, Some(progress(1, "b")));
+        assert_eq!(rx.recv().await, Some(progress(2, "a")));
+
+        hub.publish(&progress(1, "c")).await;
+        hub.publish(&progress(1, "d")).await;
+
+        let start = Instant::now();
+        assert_eq!(rx.recv().await, Some(progress(1, "d")));
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        hub.publish(&progress(1, "e")).await;
+        hub.publish(&event(1)).await;
+
+        let start = Instant::now();
+        assert_eq!(rx.recv().await, Some(progress(1, "e")));
+        assert_eq!(rx.recv().await, Some(event(1)));
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        assert_eq!(hub.stats().subscribers[0].coalesced, 2);
+        assert_eq!(rx.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn coalesce_progress() {
+        let hub = EventHub::new(HubConfig {
+            capacity: 8,
+            overflow: OverflowPolicy::CoalesceProgress,
+            progress_window_ms: None,
+        });
+
+        let mut rx = hub.subscribe();
+
+        hub.publish(&progress(1, "a")).await;
+        hub.publish(&progress(2, "a")).await;
+        hub.publish(&progress(1, "b")).await;
+        hub.publish(&event(1)).await;
+
+        assert_eq!(rx.dropped(), 1);
+        drop(hub);
+
+        assert_eq!(rx.recv().await, Some(progress(1, "b")));
+        assert_eq!(rx.recv().await, Some(progress(2, "a")));
+        assert_eq!(rx.recv().await, Some(event(1)));
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest() {
+        let hub = hub(OverflowPolicy::DropOldest);
+        let mut rx = hub.subscribe();
+
+        hub.publish(&event(1)).await;
+        hub.publish(&event(2)).await;
+
+        let stats = hub.stats();
+        assert_eq!(stats.events[&EventKind::RenderDone], 2);
+        assert_eq!(stats.subscribers[0].dropped, 1);
+        assert_eq!(rx.dropped(), 1);
+
+        drop(hub);
+
+        assert_eq!(rx.recv().await, Some(event(2)));
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn disconnect_subscriber() {
+        let hub = hub(OverflowPolicy::DisconnectSubscriber);
+        let mut slow = hub.subscribe();
+        let mut fast = hub.subscribe();
+
+        hub.publish(&event(1)).await;
+        assert_eq!(fast.recv().await, Some(event(1)));
+        hub.publish(&event(2)).await;
+
+        assert!(hub.stats().subscribers[0].disconnected);
+        assert_eq!(slow.recv().await, Some(event(1)));
+        assert_eq!(slow.recv().await, None);
+        assert_eq!(fast.recv().await, Some(event(2)));
+    }
+
+    #[tokio::test]
+    async fn block() {
+        let hub = hub(OverflowPolicy::Block);
+        let mut rx = hub.subscribe();
+
+        hub.publish(&event(1)).await;
+
+        let blocked = event(2);
+        let blocked = tokio::time::timeout(Duration::from_millis(50), hub.publish(&blocked));
+        assert!(blocked.await.is_err());
+
+        let unblocked = event(3);
+        let ((), received) = tokio::join!(hub.publish(&unblocked), rx.recv());
+        assert_eq!(received, Some(event(1)));
+        assert_eq!(rx.recv().await, Some(event(3)));
+    }
+}
@@ -1,46 +1,196 @@
 #![cfg(any(
-    feature = "native",
-    feature = "rustls-native-roots",
-    feature = "rustls-webpki-roots"
+    feature = "ws-native",
+    feature = "ws-rustls-native-roots",
+    feature = "ws-rustls-webpki-roots"
 ))]
 
-use crate::WebsocketError;
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use tokio::sync::broadcast::{self, Sender};
+use url::Url;
+
+use crate::{client::ReconnectConfig, dns::DnsResolver, OrdrClient, WebsocketError};
 
 use self::{
     engineio::{
+        error::EngineIoError,
         packet::{Packet as EnginePacket, PacketId as EnginePacketId},
+        tls::TlsContainer,
         EngineIo,
     },
-    event::RawEvent,
+    event::{EventKind, EventMask, RawEvent},
+    frame::EventFrame,
     packet::{Packet, PacketKind},
     reconnect::Reconnect,
 };
 
+mod backoff;
+mod builder;
+mod connection;
 mod engineio;
+mod frame;
+pub(crate) mod hub;
 mod packet;
 mod reconnect;
+mod runtime;
+mod shared;
+pub(crate) mod simulator;
+mod source;
+mod timing;
 
 pub mod error;
 pub mod event;
 
+pub use self::{
+    backoff::{BackoffPolicy, ConstantBackoff, ExponentialBackoff, FibonacciBackoff},
+    builder::OrdrWebsocketBuilder,
+    connection::{ConnectionEvent, ConnectionEvents, DisconnectReason},
+    hub::{HubConfig, HubStats, OverflowPolicy, SubscriberStats},
+    runtime::{Runtime, SleepFuture, SpawnFuture, TokioRuntime},
+    shared::{SharedEvents, SharedWebsocket},
+    simulator::{DevEventSimulator, DevEvents},
+    source::EventSource,
+    timing::ServerTiming,
+};
+
+#[cfg(feature = "runtime-async-std")]
+pub use self::runtime::AsyncStdRuntime;
+
+#[cfg(feature = "runtime-smol")]
+pub use self::runtime::SmolRuntime;
+
 /// Connection to the o!rdr websocket.
 ///
 /// Await events with [`OrdrWebsocket::next_event`].
 ///
+/// Changes of the connection state are available through
+/// [`OrdrWebsocket::connection_events`].
+///
 /// To gracefully shut the connection down, use [`OrdrWebsocket::disconnect`].
+///
+/// For unit tests, a websocket that yields predefined events without
+/// connecting can be created through [`OrdrWebsocket::from_events`].
 pub struct OrdrWebsocket {
-    engineio: EngineIo,
+    transport: Transport,
     reconnect: Reconnect,
+    /// `None` while connected, otherwise when the connection was lost.
+    disconnected_at: Option<Instant>,
+    attempts: u32,
+    connection_events: Sender<ConnectionEvent>,
+    event_mask: EventMask,
+    runtime: Arc<dyn Runtime>,
 }
 
 impl OrdrWebsocket {
     /// Connect to the o!rdr websocket.
     pub async fn connect() -> Result<Self, WebsocketError> {
-        let engineio = EngineIo::connect().await?;
+        Self::builder().connect().await
+    }
 
-        let mut this = Self {
-            engineio,
+    /// Get a handle to the websocket connection that is shared across the
+    /// whole process, connecting first if there currently is none.
+    ///
+    /// This avoids opening a separate connection in every part of an
+    /// application. The connection is gracefully disconnected once all
+    /// handles are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub async fn shared() -> Result<SharedWebsocket, WebsocketError> {
+        SharedWebsocket::get_or_connect().await
+    }
+
+    /// Connect to the o!rdr websocket with the DNS resolver and, if possible,
+    /// the TLS config of the given client.
+    ///
+    /// See [`OrdrWebsocketBuilder::client`].
+    pub async fn connect_with_client(client: &OrdrClient) -> Result<Self, WebsocketError> {
+        Self::builder().client(client).connect().await
+    }
+
+    /// Create a builder to configure the websocket before connecting.
+    pub fn builder() -> OrdrWebsocketBuilder {
+        OrdrWebsocketBuilder::new()
+    }
+
+    /// Connect to the o!rdr websocket and use the given backoff between
+    /// reconnect attempts.
+    pub async fn connect_with_reconnect(
+        reconnect: ReconnectConfig,
+    ) -> Result<Self, WebsocketError> {
+        Self::builder().reconnect(reconnect).connect().await
+    }
+
+    /// Create a websocket that does not connect to o!rdr but yields the given
+    /// events through [`OrdrWebsocket::next_event`] instead.
+    ///
+    /// Intended for unit testing code that is written against the websocket.
+    /// The event mask applies as usual. Once all events have been yielded,
+    /// the websocket is considered disconnected and
+    /// [`WebsocketError::ReconnectExhausted`] is returned from then on.
+    ///
+    /// # Example
+    /// ```
+    /// use bytes::Bytes;
+    /// use rosu_render::{
+    ///     websocket::event::{RawEvent, RawRenderDone},
+    ///     OrdrWebsocket, WebsocketError,
+    /// };
+    ///
+    /// # #[tokio::main] async fn main() {
+    /// let done = RawRenderDone {
+    ///     render_id: 1,
+    ///     bytes: Bytes::from_static(br#"{"renderID":1,"videoUrl":"https://link.issou.best/abc"}"#),
+    /// };
+    ///
+    /// let mut websocket = OrdrWebsocket::from_events([RawEvent::RenderDone(done)]);
+    ///
+    /// assert!(matches!(websocket.next_event().await, Ok(RawEvent::RenderDone(_))));
+    /// assert!(matches!(
+    ///     websocket.next_event().await,
+    ///     Err(WebsocketError::ReconnectExhausted { .. })
+    /// ));
+    /// # }
+    /// ```
+    pub fn from_events(events: impl IntoIterator<Item = RawEvent>) -> Self {
+        let (connection_events, _) = broadcast::channel(ConnectionEvents::CAPACITY);
+
+        Self {
+            transport: Transport::Local(events.into_iter().collect()),
             reconnect: Reconnect::default(),
+            disconnected_at: None,
+            attempts: 0,
+            connection_events,
+            event_mask: EventMask::ALL,
+            runtime: Arc::new(TokioRuntime),
+        }
+    }
+
+    async fn connect_with(
+        url: Url,
+        reconnect: Reconnect,
+        resolver: DnsResolver,
+        tls: TlsContainer,
+        runtime: Arc<dyn Runtime>,
+    ) -> Result<Self, WebsocketError> {
+        let engineio = EngineIo::connect(url, resolver, tls, Arc::clone(&runtime)).await?;
+
+        let (connection_events, _) = broadcast::channel(ConnectionEvents::CAPACITY);
+
+        let mut this = Self {
+            transport: Transport::EngineIo(Box::new(engineio)),
+            reconnect,
+            disconnected_at: None,
+            attempts: 0,
+            connection_events,
+            event_mask: EventMask::ALL,
+            runtime,
         };
 
         this.open().await?;
@@ -49,10 +199,20 @@ impl OrdrWebsocket {
     }
 
     /// Await the next o!rdr websocket event.
+    ///
+    /// Connection losses are handled by reconnecting. If reconnecting was
+    /// given up as configured through [`ReconnectConfig`], this returns
+    /// [`WebsocketError::ReconnectExhausted`] from then on.
     pub async fn next_event(&mut self) -> Result<RawEvent, WebsocketError> {
+        if let Transport::Local(_) = self.transport {
+            return self.next_local_event();
+        }
+
+        self.check_exhausted()?;
+
         loop {
-            let Some(bytes) = self.engineio.next_message().await? else {
-                self.reconnect().await?;
+            let Some(bytes) = self.transport.next_message().await? else {
+                self.reconnect(DisconnectReason::Closed).await?;
 
                 continue;
             };
@@ -63,36 +223,173 @@ impl OrdrWebsocket {
                 PacketKind::Event => {}
                 PacketKind::Ack => self.ack(&packet).await?,
                 PacketKind::Connect => continue,
-                PacketKind::Disconnect | PacketKind::ConnectError => {
-                    self.reconnect().await?;
+                PacketKind::Disconnect => {
+                    self.reconnect(DisconnectReason::ServerDisconnect).await?;
+
+                    continue;
+                }
+                PacketKind::ConnectError => {
+                    self.reconnect(DisconnectReason::ConnectError).await?;
 
                     continue;
                 }
             }
 
             if let Some(data) = packet.data {
+                if self.is_masked(&data) {
+                    continue;
+                }
+
                 return RawEvent::from_bytes(data);
             }
         }
     }
 
+    fn next_local_event(&mut self) -> Result<RawEvent, WebsocketError> {
+        let Transport::Local(ref mut events) = self.transport else {
+            unreachable!("only called for local transports")
+        };
+
+        let mask = self.event_mask;
+
+        if let Some(event) =
+            std::iter::from_fn(|| events.pop_front()).find(|event| mask.contains(event.kind()))
+        {
+            return Ok(event);
+        }
+
+        // There is nothing to reconnect to so running out of events is
+        // equivalent to having given up reconnecting
+        let disconnected_at = if let Some(disconnected_at) = self.disconnected_at {
+            disconnected_at
+        } else {
+            self.notify(ConnectionEvent::Disconnected {
+                reason: DisconnectReason::Closed,
+            });
+
+            *self.disconnected_at.insert(Instant::now())
+        };
+
+        Err(WebsocketError::ReconnectExhausted {
+            attempts: 0,
+            downtime: disconnected_at.elapsed(),
+        })
+    }
+
+    /// Only yield events of the given kinds through [`OrdrWebsocket::next_event`].
+    ///
+    /// Other events are dropped before they are deserialized which saves work
+    /// when only a few kinds are of interest, e.g. to skip the
+    /// [`RenderProgress`](crate::model::RenderProgress) events of all renders.
+    ///
+    /// Defaults to [`EventMask::ALL`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use rosu_render::{websocket::event::EventKind, OrdrWebsocket};
+    ///
+    /// # #[tokio::main] async fn main() -> Result<(), rosu_render::WebsocketError> {
+    /// let mut websocket = OrdrWebsocket::connect().await?;
+    /// websocket.set_event_mask(EventKind::RenderDone | EventKind::RenderFailed);
+    /// # Ok(()) }
+    /// ```
+    pub fn set_event_mask(&mut self, mask: impl Into<EventMask>) {
+        self.event_mask = mask.into();
+    }
+
+    /// The kinds of events that are yielded through [`OrdrWebsocket::next_event`].
+    #[must_use]
+    pub const fn event_mask(&self) -> EventMask {
+        self.event_mask
+    }
+
+    /// Whether the event is of a kind that is not contained in the mask.
+    ///
+    /// Events of unknown kind are never masked so that they are handled as usual.
+    fn is_masked(&self, data: &[u8]) -> bool {
+        if self.event_mask.is_all() {
+            return false;
+        }
+
+        EventFrame::parse_name(data)
+            .ok()
+            .and_then(EventKind::from_event_name)
+            .is_some_and(|kind| !self.event_mask.contains(kind))
+    }
+
+    /// Receive [`ConnectionEvent`]s from now on, e.g. to pause commissioning
+    /// renders while the connection is down.
+    ///
+    /// Each call creates a new independent receiver.
+    #[must_use]
+    pub fn connection_events(&self) -> ConnectionEvents {
+        ConnectionEvents {
+            rx: self.connection_events.subscribe(),
+        }
+    }
+
+    /// The heartbeat timing of the server as announced when the current
+    /// connection was established.
+    ///
+    /// A warning is logged whenever a ping of the server arrives close to
+    /// its timeout.
+    #[must_use]
+    pub const fn server_timing(&self) -> ServerTiming {
+        self.transport.timing()
+    }
+
+    /// Whether the connection is currently established.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.disconnected_at.is_none()
+    }
+
+    pub(crate) fn connection_events_sender(&self) -> Sender<ConnectionEvent> {
+        self.connection_events.clone()
+    }
+
+    pub(crate) fn runtime(&self) -> Arc<dyn Runtime> {
+        Arc::clone(&self.runtime)
+    }
+
     /// Gracefully disconnect from the websocket.
     pub async fn disconnect(self) -> Result<(), WebsocketError> {
-        self.engineio
+        self.transport
             .disconnect()
             .await
             .map_err(WebsocketError::EngineIo)
     }
 
-    async fn reconnect(&mut self) -> Result<(), WebsocketError> {
-        if let Some(delay) = self.reconnect.delay() {
+    async fn reconnect(&mut self, reason: DisconnectReason) -> Result<(), WebsocketError> {
+        if self.disconnected_at.is_none() {
+            self.disconnected_at = Some(Instant::now());
+            self.notify(ConnectionEvent::Disconnected { reason });
+        }
+
+        self.check_exhausted()?;
+
+        self.attempts += 1;
+        let delay = self.reconnect.delay();
+
+        self.notify(ConnectionEvent::Reconnecting {
+            attempt: self.attempts,
+            delay: delay.unwrap_or_default(),
+        });
+
+        if let Some(delay) = delay {
             trace!(?delay, "Delaying reconnect...");
-            tokio::time::sleep(delay).await;
+            self.runtime.sleep(delay).await;
         }
 
-        let err = match self.engineio.reconnect().await {
+        let err = match self.transport.reconnect().await {
             Ok(()) => match self.open().await {
-                Ok(()) => return Ok(()),
+                Ok(()) => {
+                    self.disconnected_at = None;
+                    self.attempts = 0;
+                    self.notify(ConnectionEvent::Connected);
+
+                    return Ok(());
+                }
                 Err(err) => err,
             },
             Err(err) => WebsocketError::EngineIo(err),
@@ -103,10 +400,32 @@ impl OrdrWebsocket {
         Err(err)
     }
 
+    fn check_exhausted(&self) -> Result<(), WebsocketError> {
+        let Some(disconnected_at) = self.disconnected_at else {
+            return Ok(());
+        };
+
+        let downtime = disconnected_at.elapsed();
+
+        if self.reconnect.exhausted(self.attempts, downtime) {
+            return Err(WebsocketError::ReconnectExhausted {
+                attempts: self.attempts,
+                downtime,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn notify(&self, event: ConnectionEvent) {
+        // Only fails if there are currently no receivers
+        let _ = self.connection_events.send(event);
+    }
+
     async fn emit(&mut self, packet: Packet) -> Result<(), WebsocketError> {
         let msg = EnginePacket::new(EnginePacketId::Message, packet.to_bytes());
 
-        self.engineio
+        self.transport
             .emit(msg)
             .await
             .map_err(WebsocketError::EngineIo)
@@ -122,3 +441,103 @@ impl OrdrWebsocket {
         self.emit(Packet::new_ack(id)).await
     }
 }
+
+/// Where the events of an [`OrdrWebsocket`] come from.
+enum Transport {
+    EngineIo(Box<EngineIo>),
+    /// Predefined events, see [`OrdrWebsocket::from_events`].
+    ///
+    /// These are yielded directly so none of the methods below, except for
+    /// timing and disconnecting, are reached.
+    Local(VecDeque<RawEvent>),
+}
+
+impl Transport {
+    /// Timing of a socket.io server with default configuration.
+    const LOCAL_TIMING: ServerTiming = ServerTiming {
+        ping_interval: Duration::from_secs(25),
+        ping_timeout: Duration::from_secs(20),
+    };
+
+    async fn next_message(&mut self) -> Result<Option<Bytes>, EngineIoError> {
+        match self {
+            Self::EngineIo(engineio) => engineio.next_message().await,
+            Self::Local(_) => Ok(None),
+        }
+    }
+
+    async fn emit(&mut self, packet: EnginePacket) -> Result<(), EngineIoError> {
+        match self {
+            Self::EngineIo(engineio) => engineio.emit(packet).await,
+            Self::Local(_) => Ok(()),
+        }
+    }
+
+    async fn reconnect(&mut self) -> Result<(), EngineIoError> {
+        match self {
+            Self::EngineIo(engineio) => engineio.reconnect().await,
+            Self::Local(_) => Ok(()),
+        }
+    }
+
+    async fn disconnect(self) -> Result<(), EngineIoError> {
+        match self {
+            Self::EngineIo(engineio) => (*engineio).disconnect().await,
+            Self::Local(_) => Ok(()),
+        }
+    }
+
+    const fn timing(&self) -> ServerTiming {
+        match self {
+            Self::EngineIo(engineio) => engineio.timing(),
+            Self::Local(_) => Self::LOCAL_TIMING,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{
+        event::{EventKind, RawEvent, RawRenderAdded, RawRenderDone},
+        ConnectionEvent, OrdrWebsocket,
+    };
+    use crate::WebsocketError;
+
+    #[tokio::test]
+    async fn local_events() {
+        let added = RawEvent::RenderAdded(RawRenderAdded {
+            bytes: Bytes::from_static(br#"{"renderID":1}"#),
+        });
+
+        let done = RawEvent::RenderDone(RawRenderDone {
+            render_id: 1,
+            bytes: Bytes::from_static(
+                br#"{"renderID":1,"videoUrl":"https://link.issou.best/abc"}"#,
+            ),
+        });
+
+        let mut websocket = OrdrWebsocket::from_events([added, done.clone()]);
+        websocket.set_event_mask(EventKind::RenderDone);
+        let mut connection_events = websocket.connection_events();
+
+        assert!(websocket.is_connected());
+        assert_eq!(websocket.next_event().await.unwrap(), done);
+
+        for _ in 0..2 {
+            assert!(matches!(
+                websocket.next_event().await,
+                Err(WebsocketError::ReconnectExhausted { attempts: 0, .. })
+            ));
+        }
+
+        assert!(!websocket.is_connected());
+        assert!(matches!(
+            connection_events.recv().await,
+            Some(ConnectionEvent::Disconnected { .. })
+        ));
+
+        websocket.disconnect().await.unwrap();
+    }
+}
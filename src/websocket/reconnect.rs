@@ -1,50 +1,76 @@
-use std::{
-    num::NonZeroU64,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
+
+use crate::client::ReconnectConfig;
+
+use super::backoff::{BackoffPolicy, ExponentialBackoff};
 
 /// Keeps track of successive reconnect attempts
-/// and adds a delay based on exponential backoff.
+/// and adds a delay based on a [`BackoffPolicy`].
 pub(super) struct Reconnect {
-    backoff_ms: Option<NonZeroU64>,
+    failures: u32,
     last_attempt: Instant,
+    config: ReconnectConfig,
+    policy: Box<dyn BackoffPolicy>,
 }
 
 impl Reconnect {
-    const MAX_BACKOFF_MS: u64 = 10_000;
     const RESET_INTERVAL: Duration = Duration::from_secs(60);
 
+    pub(super) fn new(config: ReconnectConfig, policy: Box<dyn BackoffPolicy>) -> Self {
+        Self {
+            failures: 0,
+            last_attempt: Instant::now(),
+            config,
+            policy,
+        }
+    }
+
     pub(super) fn delay(&mut self) -> Option<Duration> {
-        let backoff_ms = self.backoff_ms?;
+        if self.failures == 0 {
+            return None;
+        }
+
         let now = Instant::now();
 
         if self.last_attempt + Self::RESET_INTERVAL > now {
             self.last_attempt = now;
 
-            Some(Duration::from_millis(backoff_ms.get()))
+            Some(self.policy.delay(self.failures))
         } else {
-            self.backoff_ms = None;
+            self.failures = 0;
             self.last_attempt = now;
 
             None
         }
     }
 
-    /// Exponential backoff ms: 100 - 200 - 400 - 800 - 1600 - 3200 - 6400 - 10000
     pub(super) fn backoff(&mut self) {
-        self.backoff_ms = match self.backoff_ms {
-            Some(backoff_ms) => NonZeroU64::new((backoff_ms.get() * 2).min(Self::MAX_BACKOFF_MS)),
-            None => NonZeroU64::new(100),
-        };
+        self.failures = self.failures.saturating_add(1);
+    }
+
+    /// Whether no further attempt should be made after `attempts` consecutive
+    /// attempts and being disconnected for `downtime`.
+    pub(super) fn exhausted(&self, attempts: u32, downtime: Duration) -> bool {
+        let ReconnectConfig {
+            max_attempts,
+            max_downtime_ms,
+            ..
+        } = self.config;
+
+        max_attempts.is_some_and(|max| attempts >= max)
+            || max_downtime_ms.is_some_and(|max| downtime >= Duration::from_millis(max))
+    }
+}
+
+impl From<ReconnectConfig> for Reconnect {
+    fn from(config: ReconnectConfig) -> Self {
+        Self::new(config, Box::new(ExponentialBackoff::from(config)))
     }
 }
 
 impl Default for Reconnect {
     fn default() -> Self {
-        Self {
-            backoff_ms: None,
-            last_attempt: Instant::now(),
-        }
+        Self::from(ReconnectConfig::default())
     }
 }
 
@@ -52,11 +78,16 @@ impl Default for Reconnect {
 mod tests {
     use std::time::{Duration, Instant};
 
+    use crate::client::ReconnectConfig;
+
     use super::Reconnect;
 
     #[test]
     fn test_reconnect() {
-        let mut reconnect = Reconnect::default();
+        let mut reconnect = Reconnect::from(ReconnectConfig {
+            jitter: false,
+            ..Default::default()
+        });
         assert_eq!(reconnect.delay(), None);
 
         reconnect.backoff();
@@ -95,4 +126,20 @@ mod tests {
         reconnect.backoff();
         assert_eq!(reconnect.delay(), Some(Duration::from_millis(200)));
     }
+
+    #[test]
+    fn test_exhausted() {
+        let reconnect = Reconnect::default();
+        assert!(!reconnect.exhausted(u32::MAX, Duration::MAX));
+
+        let reconnect = Reconnect::from(ReconnectConfig {
+            max_attempts: Some(3),
+            max_downtime_ms: Some(5000),
+            ..Default::default()
+        });
+
+        assert!(!reconnect.exhausted(2, Duration::from_secs(4)));
+        assert!(reconnect.exhausted(3, Duration::ZERO));
+        assert!(reconnect.exhausted(0, Duration::from_secs(5)));
+    }
 }
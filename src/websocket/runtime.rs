@@ -0,0 +1,137 @@
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+    time::Duration,
+};
+
+use futures::future::{self, Either};
+
+/// Future that is run in the background through [`Runtime::spawn`].
+pub type SpawnFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Future returned by [`Runtime::sleep`].
+pub type SleepFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Spawns tasks and provides timers for the [`OrdrWebsocket`](super::OrdrWebsocket)
+/// so that it can be used outside of a tokio runtime.
+///
+/// Configured through [`OrdrWebsocketBuilder::runtime`](super::OrdrWebsocketBuilder::runtime).
+///
+/// Note that the runtime only covers tasks and timers. With the features
+/// `runtime-async-std` or `runtime-smol`, the connection itself is
+/// established through that runtime as well; otherwise it requires
+/// tokio's IO driver.
+pub trait Runtime: Send + Sync + 'static {
+    /// Run the future in the background.
+    fn spawn(&self, future: SpawnFuture);
+
+    /// Complete once the duration has passed.
+    fn sleep(&self, duration: Duration) -> SleepFuture;
+}
+
+/// Runs on the current tokio runtime.
+///
+/// # Panics
+///
+/// Its methods panic if called outside of a tokio runtime.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TokioRuntime;
+
+impl Runtime for TokioRuntime {
+    fn spawn(&self, future: SpawnFuture) {
+        tokio::spawn(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Runs on the global `async-std` executor.
+#[cfg(feature = "runtime-async-std")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AsyncStdRuntime;
+
+#[cfg(feature = "runtime-async-std")]
+impl Runtime for AsyncStdRuntime {
+    fn spawn(&self, future: SpawnFuture) {
+        // Dropping the handle detaches the task
+        async_std::task::spawn(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+/// Runs on the global `smol` executor.
+#[cfg(feature = "runtime-smol")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SmolRuntime;
+
+#[cfg(feature = "runtime-smol")]
+impl Runtime for SmolRuntime {
+    fn spawn(&self, future: SpawnFuture) {
+        smol::spawn(future).detach();
+    }
+
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(async move {
+            smol::Timer::after(duration).await;
+        })
+    }
+}
+
+/// Await the future unless the duration passes first.
+pub(crate) async fn timeout<F: Future>(
+    runtime: &dyn Runtime,
+    duration: Duration,
+    future: F,
+) -> Option<F::Output> {
+    let future = pin!(future);
+
+    match future::select(future, runtime.sleep(duration)).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::future;
+
+    use super::{timeout, Runtime, TokioRuntime};
+
+    #[tokio::test(start_paused = true)]
+    async fn timeout_elapses() {
+        let runtime = TokioRuntime;
+
+        assert_eq!(
+            timeout(&runtime, Duration::from_secs(1), async { 1 }).await,
+            Some(1)
+        );
+
+        let pending = future::pending::<()>();
+        assert_eq!(
+            timeout(&runtime, Duration::from_secs(1), pending).await,
+            None
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn spawn_and_sleep() {
+        let runtime = TokioRuntime;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let sleep = runtime.sleep(Duration::from_secs(5));
+
+        runtime.spawn(Box::pin(async move {
+            sleep.await;
+            let _ = tx.send(());
+        }));
+
+        let received = timeout(&runtime, Duration::from_secs(10), rx).await;
+        assert!(matches!(received, Some(Ok(()))));
+    }
+}
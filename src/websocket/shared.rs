@@ -0,0 +1,155 @@
+use std::{
+    pin::pin,
+    sync::{Arc, Weak},
+};
+
+use futures::future::{self, Either};
+use tokio::sync::{broadcast::Sender, oneshot, Mutex};
+
+use crate::WebsocketError;
+
+use super::{
+    event::RawEvent,
+    hub::{EventHub, HubReceiver},
+    ConnectionEvent, ConnectionEvents, HubConfig, HubStats, OrdrWebsocket,
+};
+
+/// The currently shared connection, if any handle is still alive.
+static SHARED: Mutex<Weak<SharedRef>> = Mutex::const_new(Weak::new());
+
+/// Handle to a websocket connection that is shared across the whole process.
+///
+/// Created through [`OrdrWebsocket::shared`]. The connection is kept busy in a
+/// background task and its events are forwarded to every [`SharedEvents`].
+///
+/// Cheap to clone. Once all handles are dropped, the connection is
+/// gracefully disconnected.
+#[derive(Clone)]
+pub struct SharedWebsocket {
+    inner: Arc<SharedRef>,
+}
+
+struct SharedRef {
+    events: Arc<EventHub>,
+    connection_events: Sender<ConnectionEvent>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl Drop for SharedRef {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            // Only fails if the background task already stopped
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+impl SharedWebsocket {
+    pub(super) async fn get_or_connect() -> Result<Self, WebsocketError> {
+        let mut shared = SHARED.lock().await;
+
+        if let Some(inner) = shared.upgrade() {
+            return Ok(Self { inner });
+        }
+
+        let websocket = OrdrWebsocket::connect().await?;
+        let events = Arc::new(EventHub::new(HubConfig::default()));
+        let connection_events = websocket.connection_events_sender();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        let runtime = websocket.runtime();
+        runtime.spawn(Box::pin(event_loop(
+            websocket,
+            Arc::clone(&events),
+            shutdown_rx,
+        )));
+
+        let inner = Arc::new(SharedRef {
+            events,
+            connection_events,
+            shutdown: Some(shutdown_tx),
+        });
+
+        *shared = Arc::downgrade(&inner);
+
+        Ok(Self { inner })
+    }
+
+    /// Receive all websocket events from now on.
+    ///
+    /// Each call creates a new independent receiver.
+    #[must_use]
+    pub fn events(&self) -> SharedEvents {
+        SharedEvents {
+            rx: self.inner.events.subscribe(),
+        }
+    }
+
+    /// Statistics of the events distributed to [`SharedEvents`] and of
+    /// each receiver, e.g. to detect slow event handlers.
+    #[must_use]
+    pub fn event_stats(&self) -> HubStats {
+        self.inner.events.stats()
+    }
+
+    /// Receive changes of the websocket's connection state from now on.
+    ///
+    /// Each call creates a new independent receiver.
+    #[must_use]
+    pub fn connection_events(&self) -> ConnectionEvents {
+        ConnectionEvents {
+            rx: self.inner.connection_events.subscribe(),
+        }
+    }
+}
+
+/// Receiver of the events of a [`SharedWebsocket`].
+///
+/// Created through [`SharedWebsocket::events`].
+pub struct SharedEvents {
+    rx: HubReceiver,
+}
+
+impl SharedEvents {
+    /// Await the next event.
+    ///
+    /// If the receiver falls behind by more than 128 events, the oldest ones are skipped.
+    ///
+    /// Returns `None` once the connection was given up or all handles were dropped.
+    pub async fn recv(&mut self) -> Option<RawEvent> {
+        self.rx.recv().await
+    }
+
+    /// The amount of events that were dropped because this receiver fell behind.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.rx.dropped()
+    }
+}
+
+async fn event_loop(
+    mut websocket: OrdrWebsocket,
+    events: Arc<EventHub>,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    loop {
+        let next_event = pin!(websocket.next_event());
+
+        match future::select(next_event, &mut shutdown).await {
+            Either::Left((Ok(event), _)) => events.publish(&event).await,
+            Either::Left((Err(err @ WebsocketError::ReconnectExhausted { .. }), _)) => {
+                warn!(?err, "Stopped shared websocket");
+
+                return;
+            }
+            Either::Left((Err(err), _)) => {
+                warn!(?err, "Error while receiving shared websocket event");
+            }
+            Either::Right(_) => break,
+        }
+    }
+
+    if let Err(err) = websocket.disconnect().await {
+        warn!(?err, "Failed to disconnect shared websocket");
+    }
+}
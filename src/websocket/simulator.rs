@@ -0,0 +1,251 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bytes::Bytes;
+use serde_json::json;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::{client::error::ErrorCode, model::RenderAdded};
+
+use super::event::RawEvent;
+
+/// Fabricates the websocket events of renders without connecting to o!rdr.
+///
+/// Useful alongside a dev mode [`Verification`](crate::model::Verification)
+/// to test bot logic hermetically, e.g. in CI. Simulated events are received
+/// through the [`DevEvents`] that were created alongside the simulator, or
+/// through a [`RenderTracker`](crate::tracker::RenderTracker) created via
+/// [`RenderTracker::simulated`](crate::tracker::RenderTracker::simulated).
+///
+/// Cheap to clone.
+///
+/// # Example
+/// ```
+/// use rosu_render::websocket::{event::RawEvent, DevEventSimulator};
+///
+/// # #[tokio::main] async fn main() {
+/// let (simulator, mut events) = DevEventSimulator::new();
+/// let simulator = simulator.progress_steps(3);
+///
+/// let added = simulator.simulate_success();
+///
+/// while let Some(event) = events.next_event().await {
+///     if let RawEvent::RenderDone(done) = event {
+///         assert_eq!(done.render_id, added.render_id);
+///         break;
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DevEventSimulator {
+    tx: UnboundedSender<RawEvent>,
+    next_render_id: Arc<AtomicU32>,
+    progress_steps: u8,
+    interval: Duration,
+}
+
+/// Receiver of the events of a [`DevEventSimulator`].
+pub struct DevEvents {
+    rx: UnboundedReceiver<RawEvent>,
+}
+
+impl DevEvents {
+    /// Await the next simulated event.
+    ///
+    /// Returns `None` once all simulators have been dropped and all
+    /// their events have been received.
+    pub async fn next_event(&mut self) -> Option<RawEvent> {
+        self.rx.recv().await
+    }
+}
+
+impl DevEventSimulator {
+    const DEFAULT_PROGRESS_STEPS: u8 = 5;
+    const DEFAULT_INTERVAL: Duration = Duration::from_millis(10);
+    const FIRST_RENDER_ID: u32 = 1_000_000;
+
+    /// Create a new simulator and the receiver of its events.
+    #[must_use]
+    pub fn new() -> (Self, DevEvents) {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let simulator = Self {
+            tx,
+            next_render_id: Arc::new(AtomicU32::new(Self::FIRST_RENDER_ID)),
+            progress_steps: Self::DEFAULT_PROGRESS_STEPS,
+            interval: Self::DEFAULT_INTERVAL,
+        };
+
+        (simulator, DevEvents { rx })
+    }
+
+    /// How many progress events are emitted per render. Defaults to 5.
+    #[must_use]
+    pub fn progress_steps(self, progress_steps: u8) -> Self {
+        Self {
+            progress_steps,
+            ..self
+        }
+    }
+
+    /// The delay between consecutive events of a render. Defaults to 10ms.
+    #[must_use]
+    pub fn interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// Simulate a render that succeeds, like [`Verification::DevModeSuccess`].
+    ///
+    /// Emits `render_added`, the progress events, and `render_done` in the background.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    ///
+    /// [`Verification::DevModeSuccess`]: crate::model::Verification::DevModeSuccess
+    #[allow(clippy::must_use_candidate)]
+    pub fn simulate_success(&self) -> RenderAdded {
+        self.simulate(true)
+    }
+
+    /// Simulate a render that fails, like [`Verification::DevModeWsFail`].
+    ///
+    /// Emits `render_added`, the progress events, and `render_failed` in the background.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    ///
+    /// [`Verification::DevModeWsFail`]: crate::model::Verification::DevModeWsFail
+    #[allow(clippy::must_use_candidate)]
+    pub fn simulate_failure(&self) -> RenderAdded {
+        self.simulate(false)
+    }
+
+    fn simulate(&self, success: bool) -> RenderAdded {
+        let render_id = self.next_render_id.fetch_add(1, Ordering::Relaxed);
+        let events = lifecycle(render_id, self.progress_steps, success);
+        let tx = self.tx.clone();
+        let interval = self.interval;
+
+        tokio::spawn(async move {
+            for event in events {
+                if tx.send(event).is_err() {
+                    return;
+                }
+
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        RenderAdded { render_id }
+    }
+}
+
+/// Fabricate all events of a render in order.
+fn lifecycle(render_id: u32, progress_steps: u8, success: bool) -> Vec<RawEvent> {
+    let failure = (!success).then_some(ErrorCode::ReplayParsingError);
+
+    lifecycle_payloads(render_id, progress_steps, failure)
+        .into_iter()
+        .map(|payload| {
+            RawEvent::from_bytes(Bytes::from(payload)).expect("simulated event must be valid")
+        })
+        .collect()
+}
+
+/// Fabricate the socket.io payloads of all events of a render in order,
+/// e.g. `["render_added_json",{"renderID":42}]`.
+///
+/// The render fails with the given error code, if any.
+pub(crate) fn lifecycle_payloads(
+    render_id: u32,
+    progress_steps: u8,
+    failure: Option<ErrorCode>,
+) -> Vec<String> {
+    let mut events = Vec::with_capacity(usize::from(progress_steps) + 2);
+
+    events.push(event(
+        "render_added_json",
+        &json!({ "renderID": render_id }),
+    ));
+
+    for step in 1..=progress_steps {
+        let percent = u32::from(step) * 100 / u32::from(progress_steps);
+
+        let progress = json!({
+            "renderID": render_id,
+            "progress": format!("Rendering... ({percent}%)"),
+            "description": "Simulated render",
+            "renderer": "dev-simulator",
+            "username": "dev-simulator",
+        });
+
+        events.push(event("render_progress_json", &progress));
+    }
+
+    let terminal = if let Some(code) = failure {
+        let failed = json!({
+            "renderID": render_id,
+            "errorCode": code.to_u8(),
+            "errorMessage": "Simulated render failure",
+        });
+
+        event("render_failed_json", &failed)
+    } else {
+        let done = json!({
+            "renderID": render_id,
+            "videoUrl": format!("https://ordr-renders.issou.best/render/ordr-render-{render_id}.mp4"),
+        });
+
+        event("render_done_json", &done)
+    };
+
+    events.push(terminal);
+
+    events
+}
+
+fn event(name: &str, payload: &serde_json::Value) -> String {
+    format!(r#"["{name}",{payload}]"#)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::websocket::event::RawEvent;
+
+    use super::lifecycle;
+
+    #[test]
+    fn fabricated_events_deserialize() {
+        for success in [true, false] {
+            let events = lifecycle(42, 4, success);
+
+            assert_eq!(events.len(), 6);
+            assert!(matches!(events[0], RawEvent::RenderAdded(_)));
+
+            for event in events.iter() {
+                event.deserialize().unwrap();
+            }
+
+            let RawEvent::RenderProgress(ref last_progress) = events[4] else {
+                panic!("expected render progress");
+            };
+
+            let percentage = last_progress.deserialize().unwrap().percentage();
+            assert_eq!(percentage, Some(100.0));
+
+            if success {
+                assert!(matches!(events[5], RawEvent::RenderDone(_)));
+            } else {
+                assert!(matches!(events[5], RawEvent::RenderFailed(_)));
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::future::Future;
+
+use crate::WebsocketError;
+
+use super::{event::RawEvent, DevEvents, OrdrWebsocket, SharedEvents};
+
+/// Anything that yields o!rdr websocket events.
+///
+/// Lets code such as the [`RenderTracker`](crate::tracker::RenderTracker)
+/// switch between the actual websocket and e.g. a [`DevEventSimulator`]
+/// without further changes.
+///
+/// [`DevEventSimulator`]: super::DevEventSimulator
+pub trait EventSource: Send + 'static {
+    /// Await the next event.
+    ///
+    /// Returns `None` if no further events will be received. Errors that end
+    /// the source, like [`WebsocketError::ReconnectExhausted`], may also be
+    /// yielded once before that.
+    fn next_event(
+        &mut self,
+    ) -> impl Future<Output = Option<Result<RawEvent, WebsocketError>>> + Send;
+}
+
+impl EventSource for OrdrWebsocket {
+    async fn next_event(&mut self) -> Option<Result<RawEvent, WebsocketError>> {
+        Some(OrdrWebsocket::next_event(self).await)
+    }
+}
+
+impl EventSource for SharedEvents {
+    async fn next_event(&mut self) -> Option<Result<RawEvent, WebsocketError>> {
+        self.recv().await.map(Ok)
+    }
+}
+
+impl EventSource for DevEvents {
+    async fn next_event(&mut self) -> Option<Result<RawEvent, WebsocketError>> {
+        DevEvents::next_event(self).await.map(Ok)
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+/// Heartbeat timing announced by the server during the engine.io handshake.
+///
+/// The server sends a ping every `ping_interval` and considers the
+/// connection lost if no pong was received within `ping_timeout`.
+/// Likewise, the connection is considered lost if no ping arrived within
+/// `ping_interval + ping_timeout`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ServerTiming {
+    pub ping_interval: Duration,
+    pub ping_timeout: Duration,
+}
+
+impl ServerTiming {
+    /// The duration after which a missing ping is considered a lost connection.
+    #[must_use]
+    pub fn heartbeat_deadline(&self) -> Duration {
+        self.ping_interval + self.ping_timeout
+    }
+
+    /// Whether a ping that arrived `elapsed` after the previous one used up
+    /// most of the timeout, hinting at a flaky connection.
+    pub(crate) fn is_ping_late(&self, elapsed: Duration) -> bool {
+        elapsed > self.ping_interval + self.ping_timeout * 3 / 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ServerTiming;
+
+    #[test]
+    fn late_ping() {
+        let timing = ServerTiming {
+            ping_interval: Duration::from_secs(25),
+            ping_timeout: Duration::from_secs(20),
+        };
+
+        assert_eq!(timing.heartbeat_deadline(), Duration::from_secs(45));
+        assert!(!timing.is_ping_late(Duration::from_secs(26)));
+        assert!(timing.is_ping_late(Duration::from_secs(41)));
+    }
+}
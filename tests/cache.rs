@@ -0,0 +1,127 @@
+#![cfg(all(
+    feature = "cache",
+    feature = "mock-server",
+    any(
+        feature = "ws-native",
+        feature = "ws-rustls-native-roots",
+        feature = "ws-rustls-webpki-roots"
+    )
+))]
+
+use std::time::Duration;
+
+use rosu_render::{
+    client::{CacheStats, CachedRoute},
+    mock::MockServer,
+};
+
+const ONLINE_COUNT: &str = "servers/onlinecount";
+
+#[tokio::test]
+async fn fresh_entry_is_served_from_cache() {
+    let server = MockServer::start().await.unwrap();
+    server.set_online_count(3);
+
+    let client = server
+        .client()
+        .cache_ttl(CachedRoute::ServerOnlineCount, Duration::from_secs(60))
+        .build();
+
+    assert_eq!(client.server_online_count().await.unwrap().0, 3);
+
+    server.set_online_count(4);
+    assert_eq!(client.server_online_count().await.unwrap().0, 3);
+
+    assert_eq!(server.request_count(ONLINE_COUNT), 1);
+    assert_eq!(
+        client.cache_stats(),
+        Some(CacheStats {
+            hits: 1,
+            misses: 1,
+            revalidations: 0
+        })
+    );
+}
+
+#[tokio::test]
+async fn expired_entry_is_revalidated() {
+    let server = MockServer::start().await.unwrap();
+    server.set_online_count(3);
+
+    let ttl = Duration::from_millis(20);
+    let client = server
+        .client()
+        .cache_ttl(CachedRoute::ServerOnlineCount, ttl)
+        .build();
+
+    assert_eq!(client.server_online_count().await.unwrap().0, 3);
+
+    // Unchanged responses are answered with 304 Not Modified
+    tokio::time::sleep(ttl * 2).await;
+    assert_eq!(client.server_online_count().await.unwrap().0, 3);
+
+    // Changed responses are fetched again
+    server.set_online_count(4);
+    tokio::time::sleep(ttl * 2).await;
+    assert_eq!(client.server_online_count().await.unwrap().0, 4);
+
+    assert_eq!(server.request_count(ONLINE_COUNT), 3);
+    assert_eq!(
+        client.cache_stats(),
+        Some(CacheStats {
+            hits: 0,
+            misses: 3,
+            revalidations: 1
+        })
+    );
+}
+
+#[tokio::test]
+async fn entries_are_keyed_by_query() {
+    let server = MockServer::start().await.unwrap();
+    let skins = include_str!("fixtures/http/skin_list.json");
+
+    for page in 1..=2 {
+        server.stub(&format!("skins?pageSize=1&page={page}"), skins);
+    }
+
+    let client = server
+        .client()
+        .cache_ttl(CachedRoute::SkinList, Duration::from_secs(60))
+        .build();
+
+    for page in [1, 2, 1, 2] {
+        client.skin_list().page_size(1).page(page).await.unwrap();
+    }
+
+    assert_eq!(server.request_count("skins?pageSize=1&page=1"), 1);
+    assert_eq!(server.request_count("skins?pageSize=1&page=2"), 1);
+    assert_eq!(client.cache_stats().unwrap().hits, 2);
+}
+
+#[tokio::test]
+async fn no_cache_stats_without_cached_routes() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().build();
+
+    client.server_online_count().await.unwrap();
+
+    assert_eq!(client.cache_stats(), None);
+}
+
+#[tokio::test]
+async fn malformed_response_is_not_cached() {
+    let server = MockServer::start().await.unwrap();
+    server.stub("skins?pageSize=1&page=1", "{");
+
+    let client = server
+        .client()
+        .cache_ttl(CachedRoute::SkinList, Duration::from_secs(60))
+        .build();
+
+    for _ in 0..2 {
+        assert!(client.skin_list().page_size(1).page(1).await.is_err());
+    }
+
+    assert_eq!(server.request_count("skins?pageSize=1&page=1"), 2);
+}
@@ -0,0 +1,324 @@
+#![cfg(all(
+    feature = "mock-server",
+    any(
+        feature = "ws-native",
+        feature = "ws-rustls-native-roots",
+        feature = "ws-rustls-webpki-roots"
+    )
+))]
+
+use std::time::Duration;
+
+use rosu_render::{
+    client::error::ErrorCode,
+    mock::MockServer,
+    model::{RenderSkinOption, ServerCountEvent},
+    request::RenderJob,
+    tracker::{RenderFailure, RenderTracker, RenderUpdate, RetryPolicy},
+    websocket::event::{EventKind, RawEvent},
+    ClientError,
+};
+
+const REPLAY_URL: &str = "https://example.com/replay.osr";
+
+#[tokio::test]
+async fn render_done() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().build();
+    let mut websocket = server.websocket().connect().await.unwrap();
+
+    let skin = RenderSkinOption::default();
+
+    let render_added = client
+        .render_with_replay_url(REPLAY_URL, "rosu-render-mock-test", &skin)
+        .await
+        .unwrap();
+
+    let mut progressed = false;
+
+    let done = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match websocket.next_event().await.unwrap() {
+                RawEvent::RenderProgress(event) if event.render_id == render_added.render_id => {
+                    progressed = true;
+                }
+                RawEvent::RenderDone(event) if event.render_id == render_added.render_id => {
+                    return event;
+                }
+                RawEvent::RenderFailed(event) => panic!("unexpected failure: {event:?}"),
+                _ => {}
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    assert!(progressed);
+    done.deserialize().unwrap();
+
+    websocket.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn render_failed() {
+    let server = MockServer::start().await.unwrap();
+    server.fail_renders(true);
+
+    let client = server.client().build();
+    let mut websocket = server.websocket().connect().await.unwrap();
+
+    let skin = RenderSkinOption::default();
+
+    let render_added = client
+        .render_with_replay_url(REPLAY_URL, "rosu-render-mock-test", &skin)
+        .await
+        .unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match websocket.next_event().await.unwrap() {
+                RawEvent::RenderFailed(event) if event.render_id == render_added.render_id => {
+                    break;
+                }
+                RawEvent::RenderDone(event) => panic!("unexpected success: {event:?}"),
+                _ => {}
+            }
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn event_mask() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().build();
+
+    let mut websocket = server
+        .websocket()
+        .event_mask(EventKind::RenderDone | EventKind::RenderFailed)
+        .connect()
+        .await
+        .unwrap();
+
+    let skin = RenderSkinOption::default();
+
+    let render_added = client
+        .render_with_replay_url(REPLAY_URL, "rosu-render-mock-test", &skin)
+        .await
+        .unwrap();
+
+    // Neither the render's progress nor its addition is yielded
+    let event = tokio::time::timeout(Duration::from_secs(5), websocket.next_event())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let RawEvent::RenderDone(event) = event else {
+        panic!("expected render done, got {event:?}");
+    };
+
+    assert_eq!(event.render_id, render_added.render_id);
+}
+
+#[tokio::test]
+async fn retry_transient_failure() {
+    let server = MockServer::start().await.unwrap();
+    server.fail_next_render(ErrorCode::MirrorsUnavailable);
+
+    let client = server.client().build();
+    let websocket = server.websocket().connect().await.unwrap();
+
+    let policy = RetryPolicy::new(1).delay(Duration::from_millis(10));
+    let tracker = RenderTracker::new(websocket).retry_policy(policy);
+
+    let job = RenderJob::with_url(
+        REPLAY_URL,
+        "rosu-render-mock-test",
+        RenderSkinOption::default(),
+    );
+    let mut subscription = tracker.commission(&client, job).await.unwrap();
+    let first_id = subscription.render_id();
+
+    let retried = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match subscription.next().await.unwrap() {
+                RenderUpdate::Retried(retried) => return retried,
+                RenderUpdate::Progress(_) => {}
+                update => panic!("unexpected update: {update:?}"),
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(retried.attempt, 1);
+    assert_eq!(retried.failed.render_id, first_id);
+    assert_eq!(subscription.render_id(), retried.render_id);
+
+    let done = tokio::time::timeout(Duration::from_secs(5), subscription.wait())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(done.render_id, retried.render_id);
+}
+
+#[tokio::test]
+async fn timeout_during_retry_delay() {
+    let server = MockServer::start().await.unwrap();
+    server.fail_next_render(ErrorCode::MirrorsUnavailable);
+
+    let client = server.client().build();
+    let websocket = server.websocket().connect().await.unwrap();
+
+    let policy = RetryPolicy::new(1).delay(Duration::from_secs(60));
+    let tracker = RenderTracker::new(websocket).retry_policy(policy);
+
+    let job = RenderJob::with_url(
+        REPLAY_URL,
+        "rosu-render-mock-test",
+        RenderSkinOption::default(),
+    );
+    let subscription = tracker
+        .commission(&client, job)
+        .await
+        .unwrap()
+        .with_timeout(Duration::from_millis(100));
+
+    let res = tokio::time::timeout(Duration::from_secs(5), subscription.wait())
+        .await
+        .unwrap();
+
+    assert!(matches!(res, Err(RenderFailure::TimedOut)));
+}
+
+#[tokio::test]
+async fn render_rejected() {
+    let server = MockServer::start().await.unwrap();
+    server.reject_next_render(ErrorCode::InvalidGameMode);
+
+    let client = server.client().build();
+    let skin = RenderSkinOption::default();
+
+    let err = client
+        .render_with_replay_url(REPLAY_URL, "rosu-render-mock-test", &skin)
+        .await
+        .unwrap_err();
+
+    let ClientError::RenderSubmission { error } = err else {
+        panic!("expected render submission error, got {err:?}");
+    };
+
+    assert_eq!(error.error_code(), Some(ErrorCode::InvalidGameMode));
+
+    // Only the next submission is rejected
+    client
+        .render_with_replay_url(REPLAY_URL, "rosu-render-mock-test", &skin)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn watch_server_count() {
+    let server = MockServer::start().await.unwrap();
+    server.set_online_count(0);
+
+    let client = server.client().build();
+    let mut watcher = client
+        .watch_server_count(Duration::from_millis(10))
+        .thresholds([1, 3]);
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        let dropped = |threshold| ServerCountEvent::Dropped {
+            threshold,
+            count: 0,
+        };
+
+        assert_eq!(watcher.next().await.unwrap(), dropped(3));
+        assert_eq!(watcher.next().await.unwrap(), dropped(1));
+        assert_eq!(watcher.count(), Some(0));
+
+        server.set_online_count(2);
+
+        let event = watcher.next().await.unwrap();
+        assert_eq!(
+            event,
+            ServerCountEvent::Recovered {
+                threshold: 1,
+                count: 2
+            }
+        );
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn shared_hyper_client() {
+    let server = MockServer::start().await.unwrap();
+    server.set_online_count(3);
+
+    let client = server.client().hyper_client(hyper::Client::new()).build();
+    let count = client.server_online_count().await.unwrap();
+
+    assert_eq!(count.0, 3);
+}
+
+#[cfg(feature = "reqwest")]
+#[tokio::test]
+async fn shared_reqwest_client() {
+    let server = MockServer::start().await.unwrap();
+    server.set_online_count(5);
+
+    let reqwest_client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let client = server.client().reqwest_client(reqwest_client).build();
+    let count = client.server_online_count().await.unwrap();
+
+    assert_eq!(count.0, 5);
+}
+
+#[cfg(feature = "runtime-smol")]
+#[tokio::test]
+async fn smol_runtime() {
+    use rosu_render::websocket::SmolRuntime;
+
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().build();
+    let builder = server.websocket().runtime(SmolRuntime);
+    let (connected_tx, connected_rx) = tokio::sync::oneshot::channel();
+
+    // Receive events on a thread without tokio runtime
+    let receiver = std::thread::spawn(move || {
+        smol::block_on(async move {
+            let mut websocket = builder.connect().await.unwrap();
+            connected_tx.send(()).unwrap();
+
+            loop {
+                if let RawEvent::RenderDone(event) = websocket.next_event().await.unwrap() {
+                    return event.render_id;
+                }
+            }
+        })
+    });
+
+    connected_rx.await.unwrap();
+    let skin = RenderSkinOption::default();
+
+    let render_added = client
+        .render_with_replay_url(REPLAY_URL, "rosu-render-mock-test", &skin)
+        .await
+        .unwrap();
+
+    let join = tokio::task::spawn_blocking(move || receiver.join().unwrap());
+    let render_id = tokio::time::timeout(Duration::from_secs(5), join)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(render_id, render_added.render_id);
+}
@@ -1,13 +1,20 @@
-use std::time::Duration;
-
-use rosu_render::{
-    model::{RenderOptions, RenderSkinOption, Verification},
-    websocket::event::RawEvent,
-    OrdrClient, OrdrWebsocket,
-};
+use rosu_render::OrdrClient;
 
+#[cfg(any(
+    feature = "ws-native",
+    feature = "ws-rustls-native-roots",
+    feature = "ws-rustls-webpki-roots"
+))]
 #[tokio::test]
 async fn render_success() {
+    use std::time::Duration;
+
+    use rosu_render::{
+        model::{RenderOptions, RenderSkinOption, Verification},
+        websocket::event::RawEvent,
+        OrdrWebsocket,
+    };
+
     let replay_file = tokio::fs::read("./assets/2283307549.osr").await.unwrap();
 
     let mut websocket = OrdrWebsocket::connect().await.unwrap();
@@ -0,0 +1,199 @@
+//! Deserializes representative payloads of every endpoint and websocket event.
+//!
+//! Run with the `schema-audit` feature to also fail on fields that are not
+//! modeled by the crate.
+
+use std::{borrow::Cow, fs};
+
+use rosu_render::{
+    client::error::{ApiError, ErrorCode},
+    model::{
+        BackgroundType, CustomSkinProcessUpdate, RawRenderList, RenderAdded, RenderDone,
+        RenderFailed, RenderList, RenderListRef, RenderProgress, RenderServers, Rgb,
+        ServerOnlineCount, SkinDeleted, SkinInfo, SkinList, SkinListRef,
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+fn fixture(path: &str) -> String {
+    fs::read_to_string(format!("./tests/fixtures/{path}.json")).unwrap()
+}
+
+fn parse<T: DeserializeOwned>(path: &str) -> T {
+    let json = fixture(path);
+
+    match serde_json::from_str(&json) {
+        Ok(value) => value,
+        Err(err) => panic!("failed to deserialize {path}: {err}"),
+    }
+}
+
+/// Asserts that `actual` contains every field of `expected`. Catches new
+/// fields of types that cannot deny unknown fields.
+fn assert_known_fields(name: &str, expected: &Value, actual: &Value) {
+    let (Value::Object(expected), Value::Object(actual)) = (expected, actual) else {
+        panic!("{name} is not an object");
+    };
+
+    let unknown: Vec<_> = expected
+        .keys()
+        .filter(|key| !actual.contains_key(*key))
+        .collect();
+
+    assert!(unknown.is_empty(), "{name} has unknown fields {unknown:?}");
+}
+
+fn assert_round_trip<T: DeserializeOwned + Serialize>(path: &str) {
+    let expected: Value = serde_json::from_str(&fixture(path)).unwrap();
+    let actual = serde_json::to_value(parse::<T>(path)).unwrap();
+
+    assert_known_fields(path, &expected, &actual);
+}
+
+#[test]
+fn render_list() {
+    let list: RenderList = parse("http/render_list");
+
+    assert_eq!(list.max_renders, 3);
+    assert_eq!(list.renders.len(), 2);
+    assert_eq!(list.renders[0].id, 812_345);
+    assert_eq!(list.renders[0].renderer, *"ordr server 1");
+
+    let expected: Value = serde_json::from_str(&fixture("http/render_list")).unwrap();
+    let actual = serde_json::to_value(&list.renders[0]).unwrap();
+
+    assert_known_fields("render", &expected["renders"][0], &actual);
+}
+
+#[test]
+fn raw_render_list() {
+    let list: RawRenderList = parse("http/render_list");
+    let ids: Vec<_> = list.renders.iter().map(|render| render.id).collect();
+
+    assert_eq!(ids, [812_345, 812_301]);
+
+    let render = list.renders[1].deserialize().unwrap();
+    assert_eq!(&*render.replay_username, "WhiteCat");
+}
+
+#[test]
+fn render_list_ref() {
+    let json = fixture("http/render_list");
+    let list: RenderListRef<'_> = serde_json::from_str(&json).unwrap();
+    let render = &list.renders[0];
+
+    assert!(matches!(render.title, Cow::Borrowed(_)));
+    // Contains escaped quotes
+    assert!(matches!(render.map_title, Cow::Owned(_)));
+
+    let owned = serde_json::to_value(render.to_owned()).unwrap();
+    let expected =
+        serde_json::to_value(&parse::<RenderList>("http/render_list").renders[0]).unwrap();
+
+    assert_eq!(owned, expected);
+}
+
+#[test]
+fn render_added() {
+    let added: RenderAdded = parse("http/render_added");
+
+    assert_eq!(added.render_id, 812_345);
+}
+
+#[test]
+fn render_servers() {
+    let servers: RenderServers = parse("http/render_servers");
+
+    assert_eq!(servers.servers.len(), 1);
+    assert!(servers.servers[0].motion_blur_capable);
+
+    let customization = &servers.servers[0].customization;
+    assert_eq!(customization.text_color, Some(Rgb::new(255, 255, 255)));
+    assert_eq!(customization.background_type, BackgroundType::Default);
+
+    assert_round_trip::<RenderServers>("http/render_servers");
+}
+
+#[test]
+fn server_online_count() {
+    let count: ServerOnlineCount = parse("http/server_online_count");
+
+    assert_eq!(count.0, 9);
+}
+
+#[test]
+fn skin_list() {
+    let list: SkinList = parse("http/skin_list");
+
+    assert_eq!(list.max_skins, 1);
+    assert_eq!(&*list.skins[0].presentation_name, "WhiteCat 2.1");
+}
+
+#[test]
+fn skin_list_ref() {
+    let json = fixture("http/skin_list");
+    let list: SkinListRef<'_> = serde_json::from_str(&json).unwrap();
+
+    assert!(matches!(list.skins[0].presentation_name, Cow::Borrowed(_)));
+    assert_eq!(
+        list.skins[0].to_owned(),
+        parse::<SkinList>("http/skin_list").skins[0]
+    );
+}
+
+#[test]
+fn skin_custom() {
+    let info: SkinInfo = parse("http/skin_custom");
+
+    assert_eq!(&*info.author, "Garin");
+}
+
+#[test]
+fn skin_deleted() {
+    let deleted: SkinDeleted = parse("http/skin_deleted");
+
+    assert!(deleted.removed);
+}
+
+#[test]
+fn api_error() {
+    let error: ApiError = parse("http/api_error");
+
+    assert_eq!(error.code, Some(ErrorCode::ReplayAlreadyInQueue));
+}
+
+#[test]
+fn render_added_event() {
+    let event: RenderAdded = parse("events/render_added_json");
+
+    assert_eq!(event.render_id, 812_345);
+}
+
+#[test]
+fn render_progress_event() {
+    let event: RenderProgress = parse("events/render_progress_json");
+
+    assert_eq!(event.percentage(), Some(42.0));
+}
+
+#[test]
+fn render_done_event() {
+    let event: RenderDone = parse("events/render_done_json");
+
+    assert_eq!(event.render_id, 812_345);
+}
+
+#[test]
+fn render_failed_event() {
+    let event: RenderFailed = parse("events/render_failed_json");
+
+    assert_eq!(event.error_code, Some(ErrorCode::BeatmapNotFound));
+}
+
+#[test]
+fn custom_skin_process_update_event() {
+    let event: CustomSkinProcessUpdate = parse("events/custom_skin_process_update");
+
+    assert_eq!(event.skin_id, 1234);
+}
@@ -0,0 +1,131 @@
+#![cfg(all(
+    feature = "vcr",
+    any(
+        feature = "ws-native",
+        feature = "ws-rustls-native-roots",
+        feature = "ws-rustls-webpki-roots"
+    )
+))]
+
+use std::pin::pin;
+
+use futures::StreamExt;
+use rosu_render::{
+    mock::{Cassette, MockServer, Recorder},
+    model::RenderSkinOption,
+};
+
+const MAPSET_ID: u32 = 1_172_819;
+
+async fn replay(name: &str) -> MockServer {
+    let cassette = Cassette::load(format!("./tests/cassettes/{name}.json")).unwrap();
+
+    MockServer::replay(cassette).await.unwrap()
+}
+
+#[tokio::test]
+async fn render_list_pages() {
+    let server = replay("render_list").await;
+    let client = server.client().build();
+
+    let mut pages = pin!(client
+        .render_list()
+        .page_size(2)
+        .mapset_id(MAPSET_ID)
+        .pages());
+    let mut render_ids = Vec::new();
+
+    while let Some(page) = pages.next().await {
+        let page = page.unwrap();
+        assert_eq!(page.max_renders, 3);
+        render_ids.extend(page.renders.iter().map(|render| render.id));
+    }
+
+    assert_eq!(render_ids, [812_345, 812_301, 812_250]);
+}
+
+#[tokio::test]
+async fn render_list_pages_map_id() {
+    let server = replay("render_list").await;
+    let client = server.client().build();
+
+    let pages = client
+        .render_list()
+        .page_size(2)
+        .mapset_id(MAPSET_ID)
+        .pages()
+        .map_id(2_449_723);
+
+    let mut pages = pin!(pages);
+    let mut render_ids = Vec::new();
+
+    while let Some(page) = pages.next().await {
+        render_ids.extend(page.unwrap().renders.iter().map(|render| render.id));
+    }
+
+    assert_eq!(render_ids, [812_345, 812_250]);
+}
+
+#[tokio::test]
+async fn raw_render_list() {
+    let server = replay("render_list").await;
+    let client = server.client().build();
+
+    let list = client
+        .render_list()
+        .page_size(2)
+        .page(1)
+        .mapset_id(MAPSET_ID)
+        .raw()
+        .await
+        .unwrap();
+
+    let render = list
+        .renders
+        .iter()
+        .find(|render| render.id == 812_301)
+        .unwrap()
+        .deserialize()
+        .unwrap();
+
+    assert_eq!(render.map_id, 2_449_720);
+}
+
+#[tokio::test]
+async fn record_then_replay() {
+    let upstream = MockServer::start().await.unwrap();
+    upstream.set_online_count(7);
+
+    let recorder = Recorder::start(upstream.base_url()).await.unwrap();
+    let client = recorder.client().disable_ratelimit().build();
+    let skin = RenderSkinOption::default();
+
+    let recorded_added = client
+        .render_with_replay_url("https://example.com/replay.osr", "user", &skin)
+        .await
+        .unwrap();
+    assert_eq!(client.server_online_count().await.unwrap().0, 7);
+
+    let cassette = recorder.cassette();
+    assert_eq!(cassette.interactions.len(), 2);
+
+    let path = std::env::temp_dir().join(format!("rosu-render-vcr-{}.json", std::process::id()));
+    cassette.save(&path).unwrap();
+    let cassette = Cassette::load(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    drop(upstream);
+
+    let server = MockServer::replay(cassette).await.unwrap();
+    let client = server.client().build();
+
+    // The replayed responses stay the same, even when repeated
+    for _ in 0..2 {
+        let added = client
+            .render_with_replay_url("https://example.com/replay.osr", "user", &skin)
+            .await
+            .unwrap();
+        assert_eq!(added.render_id, recorded_added.render_id);
+        assert_eq!(client.server_online_count().await.unwrap().0, 7);
+    }
+}